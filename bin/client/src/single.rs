@@ -3,7 +3,7 @@ use alloc::sync::Arc;
 use alloy_consensus::Sealed;
 use alloy_primitives::B256;
 use core::fmt::Debug;
-use hydro_eigenda::EigenDASource;
+use hydro_eigenda::{derive::BatchDataSink, EigenDASource};
 use hydro_oracle::OracleEigenDaProvider;
 use kona_client::{fpvm_evm::FpvmOpEvmFactory, single::FaultProofProgramError};
 use kona_driver::Driver;
@@ -22,6 +22,20 @@ use tracing::{error, info};
 /// Executes the fault proof program with the given [PreimageOracleClient] and [HintWriterClient].
 #[inline]
 pub async fn run<P, H>(oracle_client: P, hint_client: H) -> Result<(), FaultProofProgramError>
+where
+    P: PreimageOracleClient + Send + Sync + Debug + Clone + 'static,
+    H: HintWriterClient + Send + Sync + Debug + Clone + 'static,
+{
+    run_with_batch_data_sink(oracle_client, hint_client, None).await
+}
+
+/// Executes the fault proof program like [run], handing the batch data of each L1 block loaded
+/// by the EigenDA source to `batch_data_sink` if any, e.g. to dump it when run natively.
+pub async fn run_with_batch_data_sink<P, H>(
+    oracle_client: P,
+    hint_client: H,
+    batch_data_sink: Option<Arc<dyn BatchDataSink>>,
+) -> Result<(), FaultProofProgramError>
 where
     P: PreimageOracleClient + Send + Sync + Debug + Clone + 'static,
     H: HintWriterClient + Send + Sync + Debug + Clone + 'static,
@@ -91,12 +105,15 @@ where
     l2_provider.set_cursor(cursor.clone());
 
     let evm_factory = FpvmOpEvmFactory::new(hint_client, oracle_client);
-    let da_provider = EigenDASource::new(
+    let mut da_provider = EigenDASource::new(
         l1_provider.clone(),
         beacon,
         OracleEigenDaProvider::new(oracle.clone()),
         rollup_config.batch_inbox_address,
     );
+    if let Some(sink) = batch_data_sink {
+        da_provider = da_provider.with_batch_data_sink(sink);
+    }
 
     let pipeline = OraclePipeline::new(
         rollup_config.clone(),
@@ -0,0 +1,45 @@
+//! Contains the [ProxyCapabilities] advertised by an EigenDA Proxy.
+
+use serde::Deserialize;
+
+/// The commitment mode of the commitments posted by the batcher, OP generic commitments such
+/// as `0x010000...`, which the host retrieves blobs with.
+pub const OP_GENERIC_COMMITMENT_MODE: &str = "optimism_generic";
+
+/// The capabilities an EigenDA Proxy advertises on its `/capabilities` endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ProxyCapabilities {
+    /// The commitment modes the proxy serves blobs for, e.g. `optimism_generic`.
+    pub commitment_modes: Vec<String>,
+    /// The maximum size in bytes of a blob served by the proxy.
+    pub max_blob_size: usize,
+    /// The version of the API of the proxy.
+    pub api_version: String,
+}
+
+impl ProxyCapabilities {
+    /// Returns whether the proxy serves blobs for commitments of the given mode.
+    pub fn supports_commitment_mode(&self, mode: &str) -> bool {
+        self.commitment_modes
+            .iter()
+            .any(|supported| supported.eq_ignore_ascii_case(mode))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_capabilities() {
+        let capabilities: ProxyCapabilities = serde_json::from_str(
+            r#"{"commitment_modes":["Optimism_Generic","standard"],"max_blob_size":16777216,
+                "api_version":"v1"}"#,
+        )
+        .unwrap();
+        assert_eq!(capabilities.max_blob_size, 16 * 1024 * 1024);
+        assert_eq!(capabilities.api_version, "v1");
+        assert!(capabilities.supports_commitment_mode(OP_GENERIC_COMMITMENT_MODE));
+        assert!(!capabilities.supports_commitment_mode("optimism_keccak"));
+    }
+}
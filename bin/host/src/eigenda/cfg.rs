@@ -2,7 +2,6 @@
 
 // use celestia_types::nmt::Namespace;
 use clap::Parser;
-use hydro_oracle::hint::HintWrapper;
 use kona_genesis::RollupConfig;
 use kona_host::{
     eth::http_provider,
@@ -11,23 +10,46 @@ use kona_host::{
     OnlineHostBackendCfg, PreimageServer, SharedKeyValueStore, SplitKeyValueStore,
 };
 
+use alloy_primitives::{hex, Bytes, B256};
+use hydro_eigenda::{
+    common::{Keccak256Hasher, DEFAULT_MAX_COMMITMENT_LENGTH, MAX_BLOB_SIZE},
+    derive::BatchDataSink,
+    errors::EigenDAProxyError,
+    retry::RetryPolicy,
+};
+use hydro_proofs::witness::{set_default_srs, SrsConfig};
 use kona_cli::cli_styles;
 use serde::Serialize;
 
 use anyhow::{anyhow, Result};
 use kona_preimage::{
-    BidirectionalChannel, Channel, HintReader, HintWriter, OracleReader, OracleServer,
+    BidirectionalChannel, Channel, HintReader, HintRouter, HintWriter, OracleReader, OracleServer,
+    PreimageFetcher,
 };
 use kona_providers_alloy::{OnlineBeaconClient, OnlineBlobProvider};
 use kona_std_fpvm::{FileChannel, FileDescriptor};
 use op_alloy_network::Optimism;
-use std::{sync::Arc, time::Duration};
+use reqwest::Url;
+use std::{
+    iter,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 use tokio::{
     sync::RwLock,
     task::{self, JoinHandle},
 };
+use tracing::{error, info, warn};
 
-use super::{EigenDAChainHintHandler, EigenDAChainProviders, EigenDAProxy, OnlineEigenDAProvider};
+use super::{
+    CachingHostBackend, CaptureFilter, DaWorkerPool, EigenDAChainHintHandler,
+    EigenDAChainProviders, EigenDAProxy, FileBatchDataSink, FixtureEigenDAProvider,
+    FixtureHostBackend, HostHint, LongPoll, OnlineEigenDAProvider, ProxyAuthToken,
+    ProxyCapabilities, ProxyVersion, ProxyVersionCheck, RequestCapture, DEFAULT_MAX_RESPONSE_SIZE,
+    OP_GENERIC_COMMITMENT_MODE,
+};
 
 /// The host binary CLI application arguments.
 #[derive(Default, Parser, Serialize, Clone, Debug)]
@@ -43,9 +65,19 @@ pub struct EigenDAChainHost {
 #[derive(Default, Parser, Serialize, Clone, Debug)]
 #[command(styles = cli_styles())]
 pub struct EigenDACfg {
-    /// The url of EigenDA Proxy service
-    #[arg(long, alias = "proxy-url", env)]
-    pub proxy_url: Option<String>,
+    /// The urls of EigenDA Proxy services, comma separated or repeated. Blobs are retrieved
+    /// from the proxies in order, starting with the one which served the last blob.
+    #[arg(long, alias = "proxy-url", value_parser = parse_proxy_url, value_delimiter = ',', env)]
+    pub proxy_url: Vec<String>,
+    /// Allow a plain `http://` EigenDA Proxy url. Retrievals are then neither encrypted nor
+    /// authenticated, only use this for local or test setups.
+    #[arg(long, alias = "allow-insecure-da-http", default_value_t = false, env)]
+    pub allow_insecure_da_http: bool,
+    /// Query the version of the EigenDA Proxies at startup, along with the capabilities they
+    /// advertise, `warn` logging and `strict` rejecting a proxy outside of the known compatible
+    /// versions, not reporting one, or whose capabilities do not fit the configuration.
+    #[arg(long, alias = "proxy-version-check", value_enum, default_value_t, env)]
+    pub proxy_version_check: ProxyVersionCheck,
     /// The total amount of time that the batcher will spend waiting for EigenDA to retrieve a blob
     #[arg(long,
          alias = "retrieve-timeout",
@@ -54,6 +86,272 @@ pub struct EigenDACfg {
          env
      )]
     pub retrieve_timeout: Duration,
+    /// The number of seconds to wait for the EigenDA Proxy to disperse a blob, which takes
+    /// longer than retrieving it. Only used by tooling and tests dispersing blobs.
+    #[arg(long,
+         alias = "disperse-timeout",
+         default_value = "300",
+         value_parser = parse_duration,
+         env
+     )]
+    pub disperse_timeout: Duration,
+    /// Read every blob dispersed through the EigenDA Proxy back and check that it matches the
+    /// dispersed data. Only used by tooling and tests dispersing blobs.
+    #[arg(long, alias = "verify-dispersal", default_value_t = false, env)]
+    pub verify_dispersal: bool,
+    /// The maximum size in bytes of a blob returned by the EigenDA Proxy
+    #[arg(long,
+         alias = "max-response-size",
+         default_value_t = DEFAULT_MAX_RESPONSE_SIZE,
+         env
+     )]
+    pub max_response_size: usize,
+    /// The maximum size in bytes of a commitment, longer commitments are rejected before
+    /// being sent to the EigenDA Proxy
+    #[arg(long,
+         alias = "max-commitment-length",
+         default_value_t = DEFAULT_MAX_COMMITMENT_LENGTH,
+         env
+     )]
+    pub max_commitment_length: usize,
+    /// The SHA-256 hash of the DER encoded SubjectPublicKeyInfo of the TLS certificate of the
+    /// EigenDA Proxy. Connections to a proxy presenting another public key are rejected.
+    #[arg(long, alias = "proxy-pinned-cert", env)]
+    pub proxy_pinned_cert: Option<B256>,
+    /// Bearer token sent in the `Authorization` header of every EigenDA Proxy request, for a
+    /// proxy behind an auth gateway. It is never logged nor serialized.
+    #[arg(long, alias = "proxy-auth-token", env, hide_env_values = true)]
+    #[serde(skip_serializing)]
+    pub proxy_auth_token: Option<ProxyAuthToken>,
+    /// Poll blobs which are not available on the EigenDA Proxy yet for up to this many
+    /// seconds, instead of failing on the first `404`. Useful near the chain tip.
+    #[arg(long, alias = "long-poll-timeout", value_parser = parse_duration, env)]
+    pub long_poll_timeout: Option<Duration>,
+    /// The maximum number of seconds spent processing an EigenDA hint, covering the blob
+    /// retrieval and the reconstruction of its preimages. Unbounded when unset.
+    #[arg(long, alias = "hint-timeout", value_parser = parse_duration, env)]
+    pub hint_timeout: Option<Duration>,
+    /// The number of times an EigenDA Proxy request failing with a transient error, e.g. a
+    /// connection error or a `5xx` gateway status, is retried with exponential backoff. All
+    /// attempts share the retrieve timeout.
+    #[arg(long, alias = "proxy-retries", default_value_t = 0, env)]
+    pub proxy_retries: u32,
+    /// The delay in milliseconds before the first retry of an EigenDA Proxy request, doubled
+    /// on every further retry and jittered by up to half of it.
+    #[arg(long, alias = "proxy-retry-delay-ms", default_value_t = 100, env)]
+    pub proxy_retry_delay_ms: u64,
+    /// The number of retrieved blobs to keep in memory, `0` disabling the cache. Blobs are
+    /// cached by the KZG commitment of their cert, so that a blob addressed by several
+    /// commitment encodings is only retrieved once.
+    #[arg(long, alias = "blob-cache-size", default_value_t = 0, env)]
+    pub blob_cache_size: usize,
+    /// File of EigenDA commitments whose blobs are retrieved into the blob cache at startup,
+    /// one 0x-prefixed hex commitment per line. Lines starting with `#` are ignored. Requires
+    /// a non-zero `--blob-cache-size`.
+    #[arg(long, alias = "warm-blob-cache", env)]
+    pub warm_blob_cache: Option<PathBuf>,
+    /// Trust the commitment of the certs returned by the EigenDA Proxy instead of recomputing
+    /// it from the blob. This skips the KZG commitment check and the KZG proof generation.
+    ///
+    /// UNSAFE: only enable for a trusted proxy, a malicious proxy can then serve arbitrary
+    /// blob data. The KZG opening of the blob is not served either, so clients verifying
+    /// blobs against their cert, the default, fail to reconstruct them.
+    #[arg(long, alias = "trust-proxy-commitment", default_value_t = false, env)]
+    pub trust_proxy_commitment: bool,
+    /// Check the blobs returned by the EigenDA Proxy against the cert of the requested
+    /// commitment as they are retrieved, before they are cached. Requires the KZG trusted setup.
+    #[arg(long, alias = "check-proxy-commitment", default_value_t = false, env)]
+    pub check_proxy_commitment: bool,
+    /// Ignore hints of unknown types instead of failing, to serve clients newer than the host
+    /// during rolling upgrades.
+    #[arg(long, alias = "lenient-hints", default_value_t = false, env)]
+    pub lenient_hints: bool,
+    /// Directory of a secondary key-value store receiving a copy of the EigenDA preimages, to
+    /// keep a standby host warm. Mirroring failures are logged and do not fail the hint.
+    #[arg(long, alias = "mirror-data-dir", env)]
+    pub mirror_data_dir: Option<PathBuf>,
+    /// Directory of recorded EigenDA blobs served instead of the EigenDA Proxy, each in a file
+    /// named after the hex keccak256 hash of its commitment. Requires an offline host, whose
+    /// other preimages are all in its data directory, to replay a recorded run.
+    #[arg(long, alias = "blob-fixture-dir", env)]
+    pub blob_fixture_dir: Option<PathBuf>,
+    /// File receiving a redacted summary of EigenDA Proxy retrievals, one JSON object per
+    /// line, to attach to support tickets.
+    #[arg(long, alias = "capture-da-request", env)]
+    pub capture_da_request: Option<PathBuf>,
+    /// The number of next retrievals captured with `--capture-da-request`.
+    #[arg(long, alias = "capture-da-request-count", default_value_t = 1, env)]
+    pub capture_da_request_count: usize,
+    /// Capture every retrieval of this commitment with `--capture-da-request`, instead of the
+    /// next retrievals.
+    #[arg(long, alias = "capture-da-commitment", env)]
+    pub capture_da_commitment: Option<Bytes>,
+    /// The number of threads of a dedicated pool reconstructing EigenDA blobs, bounding how
+    /// many are processed concurrently. Defaults to the blocking pool of the host runtime.
+    #[arg(long, alias = "da-worker-threads", env)]
+    pub da_worker_threads: Option<usize>,
+    /// Write the preimages of an EigenDA blob while the next ones are computed, buffering at
+    /// most this many in between. Bounds the memory of large blobs, which are otherwise fully
+    /// reconstructed before being written.
+    #[arg(long, alias = "preimage-channel-capacity", env)]
+    pub preimage_channel_capacity: Option<NonZeroUsize>,
+    /// The number of EigenDA preimages read by the client kept in memory in native mode,
+    /// serving repeated reads of the same field elements without reaching the key-value store.
+    /// No preimage is cached when unset.
+    #[arg(long, alias = "preimage-cache-size", env)]
+    pub preimage_cache_size: Option<NonZeroUsize>,
+    /// File of the G1 points of the KZG trusted setup the witnesses of EigenDA blobs are
+    /// computed with, e.g. of another EigenDA deployment. It must cover the largest blob.
+    /// Defaults to `resources/g1.point`, loaded on first use.
+    #[arg(long, alias = "kzg-srs-path", env)]
+    pub kzg_srs_path: Option<PathBuf>,
+    /// Directory receiving the decoded batch data of each block loaded by the EigenDA source,
+    /// one file per block, to inspect exactly what derivation consumes. Only the client run in
+    /// native mode dumps its batch data. Off by default.
+    #[arg(long, alias = "dump-batch-data", env)]
+    pub dump_batch_data: Option<PathBuf>,
+    /// Address serving the metrics of the EigenDA backend for Prometheus to scrape, e.g.
+    /// `0.0.0.0:9090`. Metrics are not exported when unset.
+    #[cfg(feature = "metrics")]
+    #[arg(long, alias = "metrics-addr", env)]
+    pub metrics_addr: Option<std::net::SocketAddr>,
+}
+
+impl EigenDACfg {
+    /// The retry policy of the EigenDA Proxy requests.
+    fn retry_policy(&self) -> RetryPolicy {
+        let base_delay = Duration::from_millis(self.proxy_retry_delay_ms);
+        RetryPolicy {
+            initial_backoff: base_delay,
+            jitter: base_delay / 2,
+            ..RetryPolicy::new(self.proxy_retries.saturating_add(1))
+        }
+    }
+
+    /// Creates the [OnlineEigenDAProvider] retrieving blobs from the EigenDA Proxies, falling
+    /// back to the later ones in order.
+    pub fn create_provider(&self) -> Result<OnlineEigenDAProvider, SingleChainHostError> {
+        let (proxy_url, fallback_proxy_urls) = self
+            .proxy_url
+            .split_first()
+            .ok_or(SingleChainHostError::Other("EigenDA Proxy URL must be set"))?;
+        let mut provider = OnlineEigenDAProvider::new(self.create_proxy_client(proxy_url)?)
+            .with_max_commitment_length(self.max_commitment_length);
+        for proxy_url in fallback_proxy_urls {
+            provider = provider.with_fallback_proxy(self.create_proxy_client(proxy_url)?);
+        }
+        if self.check_proxy_commitment {
+            provider = provider.with_commitment_check();
+        }
+        Ok(match NonZeroUsize::new(self.blob_cache_size) {
+            Some(capacity) => provider.with_blob_cache(capacity),
+            None => provider,
+        })
+    }
+
+    /// Checks every EigenDA Proxy of the provider as configured with `--proxy-version-check`,
+    /// see [EigenDACfg::check_proxy].
+    pub async fn check_proxies(
+        &self,
+        provider: &OnlineEigenDAProvider,
+    ) -> Result<(), SingleChainHostError> {
+        if self.proxy_version_check == ProxyVersionCheck::Off {
+            return Ok(());
+        }
+        let clients =
+            iter::once(&provider.eigen_da_proxy_client).chain(&provider.fallback_proxy_clients);
+        for client in clients {
+            match self.check_proxy(client).await {
+                Ok(version) => info!(
+                    target: "eigenda-host",
+                    "EigenDA Proxy {} runs version {version}",
+                    client.proxy_url
+                ),
+                Err(e) if self.proxy_version_check == ProxyVersionCheck::Strict => {
+                    error!(
+                        target: "eigenda-host",
+                        "Check of EigenDA Proxy {} failed: {e}",
+                        client.proxy_url
+                    );
+                    return Err(SingleChainHostError::Other("EigenDA Proxy check failed"));
+                }
+                Err(e) => warn!(
+                    target: "eigenda-host",
+                    "Check of EigenDA Proxy {} failed: {e}",
+                    client.proxy_url
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the version of the EigenDA Proxy and, if it advertises them, its capabilities
+    /// against the configuration, returning the version.
+    pub async fn check_proxy(&self, client: &EigenDAProxy) -> Result<ProxyVersion, String> {
+        let version = client.check_version().await.map_err(|e| e.to_string())?;
+        match client.capabilities().await {
+            Ok(capabilities) => self.check_capabilities(&capabilities)?,
+            // older proxies do not advertise their capabilities
+            Err(EigenDAProxyError::NotFound) => {}
+            Err(e) => return Err(e.to_string()),
+        }
+        Ok(version)
+    }
+
+    /// Checks the configuration against the capabilities of an EigenDA Proxy, which must
+    /// serve OP generic commitments and blobs fitting in `--max-response-size`.
+    pub fn check_capabilities(&self, capabilities: &ProxyCapabilities) -> Result<(), String> {
+        if !capabilities.supports_commitment_mode(OP_GENERIC_COMMITMENT_MODE) {
+            return Err(format!(
+                "the proxy does not serve {OP_GENERIC_COMMITMENT_MODE} commitments, only {}",
+                capabilities.commitment_modes.join(", ")
+            ));
+        }
+        if self.max_response_size < capabilities.max_blob_size {
+            return Err(format!(
+                "--max-response-size {} is below the maximum blob size {} of the proxy",
+                self.max_response_size, capabilities.max_blob_size
+            ));
+        }
+        Ok(())
+    }
+
+    /// Creates the [FileBatchDataSink] dumping the batch data of the loaded blocks, if enabled
+    /// with `--dump-batch-data`.
+    pub fn create_batch_data_sink(&self) -> Option<FileBatchDataSink> {
+        self.dump_batch_data.clone().map(FileBatchDataSink::new)
+    }
+
+    /// Creates the [EigenDAProxy] client of the EigenDA Proxy at the given url.
+    fn create_proxy_client(&self, proxy_url: &str) -> Result<EigenDAProxy, SingleChainHostError> {
+        check_proxy_url_scheme(proxy_url, self.allow_insecure_da_http)?;
+        let mut eigen_da_proxy_client =
+            EigenDAProxy::new(proxy_url.to_string(), self.retrieve_timeout)
+                .with_disperse_timeout(self.disperse_timeout)
+                .with_max_response_size(self.max_response_size)
+                .with_retry(self.retry_policy());
+        if self.verify_dispersal {
+            eigen_da_proxy_client = eigen_da_proxy_client.with_dispersal_verification();
+        }
+        if let Some(spki_sha256) = self.proxy_pinned_cert {
+            eigen_da_proxy_client = eigen_da_proxy_client.with_pinned_cert(spki_sha256)?;
+        }
+        if let Some(ref token) = self.proxy_auth_token {
+            eigen_da_proxy_client = eigen_da_proxy_client.with_auth_token(token)?;
+        }
+        if let Some(deadline) = self.long_poll_timeout {
+            eigen_da_proxy_client = eigen_da_proxy_client.with_long_poll(LongPoll::new(deadline));
+        }
+        if let Some(ref path) = self.capture_da_request {
+            let filter = match self.capture_da_commitment {
+                Some(ref commitment) => CaptureFilter::Commitment(commitment.to_vec()),
+                None => CaptureFilter::Next(self.capture_da_request_count),
+            };
+            eigen_da_proxy_client =
+                eigen_da_proxy_client.with_capture(RequestCapture::new(path.clone(), filter));
+        }
+        Ok(eigen_da_proxy_client)
+    }
 }
 
 fn parse_duration(input: &str) -> Result<Duration, String> {
@@ -63,10 +361,148 @@ fn parse_duration(input: &str) -> Result<Duration, String> {
         .map_err(|e| format!("Failed to parse duration: {}", e))
 }
 
+/// The maximum length of the EigenDA Proxy url.
+const MAX_PROXY_URL_LENGTH: usize = 2048;
+
+/// Validates the EigenDA Proxy url, which must be an absolute http(s) url, and strips trailing
+/// slashes so that request paths can be appended to it.
+fn parse_proxy_url(input: &str) -> Result<String, String> {
+    if input.len() > MAX_PROXY_URL_LENGTH {
+        return Err(format!(
+            "Proxy url exceeds the maximum length of {MAX_PROXY_URL_LENGTH} bytes"
+        ));
+    }
+    let url = Url::parse(input).map_err(|e| format!("Failed to parse proxy url: {e}"))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!(
+            "Unsupported proxy url scheme {}, expected http or https",
+            url.scheme()
+        ));
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err("Proxy url has no host".to_string());
+    }
+    Ok(url.as_str().trim_end_matches('/').to_string())
+}
+
+/// Parses a list of commitments, one 0x-prefixed hex commitment per line, skipping empty lines
+/// and lines starting with `#`.
+fn read_commitments(text: &str) -> Result<Vec<Vec<u8>>, std::io::Error> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            hex::decode(line).map_err(|e| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("Invalid commitment {line}: {e}"),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Rejects a plain http proxy url unless insecure http is allowed, warning when it is used.
+fn check_proxy_url_scheme(
+    proxy_url: &str,
+    allow_insecure_http: bool,
+) -> Result<(), SingleChainHostError> {
+    if !proxy_url.starts_with("http://") {
+        return Ok(());
+    }
+    if !allow_insecure_http {
+        return Err(SingleChainHostError::Other(
+            "EigenDA Proxy URL uses plain http, use https or pass --allow-insecure-da-http",
+        ));
+    }
+    warn!(
+        target: "eigenda-host",
+        "EigenDA Proxy URL {proxy_url} uses plain http, retrievals are neither encrypted nor \
+         authenticated"
+    );
+    Ok(())
+}
+
+/// Loads the KZG trusted setup of the file at `path` and installs it as the one of the
+/// witnesses, failing unless it covers the largest EigenDA blob.
+fn install_kzg_srs(path: &Path) -> Result<(), SingleChainHostError> {
+    let srs = SrsConfig::from_path(path)
+        .load()
+        .and_then(|srs| srs.check_blob_size(MAX_BLOB_SIZE).map(|_| srs));
+    let srs = match srs {
+        Ok(srs) => srs,
+        Err(e) => {
+            error!(
+                target: "eigenda-host",
+                "Failed to load the KZG trusted setup {}: {e}",
+                path.display()
+            );
+            return Err(SingleChainHostError::Other("Invalid KZG trusted setup"));
+        }
+    };
+    info!(
+        target: "eigenda-host",
+        "Loaded {} G1 points of the KZG trusted setup {}",
+        srs.num_points(),
+        path.display()
+    );
+    set_default_srs(srs)
+        .map_err(|_| SingleChainHostError::Other("KZG trusted setup already loaded"))
+}
+
+/// Spawns the preimage server of the backend, caching up to `preimage_cache` of the EigenDA
+/// preimages it serves with a [CachingHostBackend].
+fn spawn_server<C, B>(
+    hint: C,
+    preimage: C,
+    backend: B,
+    preimage_cache: Option<NonZeroUsize>,
+) -> JoinHandle<Result<(), SingleChainHostError>>
+where
+    C: Channel + Send + Sync + 'static,
+    B: HintRouter + PreimageFetcher + Send + Sync + 'static,
+{
+    match preimage_cache {
+        Some(capacity) => {
+            let backend = CachingHostBackend::new(backend, capacity);
+            task::spawn(serve(hint, preimage, backend))
+        }
+        None => task::spawn(serve(hint, preimage, backend)),
+    }
+}
+
+/// Serves the preimages of the backend until the client is done.
+async fn serve<C, B>(hint: C, preimage: C, backend: B) -> Result<(), SingleChainHostError>
+where
+    C: Channel + Send + Sync + 'static,
+    B: HintRouter + PreimageFetcher + Send + Sync + 'static,
+{
+    PreimageServer::new(
+        OracleServer::new(preimage),
+        HintReader::new(hint),
+        Arc::new(backend),
+    )
+    .start()
+    .await
+    .map_err(SingleChainHostError::from)
+}
+
 impl EigenDAChainHost {
     /// Starts the [SingleChainHost] application.
     pub async fn start(self) -> Result<(), SingleChainHostError> {
+        #[cfg(feature = "metrics")]
+        if let Some(addr) = self.eigen_da_args.metrics_addr {
+            super::metrics::install_exporter(addr)?;
+            info!(target: "eigenda-host", "Serving metrics at {addr}");
+        }
+        if let Some(ref path) = self.eigen_da_args.kzg_srs_path {
+            install_kzg_srs(path)?;
+        }
+
         if self.single_host.server {
+            if self.eigen_da_args.dump_batch_data.is_some() {
+                warn!(target: "eigenda-host", "Batch data is only dumped in native mode");
+            }
             let hint = FileChannel::new(FileDescriptor::HintRead, FileDescriptor::HintWrite);
             let preimage =
                 FileChannel::new(FileDescriptor::PreimageRead, FileDescriptor::PreimageWrite);
@@ -83,56 +519,72 @@ impl EigenDAChainHost {
         hint: C,
         preimage: C,
     ) -> Result<JoinHandle<Result<(), SingleChainHostError>>, SingleChainHostError>
+    where
+        C: Channel + Send + Sync + 'static,
+    {
+        self.start_server_with_cache(hint, preimage, None).await
+    }
+
+    /// Starts the preimage server like [EigenDAChainHost::start_server], keeping up to
+    /// `preimage_cache` of the EigenDA preimages read by the client in memory.
+    async fn start_server_with_cache<C>(
+        &self,
+        hint: C,
+        preimage: C,
+        preimage_cache: Option<NonZeroUsize>,
+    ) -> Result<JoinHandle<Result<(), SingleChainHostError>>, SingleChainHostError>
     where
         C: Channel + Send + Sync + 'static,
     {
         let kv_store = self.create_key_value_store()?;
 
-        let task_handle = if self.is_offline() {
-            task::spawn(async {
-                PreimageServer::new(
-                    OracleServer::new(preimage),
-                    HintReader::new(hint),
-                    Arc::new(OfflineHostBackend::new(kv_store)),
-                )
-                .start()
-                .await
-                .map_err(SingleChainHostError::from)
-            })
+        let task_handle = if let Some(ref dir) = self.eigen_da_args.blob_fixture_dir {
+            if !self.is_offline() {
+                return Err(SingleChainHostError::Other(
+                    "EigenDA blob fixtures require an offline host",
+                ));
+            }
+            let backend = FixtureHostBackend::<Keccak256Hasher>::new(
+                kv_store,
+                FixtureEigenDAProvider::new(dir.clone()),
+                self.eigen_da_args.trust_proxy_commitment,
+            );
+            spawn_server(hint, preimage, backend, preimage_cache)
+        } else if self.is_offline() {
+            let backend = OfflineHostBackend::new(kv_store);
+            spawn_server(hint, preimage, backend, preimage_cache)
         } else {
             let providers = self.create_providers().await?;
             let backend = OnlineHostBackend::new(
                 self.clone(),
                 kv_store.clone(),
                 providers,
-                EigenDAChainHintHandler,
+                EigenDAChainHintHandler::new(),
             );
-
-            task::spawn(async {
-                PreimageServer::new(
-                    OracleServer::new(preimage),
-                    HintReader::new(hint),
-                    Arc::new(backend),
-                )
-                .start()
-                .await
-                .map_err(SingleChainHostError::from)
-            })
+            spawn_server(hint, preimage, backend, preimage_cache)
         };
 
         Ok(task_handle)
     }
 
-    /// Starts the host in native mode, running both the client and preimage server in the same
-    /// process.
+    /// Starts the host in native mode, running both the Hydro client and preimage server in the
+    /// same process. The client is the one the FPVM program runs.
     async fn start_native(&self) -> Result<(), SingleChainHostError> {
         let hint = BidirectionalChannel::new()?;
         let preimage = BidirectionalChannel::new()?;
 
-        let server_task = self.start_server(hint.host, preimage.host).await?;
-        let client_task = task::spawn(kona_client::single::run(
+        let preimage_cache = self.eigen_da_args.preimage_cache_size;
+        let server_task = self
+            .start_server_with_cache(hint.host, preimage.host, preimage_cache)
+            .await?;
+        let batch_data_sink = self
+            .eigen_da_args
+            .create_batch_data_sink()
+            .map(|sink| Arc::new(sink) as Arc<dyn BatchDataSink>);
+        let client_task = task::spawn(hydro_client::single::run_with_batch_data_sink(
             OracleReader::new(preimage.client),
             HintWriter::new(hint.client),
+            batch_data_sink,
         ));
 
         let (_, client_result) = tokio::try_join!(server_task, client_task)?;
@@ -142,6 +594,9 @@ impl EigenDAChainHost {
     }
 
     /// Returns `true` if the host is running in offline mode.
+    ///
+    /// No EigenDA Proxy is needed then, the EigenDA preimages being served from the data
+    /// directory, or written from the `--blob-fixture-dir` blobs.
     pub const fn is_offline(&self) -> bool {
         self.single_host.l1_node_address.is_none()
             && self.single_host.l2_node_address.is_none()
@@ -209,28 +664,245 @@ impl EigenDAChainHost {
                 .ok_or(SingleChainHostError::Other("L2 node address must be set"))?,
         );
 
-        let eigen_da_proxy_client = EigenDAProxy::new(
-            self.eigen_da_args
-                .proxy_url
-                .as_ref()
-                .ok_or(SingleChainHostError::Other("EigenDA Proxy URL must be set"))?
-                .to_string(),
-            self.eigen_da_args.retrieve_timeout,
-        );
-        let eigen_da_provider = OnlineEigenDAProvider::new(eigen_da_proxy_client);
+        let eigen_da_provider = self.eigen_da_args.create_provider()?;
+        self.eigen_da_args.check_proxies(&eigen_da_provider).await?;
+        if let Some(ref path) = self.eigen_da_args.warm_blob_cache {
+            let commitments = read_commitments(&std::fs::read_to_string(path)?)?;
+            let results = eigen_da_provider.warm_cache(&commitments).await;
+            info!(
+                target: "eigenda-host",
+                "Warmed the blob cache with {} of {} blobs",
+                results.iter().filter(|result| result.is_ok()).count(),
+                results.len()
+            );
+        }
 
-        Ok(EigenDAChainProviders {
-            inner_providers: SingleChainProviders {
+        let providers = EigenDAChainProviders::new(
+            SingleChainProviders {
                 l1: l1_provider,
                 blobs: blob_provider,
                 l2: l2_provider,
             },
-            eigen_da: eigen_da_provider,
+            eigen_da_provider,
+        );
+
+        let providers = match self.eigen_da_args.da_worker_threads {
+            Some(threads) => providers.with_da_pool(DaWorkerPool::new(threads)?),
+            None => providers,
+        };
+
+        Ok(match self.eigen_da_args.mirror_data_dir {
+            Some(ref mirror_data_dir) => providers.with_mirror_store(Arc::new(RwLock::new(
+                DiskKeyValueStore::new(mirror_data_dir.clone()),
+            ))),
+            None => providers,
         })
     }
 }
 
 impl OnlineHostBackendCfg for EigenDAChainHost {
-    type HintType = HintWrapper;
+    type HintType = HostHint;
     type Providers = EigenDAChainProviders;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eigenda::test_utils::{MockProxy, MockResponse};
+
+    #[test]
+    fn test_parse_proxy_url() {
+        assert_eq!(
+            parse_proxy_url("http://localhost:3100"),
+            Ok("http://localhost:3100".into())
+        );
+        assert_eq!(
+            parse_proxy_url("https://proxy.example.com/eigenda//"),
+            Ok("https://proxy.example.com/eigenda".into())
+        );
+        // the root path is normalized to no path at all
+        assert_eq!(
+            parse_proxy_url("http://127.0.0.1:3100/"),
+            Ok("http://127.0.0.1:3100".into())
+        );
+    }
+
+    #[test]
+    fn test_parse_proxy_url_rejects_invalid() {
+        // missing scheme
+        assert!(parse_proxy_url("localhost:3100").is_err());
+        assert!(parse_proxy_url("proxy.example.com").is_err());
+        // non http(s) schemes
+        assert!(parse_proxy_url("file:///tmp/proxy").is_err());
+        assert!(parse_proxy_url("ftp://proxy.example.com").is_err());
+        // overlong urls
+        let long = format!(
+            "http://proxy.example.com/{}",
+            "a".repeat(MAX_PROXY_URL_LENGTH)
+        );
+        assert!(parse_proxy_url(&long).is_err());
+    }
+
+    #[test]
+    fn test_cli_validates_proxy_url() {
+        let cfg = EigenDACfg::try_parse_from(["hydro-host", "--proxy-url", "http://proxy:3100/"])
+            .unwrap();
+        assert_eq!(cfg.proxy_url, vec!["http://proxy:3100".to_string()]);
+
+        assert!(
+            EigenDACfg::try_parse_from(["hydro-host", "--proxy-url", "file:///proxy"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_read_commitments() {
+        let commitments = read_commitments("# recent blocks\n0x010000aa\n\n  0x010000bb  \n");
+        assert_eq!(
+            commitments.unwrap(),
+            vec![vec![0x01, 0x00, 0x00, 0xaa], vec![0x01, 0x00, 0x00, 0xbb]]
+        );
+        assert!(read_commitments("0x01zz").is_err());
+    }
+
+    #[test]
+    fn test_insecure_http_proxy_url() {
+        let provider = |args: &[&str]| {
+            EigenDACfg::try_parse_from([&["hydro-host"], args].concat())
+                .unwrap()
+                .create_provider()
+        };
+
+        let err = provider(&["--proxy-url", "http://proxy:3100"]).unwrap_err();
+        assert!(
+            err.to_string().contains("--allow-insecure-da-http"),
+            "{err}"
+        );
+        let allowed = provider(&[
+            "--proxy-url",
+            "http://proxy:3100",
+            "--allow-insecure-da-http",
+        ]);
+        assert!(allowed.is_ok());
+        assert!(provider(&["--proxy-url", "https://proxy:3100"]).is_ok());
+        // every proxy url is checked
+        let err = provider(&["--proxy-url", "https://proxy:3100,http://backup:3100"]).unwrap_err();
+        assert!(
+            err.to_string().contains("--allow-insecure-da-http"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_multiple_proxy_urls() {
+        let expected = vec![
+            "https://proxy:3100".to_string(),
+            "https://backup:3100".to_string(),
+        ];
+        let cfg = EigenDACfg::try_parse_from([
+            "hydro-host",
+            "--proxy-url",
+            "https://proxy:3100/,https://backup:3100",
+        ])
+        .unwrap();
+        assert_eq!(cfg.proxy_url, expected);
+        let cfg = EigenDACfg::try_parse_from([
+            "hydro-host",
+            "--proxy-url",
+            "https://proxy:3100",
+            "--proxy-url",
+            "https://backup:3100/",
+        ])
+        .unwrap();
+        assert_eq!(cfg.proxy_url, expected);
+
+        let provider = cfg.create_provider().unwrap();
+        assert_eq!(provider.eigen_da_proxy_client.proxy_url, expected[0]);
+        assert_eq!(provider.fallback_proxy_clients.len(), 1);
+        assert_eq!(provider.fallback_proxy_clients[0].proxy_url, expected[1]);
+    }
+
+    #[test]
+    fn test_proxy_auth_token_not_leaked() {
+        let cfg = EigenDACfg::try_parse_from([
+            "hydro-host",
+            "--proxy-url",
+            "https://proxy:3100",
+            "--proxy-auth-token",
+            "secret",
+        ])
+        .unwrap();
+        assert_eq!(
+            cfg.proxy_auth_token,
+            Some(ProxyAuthToken::new("secret".to_string()))
+        );
+        assert!(!format!("{cfg:?}").contains("secret"));
+        assert!(!serde_json::to_string(&cfg).unwrap().contains("secret"));
+        assert!(cfg.create_provider().is_ok());
+    }
+
+    /// A proxy of the given version, advertising the given capabilities if any.
+    fn versioned_proxy(version: &'static str, capabilities: Option<&'static str>) -> MockProxy {
+        MockProxy::start(move |req| match (req.path.as_str(), capabilities) {
+            ("/version", _) => MockResponse::ok(format!(r#"{{"version":"{version}"}}"#)),
+            ("/capabilities", Some(capabilities)) => MockResponse::ok(capabilities),
+            _ => MockResponse::new(404, Vec::new()),
+        })
+    }
+
+    #[test]
+    fn test_check_capabilities() {
+        let cfg =
+            EigenDACfg::try_parse_from(["hydro-host", "--max-response-size", "1024"]).unwrap();
+        let mut capabilities: ProxyCapabilities = serde_json::from_str(
+            r#"{"commitment_modes":["optimism_generic"],"max_blob_size":1024,"api_version":"v1"}"#,
+        )
+        .unwrap();
+        assert_eq!(cfg.check_capabilities(&capabilities), Ok(()));
+
+        capabilities.max_blob_size = 2048;
+        let err = cfg.check_capabilities(&capabilities).unwrap_err();
+        assert!(err.contains("--max-response-size"), "{err}");
+
+        capabilities.commitment_modes = vec!["standard".to_string()];
+        let err = cfg.check_capabilities(&capabilities).unwrap_err();
+        assert!(err.contains("only standard"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_check_proxies() {
+        let compatible = versioned_proxy("v1.6.2", None);
+        let incompatible = versioned_proxy("v2.0.0", None);
+        let misconfigured = versioned_proxy(
+            "v1.6.2",
+            Some(r#"{"commitment_modes":["standard"],"max_blob_size":1024,"api_version":"v1"}"#),
+        );
+        let check = |check: &str, proxies: &[&MockProxy]| {
+            let urls = proxies
+                .iter()
+                .map(|proxy| proxy.url())
+                .collect::<Vec<_>>()
+                .join(",");
+            let cfg = EigenDACfg::try_parse_from([
+                "hydro-host",
+                "--proxy-url",
+                &urls,
+                "--allow-insecure-da-http",
+                "--proxy-version-check",
+                check,
+            ])
+            .unwrap();
+            async move { cfg.check_proxies(&cfg.create_provider().unwrap()).await }
+        };
+
+        assert!(check("strict", &[&compatible]).await.is_ok());
+        assert!(check("strict", &[&misconfigured]).await.is_err());
+        // every proxy is checked
+        assert!(check("strict", &[&compatible, &incompatible])
+            .await
+            .is_err());
+        assert!(check("warn", &[&compatible, &incompatible]).await.is_ok());
+        assert!(check("off", &[&incompatible]).await.is_ok());
+        // the capabilities of an incompatible proxy are not queried
+        assert_eq!(incompatible.requests().len(), 2);
+    }
+}
@@ -0,0 +1,83 @@
+//! Dumps the decoded batch data of blocks to files for inspection.
+
+use hydro_eigenda::derive::{BatchDataDump, BatchDataSink};
+use kona_protocol::BlockInfo;
+use std::path::PathBuf;
+use tracing::warn;
+
+/// A [BatchDataSink] writing the batch data of each block to its own file in a directory, in
+/// the text format of [BatchDataDump], so operators can inspect exactly what derivation
+/// consumes.
+///
+/// The file of a block is named after its number and hash, and overwritten if the block is
+/// loaded again. Failures to write the file are logged and do not fail the derivation.
+///
+/// The host passes it to the [EigenDASource](hydro_eigenda::derive::EigenDASource) of the
+/// client it runs in native mode with `--dump-batch-data`.
+#[derive(Debug, Clone)]
+pub struct FileBatchDataSink {
+    dir: PathBuf,
+}
+
+impl FileBatchDataSink {
+    /// Creates a new [FileBatchDataSink] writing to the directory at `dir`, created if missing.
+    pub const fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Returns the path of the file receiving the batch data of `block_ref`.
+    pub fn path(&self, block_ref: &BlockInfo) -> PathBuf {
+        self.dir
+            .join(format!("{}-{}.batch", block_ref.number, block_ref.hash))
+    }
+}
+
+impl BatchDataSink for FileBatchDataSink {
+    fn record(&self, block_ref: &BlockInfo, dump: &BatchDataDump) {
+        let path = self.path(block_ref);
+        let result =
+            std::fs::create_dir_all(&self.dir).and_then(|_| std::fs::write(&path, dump.to_text()));
+        if let Err(e) = result {
+            warn!(
+                target: "eigenda-host",
+                "Failed to dump the batch data of block {} to {}: {e}",
+                block_ref.number,
+                path.display()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Bytes, B256};
+    use hydro_eigenda::derive::DaSourceInfo;
+
+    #[test]
+    fn test_dump_to_file() {
+        let dir = std::env::temp_dir().join(format!("hydro-dump-{}", std::process::id()));
+        let sink = FileBatchDataSink::new(dir.clone());
+        let block = BlockInfo {
+            hash: B256::repeat_byte(0xbb),
+            number: 10,
+            ..Default::default()
+        };
+        let dump = BatchDataDump {
+            data: vec![Bytes::from_static(&[1, 2]), Bytes::from_static(&[3])],
+            sources: vec![
+                None,
+                Some(DaSourceInfo {
+                    commitment: Bytes::from_static(&[0xaa]),
+                }),
+            ],
+        };
+
+        sink.record(&block, &dump);
+        let path = sink.path(&block);
+        assert_eq!(path, dir.join(format!("10-{}.batch", block.hash)));
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(BatchDataDump::from_text(&text).unwrap(), dump);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,171 @@
+//! Contains the [FixtureHostBackend] of an offline host serving EigenDA blobs from fixtures.
+
+use crate::eigenda::{
+    preimages::{populate_preimages_or_sentinel, progress_key, write_preimages, PreimageBatch},
+    FixtureEigenDAProvider, HostHint,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use core::{fmt, marker::PhantomData};
+use hydro_eigenda::common::{BlobInfo, Keccak256Hasher, KeyHasher};
+use hydro_oracle::hint::HintWrapper;
+use kona_host::SharedKeyValueStore;
+use kona_preimage::{
+    errors::{PreimageOracleError, PreimageOracleResult},
+    HintRouter, PreimageFetcher, PreimageKey,
+};
+use kona_proof::Hint;
+
+/// The backend of an offline host replaying a recorded run, serving the preimages of the
+/// key-value store like the [OfflineHostBackend](kona_host::OfflineHostBackend), and writing
+/// those of the EigenDA blobs hinted by the client from a [FixtureEigenDAProvider] first.
+///
+/// Every other hint is ignored, its preimages must already be in the store. The preimage keys
+/// of the EigenDA blobs are derived with the [KeyHasher] `H`.
+pub struct FixtureHostBackend<H = Keccak256Hasher> {
+    kv: SharedKeyValueStore,
+    fixtures: FixtureEigenDAProvider,
+    trust_proxy_commitment: bool,
+    _hasher: PhantomData<fn() -> H>,
+}
+
+impl<H> fmt::Debug for FixtureHostBackend<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixtureHostBackend")
+            .field("fixtures", &self.fixtures)
+            .field("trust_proxy_commitment", &self.trust_proxy_commitment)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<H: KeyHasher> FixtureHostBackend<H> {
+    /// Creates a new [FixtureHostBackend] over the store, writing the preimages of the fixture
+    /// blobs as the hint handler does, see
+    /// [EigenDACfg::trust_proxy_commitment](crate::eigenda::EigenDACfg::trust_proxy_commitment).
+    pub const fn new(
+        kv: SharedKeyValueStore,
+        fixtures: FixtureEigenDAProvider,
+        trust_proxy_commitment: bool,
+    ) -> Self {
+        Self {
+            kv,
+            fixtures,
+            trust_proxy_commitment,
+            _hasher: PhantomData,
+        }
+    }
+
+    /// Reads the blob of the commitment from the fixtures and writes its preimages.
+    async fn fetch_eigen_da_blob(&self, commitment: &[u8]) -> Result<()> {
+        let blob = self
+            .fixtures
+            .get_blob(commitment)
+            .await
+            .map_err(|e| anyhow!("Failed to read fixture blob: {e}"))?;
+        let cert_blob_info = BlobInfo::from_commitment(commitment)
+            .map_err(|e| anyhow!("Failed to decode blob info: {e}"))?;
+
+        let progress = Some(progress_key::<H>(&cert_blob_info));
+        let trust_proxy_commitment = self.trust_proxy_commitment;
+        let preimages = tokio::task::spawn_blocking(move || {
+            let mut batch = PreimageBatch::default();
+            populate_preimages_or_sentinel::<H>(
+                &mut batch,
+                &cert_blob_info,
+                &blob,
+                trust_proxy_commitment,
+            )?;
+            Ok::<_, anyhow::Error>(batch.into_written())
+        })
+        .await
+        .map_err(|e| anyhow!("EigenDA fixture task failed: {e}"))??;
+        write_preimages(&mut *self.kv.write().await, progress, preimages)
+    }
+}
+
+#[async_trait]
+impl<H: KeyHasher + Send + Sync> HintRouter for FixtureHostBackend<H> {
+    async fn route_hint(&self, hint: String) -> PreimageOracleResult<()> {
+        let hint = hint
+            .parse::<Hint<HostHint>>()
+            .map_err(|e| PreimageOracleError::Other(e.to_string()))?;
+        if hint.ty == HostHint::Known(HintWrapper::EigenDABlob) {
+            self.fetch_eigen_da_blob(&hint.data)
+                .await
+                .map_err(|e| PreimageOracleError::Other(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<H: KeyHasher + Send + Sync> PreimageFetcher for FixtureHostBackend<H> {
+    async fn get_preimage(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+        self.kv
+            .read()
+            .await
+            .get(key.into())
+            .ok_or(PreimageOracleError::KeyNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+    use alloy_rlp::Encodable;
+    use hydro_eigenda::common::{eigenda_field_element_key, BlobHeader, G1Commitment};
+    use kona_host::MemoryKeyValueStore;
+    use kona_preimage::PreimageKeyType;
+    use std::sync::Arc;
+    use tokio::sync::RwLock;
+
+    #[tokio::test]
+    async fn test_serve_fixture_blob() {
+        let cert = BlobInfo {
+            blob_header: BlobHeader {
+                commitment: G1Commitment {
+                    x: [0x11; 32],
+                    y: [0x22; 32],
+                },
+                data_length: 2,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut commitment = vec![0x01, 0x00, 0x00];
+        cert.encode(&mut commitment);
+
+        let dir = std::env::temp_dir().join(format!("hydro-fixture-host-{}", std::process::id()));
+        let fixtures = FixtureEigenDAProvider::new(dir.clone());
+        fixtures
+            .write_blob(&commitment, b"recorded rollup data")
+            .unwrap();
+
+        let kv: SharedKeyValueStore = Arc::new(RwLock::new(MemoryKeyValueStore::new()));
+        let backend = FixtureHostBackend::<Keccak256Hasher>::new(kv, fixtures, true);
+        let key = eigenda_field_element_key(&[0x11; 32], &[0x22; 32], 0);
+        let key = PreimageKey::new(
+            *Keccak256Hasher::hash_key(&key),
+            PreimageKeyType::GlobalGeneric,
+        );
+        assert!(backend.get_preimage(key).await.is_err());
+
+        // other hints are served from the store alone
+        backend
+            .route_hint("l1-block-header 0x00".to_string())
+            .await
+            .unwrap();
+        let hint = format!("eigen-da-blob {}", hex::encode(&commitment));
+        backend.route_hint(hint).await.unwrap();
+        let header = backend.get_preimage(key).await.unwrap();
+        // the header of the encoded blob holds the length of the data
+        assert_eq!(header[5], b"recorded rollup data".len() as u8);
+
+        // a commitment without fixture fails the hint
+        commitment.push(0);
+        let hint = format!("eigen-da-blob {}", hex::encode(&commitment));
+        assert!(backend.route_hint(hint).await.is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
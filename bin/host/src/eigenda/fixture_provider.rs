@@ -0,0 +1,129 @@
+//! Contains the [FixtureEigenDAProvider], serving EigenDA blobs from a local directory.
+
+use alloy_primitives::{hex, keccak256, B256};
+use async_trait::async_trait;
+use hydro_eigenda::{common::BlobInfo, derive::EigenDAProvider, errors::EigenDAProviderError};
+use std::{io, path::PathBuf};
+
+/// An [EigenDAProvider] serving the blobs stored as files in a directory, to replay recorded
+/// derivation runs deterministically and without an EigenDA Proxy.
+///
+/// The file of a blob holds its raw bytes, as returned by the proxy, and is named after the
+/// hex encoded keccak256 hash of its commitment. Commitments without a file fail the
+/// retrieval.
+///
+/// The blobs written with [FixtureEigenDAProvider::write_blob] are also indexed by their cert
+/// hash, see [BlobInfo::cert_hash], with a file named `cert-` followed by the hex encoded
+/// cert hash holding the commitment of the blob.
+#[derive(Debug, Clone)]
+pub struct FixtureEigenDAProvider {
+    dir: PathBuf,
+}
+
+impl FixtureEigenDAProvider {
+    /// Creates a new [FixtureEigenDAProvider] serving the blobs of the directory at `dir`.
+    pub const fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Returns the path of the file holding the blob of `commitment`.
+    pub fn path(&self, commitment: &[u8]) -> PathBuf {
+        self.dir.join(hex::encode(keccak256(commitment)))
+    }
+
+    /// Returns the path of the file indexing the commitment of the cert hash.
+    pub fn cert_hash_path(&self, cert_hash: B256) -> PathBuf {
+        self.dir.join(format!("cert-{}", hex::encode(cert_hash)))
+    }
+
+    /// Reads the blob of the commitment from its file.
+    pub async fn get_blob(&self, commitment: &[u8]) -> Result<Vec<u8>, EigenDAProviderError> {
+        let path = self.path(commitment);
+        tokio::fs::read(&path).await.map_err(|e| {
+            EigenDAProviderError::RetrieveFramesFromDaIndexer(format!(
+                "no fixture blob for commitment {} at {}: {e}",
+                hex::encode_prefixed(commitment),
+                path.display()
+            ))
+        })
+    }
+
+    /// Reads the blob of the cert hash, through the commitment indexed by it.
+    pub async fn get_blob_by_cert_hash(
+        &self,
+        cert_hash: B256,
+    ) -> Result<Vec<u8>, EigenDAProviderError> {
+        let path = self.cert_hash_path(cert_hash);
+        let commitment = tokio::fs::read(&path).await.map_err(|e| {
+            EigenDAProviderError::RetrieveFramesFromDaIndexer(format!(
+                "no fixture blob for cert hash {cert_hash} at {}: {e}",
+                path.display()
+            ))
+        })?;
+        self.get_blob(&commitment).await
+    }
+
+    /// Writes the blob of the commitment to its file, creating the directory if missing, e.g.
+    /// to record the blobs of a run, and indexes it by cert hash if the commitment carries a
+    /// cert.
+    pub fn write_blob(&self, commitment: &[u8], blob: &[u8]) -> io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.path(commitment), blob)?;
+        match BlobInfo::from_commitment(commitment) {
+            Ok(cert) => std::fs::write(self.cert_hash_path(cert.cert_hash()), commitment),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl EigenDAProvider for FixtureEigenDAProvider {
+    type Error = EigenDAProviderError;
+
+    async fn blob_get(&self, commitment: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        self.get_blob(commitment).await
+    }
+
+    async fn blob_get_by_cert_hash(&self, cert_hash: B256) -> Result<Vec<u8>, Self::Error> {
+        self.get_blob_by_cert_hash(cert_hash).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fixture_blobs() {
+        let dir = std::env::temp_dir().join(format!("hydro-fixtures-{}", std::process::id()));
+        let provider = FixtureEigenDAProvider::new(dir.clone());
+        provider.write_blob(&[0x01, 0xaa], b"blob").unwrap();
+
+        assert_eq!(
+            provider.path(&[0x01, 0xaa]),
+            dir.join(hex::encode(keccak256([0x01, 0xaa])))
+        );
+        assert_eq!(provider.blob_get(&[0x01, 0xaa]).await.unwrap(), b"blob");
+        let err = provider.get_blob(&[0x01, 0xbb]).await.unwrap_err();
+        assert!(err.to_string().contains("no fixture blob"), "{err}");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fixture_blob_by_cert_hash() {
+        let dir = std::env::temp_dir().join(format!("hydro-fixtures-cert-{}", std::process::id()));
+        let provider = FixtureEigenDAProvider::new(dir.clone());
+        let cert = BlobInfo::default();
+        let mut commitment = vec![0x01, 0x00, 0x00];
+        commitment.extend(alloy_rlp::encode(&cert));
+        provider.write_blob(&commitment, b"blob").unwrap();
+
+        let blob = provider
+            .blob_get_by_cert_hash(cert.cert_hash())
+            .await
+            .unwrap();
+        assert_eq!(blob, b"blob");
+        assert!(provider.blob_get_by_cert_hash(B256::ZERO).await.is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,25 +1,55 @@
 //! [HintHandler] for the [EigenDAChainHost].
 
-use crate::eigenda::cfg::EigenDAChainHost;
-use alloy_primitives::keccak256;
-use alloy_rlp::Decodable;
+use crate::eigenda::{
+    cfg::EigenDAChainHost,
+    preimages::{
+        mirror_preimages, populate_cert_preimages, populate_preimages_or_sentinel, progress_key,
+        spawn_preimage_writer, write_preimages, PreimageBatch,
+    },
+    EigenDAChainProviders, HostHint,
+};
+use alloy_primitives::B256;
 use anyhow::{anyhow, ensure, Result};
 use async_trait::async_trait;
-use hydro_eigenda::common::{BlobInfo, EigenDABlobData, BYTES_PER_FIELD_ELEMENT};
+use core::{future::Future, marker::PhantomData, time::Duration};
+use hydro_eigenda::common::{BlobInfo, Keccak256Hasher, KeyHasher};
 use hydro_oracle::hint::HintWrapper;
-use hydro_proofs::witness::EigenDABlobWitness;
 use kona_host::{
     single::SingleChainHintHandler, HintHandler, OnlineHostBackendCfg, SharedKeyValueStore,
 };
-use kona_preimage::{PreimageKey, PreimageKeyType};
 use kona_proof::Hint;
+use tokio::time::timeout;
+use tracing::warn;
 
-/// The [HintHandler] for the [EigenDAChainHost].   
+/// The [HintHandler] for the [EigenDAChainHost].
+///
+/// The preimage keys of the EigenDA blobs are derived with the [KeyHasher] `H`, which must
+/// match the hasher of the client side `OracleEigenDaProvider`.
 #[derive(Debug, Clone, Copy)]
-pub struct EigenDAChainHintHandler;
+pub struct EigenDAChainHintHandler<H = Keccak256Hasher>(PhantomData<fn() -> H>);
+
+impl EigenDAChainHintHandler {
+    /// Creates a new [EigenDAChainHintHandler] deriving keys with keccak256.
+    pub const fn new() -> Self {
+        Self::with_hasher()
+    }
+}
+
+impl Default for EigenDAChainHintHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H: KeyHasher> EigenDAChainHintHandler<H> {
+    /// Creates a new [EigenDAChainHintHandler] deriving keys with the hasher `H`.
+    pub const fn with_hasher() -> Self {
+        Self(PhantomData)
+    }
+}
 
 #[async_trait]
-impl HintHandler for EigenDAChainHintHandler {
+impl<H: KeyHasher + Send + Sync + 'static> HintHandler for EigenDAChainHintHandler<H> {
     type Cfg = EigenDAChainHost;
 
     async fn fetch_hint(
@@ -29,7 +59,7 @@ impl HintHandler for EigenDAChainHintHandler {
         kv: SharedKeyValueStore,
     ) -> Result<()> {
         match hint.ty {
-            HintWrapper::Standard(standard_hint) => {
+            HostHint::Known(HintWrapper::Standard(standard_hint)) => {
                 let inner_hint = Hint {
                     ty: standard_hint,
                     data: hint.data.clone(),
@@ -47,135 +77,187 @@ impl HintHandler for EigenDAChainHintHandler {
                     Err(err) => anyhow::bail!("Standard Hint processing error {err} on hint type {standard_hint} and data {:x}", hint.data),
                 }
             }
-            HintWrapper::EigenDABlob => {
+            HostHint::Known(HintWrapper::EigenDABlob) => {
                 ensure!(hint.data.len() > 32, "Invalid hint data length");
 
-                let commitment = hint.data.to_vec();
-                // Fetch the blob from the eigen da provider.
-                let blob = providers
-                    .eigen_da
-                    .get_blob(&commitment)
-                    .await
-                    .map_err(|e| anyhow!("Failed to fetch blob: {e}"))?;
-                let mut kv_lock = kv.write().await;
-
-                // the fourth because 0x01010000 in the beginning is metadata
-                let cert_blob_info = BlobInfo::decode(&mut &commitment[3..])
-                    .map_err(|e| anyhow!("Failed to decode blob info: {e}"))?;
-                // Proxy should return a cert whose data_length measured in symbol (i.e. 32 Bytes)
-                let blob_length = cert_blob_info.blob_header.data_length as u64;
-
-                let eigenda_blob = EigenDABlobData::encode(blob.as_ref());
-
-                assert!(
-                    eigenda_blob.blob.len() <= blob_length as usize * BYTES_PER_FIELD_ELEMENT,
-                    "EigenDA blob size ({}) exceeds expected size ({})",
-                    eigenda_blob.blob.len(),
-                    blob_length as usize * BYTES_PER_FIELD_ELEMENT
-                );
-
-                //
-                // Write all the field elements to the key-value store.
-                // The preimage oracle key for each field element is the keccak256 hash of
-                // `abi.encodePacked(cert.KZGCommitment, uint256(i))`
-
-                //  TODO figure out the key size, most likely dependent on smart contract parsing
-                let mut blob_key = [0u8; 96];
-                blob_key[..32].copy_from_slice(cert_blob_info.blob_header.commitment.x.as_ref());
-                blob_key[32..64].copy_from_slice(cert_blob_info.blob_header.commitment.y.as_ref());
-
-                for i in 0..blob_length {
-                    blob_key[88..].copy_from_slice(i.to_be_bytes().as_ref());
-                    let blob_key_hash = keccak256(blob_key.as_ref());
-
-                    kv_lock.set(
-                        PreimageKey::new(*blob_key_hash, PreimageKeyType::Keccak256).into(),
-                        blob_key.into(),
-                    )?;
-
-                    let start = (i as usize) << 5;
-                    let end = start + 32;
-                    let actual_end = eigenda_blob.blob.len().min(end);
-                    let data_slice = if start >= eigenda_blob.blob.len() {
-                        vec![0u8; 32]
-                    } else {
-                        let mut padded_data = vec![0u8; 32];
-                        padded_data[..(actual_end - start)]
-                            .copy_from_slice(&eigenda_blob.blob[start..actual_end]);
-                        padded_data
-                    };
-                    kv_lock.set(
-                        PreimageKey::new(*blob_key_hash, PreimageKeyType::GlobalGeneric).into(),
-                        data_slice.into(),
-                    )?;
-                }
+                let fetch = fetch_eigen_da_blob::<H>(hint.data.to_vec(), cfg, providers, kv);
+                with_hint_timeout(cfg.eigen_da_args.hint_timeout, fetch).await?
+            }
+            HostHint::Known(HintWrapper::EigenDACert) => {
+                ensure!(hint.data.len() > 32, "Invalid hint data length");
 
-                // proof is at the random point
-                //TODO
-                // Because the blob_length in EigenDA is variable-length, KZG proofs cannot be cached at the position corresponding to blob_length
-                // For now, they are placed at the position corresponding to commit x y. Further optimization will follow the EigenLayer approach
-                let mut kzg_proof_key = [0u8; 64];
-                kzg_proof_key[..64].copy_from_slice(blob_key[..64].as_ref());
-                let kzg_proof_key_hash = keccak256(kzg_proof_key.as_ref());
+                fetch_eigen_da_cert::<H>(&hint.data, providers, kv).await?
+            }
+            HostHint::Unknown => check_unknown_hint(cfg.eigen_da_args.lenient_hints, &hint.data)?,
+        }
+        Ok(())
+    }
+}
 
-                //TODO
-                // In fact, the calculation result following the EigenLayer approach is not the same as the cert blob info.
-                // need to save the real commitment x y
-                let mut kzg_commitment_key = [0u8; 65];
-                kzg_commitment_key[..64].copy_from_slice(blob_key[..64].as_ref());
-                kzg_commitment_key[64] = 0u8;
-                let kzg_commitment_key_hash = keccak256(kzg_commitment_key.as_ref());
+/// Fetches the blob of the commitment from the EigenDA Proxy and writes its preimages to the
+/// key-value store.
+async fn fetch_eigen_da_blob<H: KeyHasher + 'static>(
+    commitment: Vec<u8>,
+    cfg: &EigenDAChainHost,
+    providers: &EigenDAChainProviders,
+    kv: SharedKeyValueStore,
+) -> Result<()> {
+    // Fetch the blob from the eigen da provider.
+    let blob = providers
+        .eigen_da
+        .get_blob(&commitment)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch blob: {e}"))?;
+    // the fourth because 0x01010000 in the beginning is metadata
+    let cert_blob_info = BlobInfo::from_commitment(&commitment)
+        .map_err(|e| anyhow!("Failed to decode blob info: {e}"))?;
 
-                let mut witness = EigenDABlobWitness::new();
+    // the writes resume from the progress of an interrupted population of the blob, if any
+    let progress = Some(progress_key::<H>(&cert_blob_info));
 
-                let _ = witness
-                    .push_witness(&eigenda_blob.blob)
-                    .map_err(|e| anyhow!("eigen da blob push witness error {e}"))?;
+    let trust_proxy_commitment = cfg.eigen_da_args.trust_proxy_commitment;
+    if let Some(capacity) = cfg.eigen_da_args.preimage_channel_capacity {
+        let (mut sender, writer) =
+            spawn_preimage_writer(kv, progress, capacity, providers.mirror_store.is_some());
+        let populated = providers
+            .da_pool
+            .run(move || {
+                populate_preimages_or_sentinel::<H>(
+                    &mut sender,
+                    &cert_blob_info,
+                    &blob,
+                    trust_proxy_commitment,
+                )
+            })
+            .await
+            .map_err(|e| anyhow!("EigenDA worker task failed: {e}"))?;
+        // the writer completes once the sender is dropped with the reconstruction, its error
+        // being the cause of a failed send
+        let written = writer
+            .await
+            .map_err(|e| anyhow!("EigenDA preimage writer failed: {e}"))??;
+        populated?;
+        if let Some(mirror) = &providers.mirror_store {
+            mirror_preimages(mirror.clone(), written);
+        }
+        return Ok(());
+    }
 
-                let last_commitment = witness.commitments.last().unwrap();
+    // the reconstruction is CPU-bound, compute it on the worker pool before
+    // taking the store lock
+    let preimages = providers
+        .da_pool
+        .run(move || {
+            let mut batch = PreimageBatch::default();
+            populate_preimages_or_sentinel::<H>(
+                &mut batch,
+                &cert_blob_info,
+                &blob,
+                trust_proxy_commitment,
+            )?;
+            Ok::<_, anyhow::Error>(batch.into_written())
+        })
+        .await
+        .map_err(|e| anyhow!("EigenDA worker task failed: {e}"))??;
 
-                if last_commitment[..32] != cert_blob_info.blob_header.commitment.x[..]
-                    || last_commitment[32..64] != cert_blob_info.blob_header.commitment.y[..]
-                {
-                    return Err(anyhow!(
-                        "proxy commitment is different from computed commitment proxy",
-                    ));
-                };
+    store_preimages(providers, kv, progress, preimages).await
+}
 
-                let proof: Vec<u8> = witness
-                    .proofs
-                    .iter()
-                    .flat_map(|x| x.as_ref().iter().copied())
-                    .collect();
-
-                kv_lock.set(
-                    PreimageKey::new(*kzg_proof_key_hash, PreimageKeyType::Keccak256).into(),
-                    kzg_proof_key.into(),
-                )?;
-                // proof to be done
-                kv_lock.set(
-                    PreimageKey::new(*kzg_proof_key_hash, PreimageKeyType::GlobalGeneric).into(),
-                    proof.into(),
-                )?;
-
-                let commitment: Vec<u8> = witness
-                    .commitments
-                    .iter()
-                    .flat_map(|x| x.as_ref().iter().copied())
-                    .collect();
-                kv_lock.set(
-                    PreimageKey::new(*kzg_commitment_key_hash, PreimageKeyType::Keccak256).into(),
-                    kzg_commitment_key.into(),
-                )?;
-
-                // proof to be done
-                kv_lock.set(
-                    PreimageKey::new(*kzg_commitment_key_hash, PreimageKeyType::GlobalGeneric)
-                        .into(),
-                    commitment.into(),
-                )?;
-            }
+/// Decodes the cert of the commitment and writes its preimages to the key-value store, without
+/// retrieving the blob, see [populate_cert_preimages].
+async fn fetch_eigen_da_cert<H: KeyHasher>(
+    commitment: &[u8],
+    providers: &EigenDAChainProviders,
+    kv: SharedKeyValueStore,
+) -> Result<()> {
+    let cert_blob_info = BlobInfo::from_commitment(commitment)
+        .map_err(|e| anyhow!("Failed to decode blob info: {e}"))?;
+
+    let mut batch = PreimageBatch::default();
+    populate_cert_preimages::<H>(&mut batch, &cert_blob_info)?;
+    store_preimages(providers, kv, None, batch.into_written()).await
+}
+
+/// Writes the preimages to the key-value store, recording the progress under `progress` if any,
+/// and copies them to the mirror store if any.
+async fn store_preimages(
+    providers: &EigenDAChainProviders,
+    kv: SharedKeyValueStore,
+    progress: Option<B256>,
+    preimages: Vec<(B256, Vec<u8>)>,
+) -> Result<()> {
+    match &providers.mirror_store {
+        Some(mirror) => {
+            write_preimages(&mut *kv.write().await, progress, preimages.iter().cloned())?;
+            // the hint is answered without waiting for the mirror
+            mirror_preimages(mirror.clone(), preimages);
         }
-        Ok(())
+        None => write_preimages(&mut *kv.write().await, progress, preimages)?,
+    }
+    Ok(())
+}
+
+/// Bounds the processing of a hint by `limit`, if any.
+///
+/// The hint fails once the limit is reached, though a reconstruction already running on the
+/// worker pool runs to completion in the background.
+async fn with_hint_timeout<T>(
+    limit: Option<Duration>,
+    processing: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    match limit {
+        Some(limit) => timeout(limit, processing)
+            .await
+            .map_err(|_| anyhow!("EigenDA hint processing timed out after {limit:?}"))?,
+        None => processing.await,
+    }
+}
+
+/// Fails on a hint of unknown type, unless unknown hints are ignored by a lenient host.
+fn check_unknown_hint(lenient: bool, data: &[u8]) -> Result<()> {
+    ensure!(lenient, "Unknown hint type");
+    warn!(
+        target: "eigenda-hint-handler",
+        "Ignoring hint of unknown type with {} bytes of data",
+        data.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    #[test]
+    fn test_unknown_hint_policy() {
+        assert_eq!(
+            HostHint::from_str("eigen-da-blob-v2").unwrap(),
+            HostHint::Unknown
+        );
+
+        assert!(check_unknown_hint(false, &[0xde, 0xad]).is_err());
+        assert!(check_unknown_hint(true, &[0xde, 0xad]).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_hint_timeout() {
+        let slow = async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(())
+        };
+        let err = with_hint_timeout(Some(Duration::from_millis(10)), slow)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+
+        let fast = async { Ok(7) };
+        assert_eq!(
+            with_hint_timeout(Some(Duration::from_secs(5)), fast)
+                .await
+                .unwrap(),
+            7
+        );
+        assert_eq!(with_hint_timeout(None, async { Ok(7) }).await.unwrap(), 7);
     }
 }
@@ -0,0 +1,58 @@
+//! The hint type parsed by the [EigenDAChainHost](crate::eigenda::EigenDAChainHost).
+
+use core::{fmt, str::FromStr};
+use hydro_oracle::hint::HintWrapper;
+use kona_proof::errors::HintParsingError;
+
+/// A hint received from the client, tolerating hints unknown to this host.
+///
+/// Parsing never fails on the hint type, an unknown one is parsed as [HostHint::Unknown] and
+/// rejected or ignored by the hint handler depending on
+/// [EigenDACfg::lenient_hints](crate::eigenda::EigenDACfg::lenient_hints). This lets an older
+/// host serve a newer client sending hints it does not need to act upon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HostHint {
+    /// A hint known to this host.
+    Known(HintWrapper),
+    /// A hint this host does not know.
+    Unknown,
+}
+
+impl FromStr for HostHint {
+    type Err = HintParsingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(HintWrapper::from_str(s).map_or(Self::Unknown, Self::Known))
+    }
+}
+
+impl fmt::Display for HostHint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Known(hint) => write!(f, "{hint}"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_host_hint() {
+        assert_eq!(
+            HostHint::from_str("eigen-da-blob").unwrap(),
+            HostHint::Known(HintWrapper::EigenDABlob)
+        );
+        assert_eq!(
+            HostHint::from_str("eigen-da-cert").unwrap(),
+            HostHint::Known(HintWrapper::EigenDACert)
+        );
+        // unknown hints are left to the policy of the handler
+        assert_eq!(
+            HostHint::from_str("eigen-da-blob-v2").unwrap(),
+            HostHint::Unknown
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//! Metrics of the EigenDA host backend, recorded with the `metrics` feature and exported for
+//! Prometheus to scrape with `--metrics-addr`. Without the feature, nothing is recorded.
+
+use hydro_eigenda::errors::EigenDAProxyError;
+use std::time::Duration;
+
+/// Histogram of the duration in seconds of the blob retrievals from the EigenDA Proxy, polling
+/// and retries included.
+pub const RETRIEVE_DURATION: &str = "hydro_eigenda_proxy_retrieve_duration_seconds";
+
+/// Counter of the blob retrievals from the EigenDA Proxy, labeled with their `outcome`: `ok`
+/// for a `200`, `not_found` for a `404`, `deadline_exceeded` when the retrieve timeout elapses
+/// and `error` for any other failure.
+pub const RETRIEVALS: &str = "hydro_eigenda_proxy_retrievals_total";
+
+/// Records the duration and outcome of a blob retrieval.
+pub(crate) fn record_retrieval(elapsed: Duration, result: &Result<Vec<u8>, EigenDAProxyError>) {
+    #[cfg(feature = "metrics")]
+    {
+        let outcome = match result {
+            Ok(_) => "ok",
+            Err(EigenDAProxyError::NotFound) => "not_found",
+            Err(EigenDAProxyError::DeadlineExceeded(_)) => "deadline_exceeded",
+            Err(_) => "error",
+        };
+        metrics::histogram!(RETRIEVE_DURATION).record(elapsed);
+        metrics::counter!(RETRIEVALS, "outcome" => outcome).increment(1);
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = (elapsed, result);
+}
+
+/// Installs the global metrics recorder, serving the metrics of the host and of the EigenDA
+/// derivation for Prometheus at `addr`.
+#[cfg(feature = "metrics")]
+pub fn install_exporter(addr: std::net::SocketAddr) -> std::io::Result<()> {
+    metrics_exporter_prometheus::PrometheusBuilder::new()
+        .with_http_listener(addr)
+        .install()
+        .map_err(std::io::Error::other)
+}
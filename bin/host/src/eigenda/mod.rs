@@ -1,11 +1,54 @@
+mod capabilities;
+pub use capabilities::{ProxyCapabilities, OP_GENERIC_COMMITMENT_MODE};
+
+mod capture;
+pub use capture::{CaptureFilter, RequestCapture};
+
+mod dump;
+pub use dump::FileBatchDataSink;
+
+mod fixture_provider;
+pub use fixture_provider::FixtureEigenDAProvider;
+
 mod online_provider;
-pub use online_provider::{EigenDAProxy, OnlineEigenDAProvider};
+pub use online_provider::{
+    BatchMode, EigenDAProxy, LongPoll, OnlineEigenDAProvider, ProxyAuthToken,
+    DEFAULT_MAX_RESPONSE_SIZE,
+};
 
 mod providers;
 pub use providers::EigenDAChainProviders;
 
+mod hint;
+pub use hint::HostHint;
+
+mod tls;
+
+mod version;
+pub use version::{ProxyVersion, ProxyVersionCheck, MAX_PROXY_VERSION, MIN_PROXY_VERSION};
+
+pub mod metrics;
+
+mod pool;
+pub use pool::DaWorkerPool;
+
 mod handler;
 pub use handler::EigenDAChainHintHandler;
 
+mod fixture_backend;
+pub use fixture_backend::FixtureHostBackend;
+
+mod preimage_cache;
+pub use preimage_cache::CachingHostBackend;
+
+mod preimages;
+pub use preimages::{populate_cert_preimages, populate_preimages};
+
 mod cfg;
 pub use cfg::{EigenDACfg, EigenDAChainHost};
+
+mod verify;
+pub use verify::{verify_commitment, DaVerifyCommand, Verification};
+
+#[cfg(test)]
+pub(crate) mod test_utils;
@@ -1,11 +1,102 @@
 //! Contains an online implementation of the `EigenDAProvider` trait.
 
-use alloy_primitives::hex;
-use core::time::Duration;
-use hydro_eigenda::errors::{EigenDAProviderError, EigenDAProxyError};
-use reqwest::{Client, StatusCode};
-use std::vec::Vec;
-use tokio::time::timeout;
+use crate::eigenda::{
+    capture::{RequestCapture, RequestSummary},
+    metrics,
+    tls::pinned_client_config,
+    ProxyCapabilities, ProxyVersion, MAX_PROXY_VERSION, MIN_PROXY_VERSION,
+};
+use alloy_primitives::{hex, B256};
+use core::{num::NonZeroUsize, time::Duration};
+use hydro_eigenda::{
+    common::{
+        BlobInfo, EigenDABlobData, BLOB_ENCODING_VERSION_0, BYTES_PER_FIELD_ELEMENT,
+        DEFAULT_MAX_COMMITMENT_LENGTH,
+    },
+    errors::{EigenDAProviderError, EigenDAProxyError},
+    retry::{retry, RetryPolicy},
+};
+use hydro_proofs::witness::EigenDABlobWitness;
+use lru::LruCache;
+use reqwest::{
+    header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE},
+    Client, ClientBuilder, Request, Response, StatusCode,
+};
+use serde::Deserialize;
+use std::{
+    convert::Infallible,
+    fmt, io, iter,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    vec::Vec,
+};
+use tokio::{
+    task::JoinSet,
+    time::{sleep, timeout, Instant},
+};
+use tracing::{debug, warn};
+
+/// The default limit on the size of a blob returned by the proxy, 16 MiB being the maximum
+/// EigenDA blob size.
+pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 16 * 1024 * 1024;
+
+/// The media type of the raw blob payload served by the proxy.
+const BLOB_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// The limit on the size of the responses of the informational endpoints of the proxy, e.g.
+/// `/version`.
+const MAX_INFO_RESPONSE_SIZE: usize = 4096;
+
+/// Polling of blobs which are not available on the proxy yet, e.g. shortly after dispersal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LongPoll {
+    /// The total time spent polling for a blob before giving up.
+    pub deadline: Duration,
+    /// The delay before the second poll, doubled after every poll.
+    pub initial_backoff: Duration,
+    /// The maximum delay between two polls.
+    pub max_backoff: Duration,
+}
+
+impl LongPoll {
+    /// Polls for at most `deadline`, backing off from 250ms up to 5s between polls.
+    pub const fn new(deadline: Duration) -> Self {
+        Self {
+            deadline,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+/// A bearer token authenticating the requests to the proxy, e.g. for an auth gateway in front
+/// of it. It is redacted when formatted so that it never ends up in the logs.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ProxyAuthToken(String);
+
+impl ProxyAuthToken {
+    /// Creates a new [ProxyAuthToken].
+    pub const fn new(token: String) -> Self {
+        Self(token)
+    }
+}
+
+impl fmt::Debug for ProxyAuthToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProxyAuthToken(<redacted>)")
+    }
+}
+
+impl FromStr for ProxyAuthToken {
+    type Err = Infallible;
+
+    fn from_str(token: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(token.to_string()))
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct EigenDAProxy {
@@ -15,6 +106,23 @@ pub struct EigenDAProxy {
     pub retrieve_client: Client,
     /// The timeout for request form retrieve service.
     pub retrieve_blob_timeout: Duration,
+    /// The timeout of a blob dispersal, which takes longer than a retrieval.
+    pub disperse_blob_timeout: Duration,
+    /// Whether dispersed blobs are read back and compared with the dispersed data.
+    pub verify_dispersal: bool,
+    /// The maximum number of bytes accepted in a response body.
+    pub max_response_size: usize,
+    /// Polls blobs not found on the proxy until they appear when set.
+    pub long_poll: Option<LongPoll>,
+    /// Records summaries of selected retrievals when set.
+    pub capture: Option<RequestCapture>,
+    /// The retries of requests failing with a transient error.
+    pub retry: RetryPolicy,
+    /// The SHA-256 hash of the SubjectPublicKeyInfo the TLS certificate of the proxy is pinned
+    /// to, if any.
+    pub pinned_cert: Option<B256>,
+    /// The `Authorization` header sent with every request, marked as sensitive.
+    auth_header: Option<HeaderValue>,
 }
 
 impl EigenDAProxy {
@@ -22,47 +130,440 @@ impl EigenDAProxy {
     pub fn new(proxy_url: String, retrieve_blob_timeout: Duration) -> Self {
         Self {
             proxy_url,
-            retrieve_client: Client::builder()
-                .timeout(retrieve_blob_timeout)
+            retrieve_client: client_builder(retrieve_blob_timeout)
                 .build()
                 .expect("retrieve client builder failed"),
             retrieve_blob_timeout,
+            disperse_blob_timeout: retrieve_blob_timeout,
+            verify_dispersal: false,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            long_poll: None,
+            capture: None,
+            retry: RetryPolicy::none(),
+            pinned_cert: None,
+            auth_header: None,
         }
     }
 
+    /// Sets the maximum number of bytes accepted in a response body.
+    pub fn with_max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = max_response_size;
+        self
+    }
+
+    /// Sets the timeout of a blob dispersal, the retrieve timeout by default.
+    pub fn with_disperse_timeout(mut self, disperse_blob_timeout: Duration) -> Self {
+        self.disperse_blob_timeout = disperse_blob_timeout;
+        self
+    }
+
+    /// Reads every dispersed blob back with its commitment, failing the dispersal with
+    /// [EigenDAProxyError::DispersalMismatch] if the proxy serves other data.
+    pub fn with_dispersal_verification(mut self) -> Self {
+        self.verify_dispersal = true;
+        self
+    }
+
+    /// Polls blobs which are not found on the proxy until they appear or the long poll
+    /// deadline passes, instead of failing with [EigenDAProxyError::NotFound] right away.
+    pub fn with_long_poll(mut self, long_poll: LongPoll) -> Self {
+        self.long_poll = Some(long_poll);
+        self
+    }
+
+    /// Only connects to a proxy whose TLS certificate carries the public key pinned by
+    /// `spki_sha256`, the SHA-256 hash of its DER encoded SubjectPublicKeyInfo. The certificate
+    /// must still chain to a trusted root.
+    pub fn with_pinned_cert(mut self, spki_sha256: B256) -> io::Result<Self> {
+        self.pinned_cert = Some(spki_sha256);
+        self.retrieve_client = self.build_client()?;
+        Ok(self)
+    }
+
+    /// Sends `Authorization: Bearer <token>` with every request to the proxy.
+    pub fn with_auth_token(mut self, token: &ProxyAuthToken) -> io::Result<Self> {
+        let mut auth_header = HeaderValue::try_from(format!("Bearer {}", token.0))
+            .map_err(|_| io::Error::other("invalid EigenDA Proxy auth token"))?;
+        auth_header.set_sensitive(true);
+        self.auth_header = Some(auth_header);
+        self.retrieve_client = self.build_client()?;
+        Ok(self)
+    }
+
+    /// Builds the http client, with the pinned certificate and the auth header if set.
+    fn build_client(&self) -> io::Result<Client> {
+        let mut builder = client_builder(self.retrieve_blob_timeout);
+        if let Some(spki_sha256) = self.pinned_cert {
+            let tls = pinned_client_config(spki_sha256).map_err(io::Error::other)?;
+            builder = builder.use_preconfigured_tls(tls);
+        }
+        if let Some(ref auth_header) = self.auth_header {
+            let headers = HeaderMap::from_iter([(AUTHORIZATION, auth_header.clone())]);
+            builder = builder.default_headers(headers);
+        }
+        builder.build().map_err(io::Error::other)
+    }
+
+    /// Records a redacted summary of the retrievals selected by the capture.
+    pub fn with_capture(mut self, capture: RequestCapture) -> Self {
+        self.capture = Some(capture);
+        self
+    }
+
+    /// Retries requests failing with a transient error, i.e. a connection error, a timeout or
+    /// a `500`, `502`, `503` or `504` response, according to the policy. A missing blob is not
+    /// retried.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     /// Retrieves a blob with the given commitment.
+    ///
+    /// With [EigenDAProxy::with_long_poll], a blob which is not found is polled again with
+    /// backoff until it appears, [EigenDAProxyError::NotFound] being returned after the
+    /// deadline. With [EigenDAProxy::with_retry], requests failing with a transient error are
+    /// retried. Other errors are returned right away.
     pub async fn retrieve_blob_with_commitment(
         &self,
         commitment: &[u8],
     ) -> Result<Vec<u8>, EigenDAProxyError> {
+        let start = Instant::now();
+        let result = self.retrieve_blob_polling(commitment).await;
+        metrics::record_retrieval(start.elapsed(), &result);
+        result
+    }
+
+    /// Retrieves the blob with the given commitment, polling for it with
+    /// [EigenDAProxy::with_long_poll].
+    async fn retrieve_blob_polling(&self, commitment: &[u8]) -> Result<Vec<u8>, EigenDAProxyError> {
+        let Some(long_poll) = self.long_poll else {
+            return self.retrieve_blob_retrying(commitment).await;
+        };
+
+        let deadline = Instant::now() + long_poll.deadline;
+        let mut backoff = long_poll.initial_backoff;
+        loop {
+            match self.retrieve_blob_retrying(commitment).await {
+                Err(EigenDAProxyError::NotFound) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(EigenDAProxyError::NotFound);
+                    }
+                    let delay = backoff.min(deadline - now);
+                    debug!(
+                        target: "eigenda-proxy",
+                        "Blob not available yet, polling again in {delay:?}"
+                    );
+                    sleep(delay).await;
+                    backoff = (backoff * 2).min(long_poll.max_backoff);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Retrieves the blob with the given commitment, retrying according to the retry policy.
+    ///
+    /// All attempts, backoff included, are bounded by the retrieve timeout, each attempt being
+    /// given an equal share of it. An attempt exceeding its share fails with a retryable
+    /// [EigenDAProxyError::AttemptTimeout], while the retrieve timeout elapsing fails the
+    /// retrieval with [EigenDAProxyError::DeadlineExceeded], reporting the error of the last
+    /// attempt along with it.
+    async fn retrieve_blob_retrying(
+        &self,
+        commitment: &[u8],
+    ) -> Result<Vec<u8>, EigenDAProxyError> {
+        let last_error = Mutex::new(None);
+        let last_error_ref = &last_error;
+        let attempts = retry(
+            &self.retry,
+            |delay| sleep(self.retry.jittered(delay, rand::random())),
+            || async move {
+                let result = self.retrieve_blob_once(commitment).await;
+                if let Err(e) = &result {
+                    debug!(target: "eigenda-proxy", "Blob retrieval attempt failed: {e}");
+                    *last_error_ref.lock().unwrap() = Some(e.to_string());
+                }
+                result
+            },
+        );
+        let result = timeout(self.retrieve_blob_timeout, attempts).await;
+        result.unwrap_or_else(|_| {
+            let elapsed = format!("retrieval exceeded {:?}", self.retrieve_blob_timeout);
+            Err(EigenDAProxyError::DeadlineExceeded(
+                match last_error.lock().unwrap().take() {
+                    Some(e) => format!("{elapsed}, last error: {e}"),
+                    None => elapsed,
+                },
+            ))
+        })
+    }
+
+    /// The timeout of a single retrieval attempt, the retrieve timeout shared by all attempts.
+    fn attempt_timeout(&self) -> Duration {
+        self.retrieve_blob_timeout / self.retry.max_attempts.max(1)
+    }
+
+    /// Performs a single retrieval of the blob with the given commitment.
+    async fn retrieve_blob_once(&self, commitment: &[u8]) -> Result<Vec<u8>, EigenDAProxyError> {
         let request_url = format!("{}/get/0x{}", self.proxy_url, hex::encode(commitment));
+        let request = self
+            .retrieve_client
+            .get(&request_url)
+            .header(ACCEPT, BLOB_CONTENT_TYPE)
+            .build()
+            .map_err(|e| EigenDAProxyError::RetrieveBlobWithCommitment(e.to_string()))?;
 
+        let Some(capture) = self.capture.as_ref().filter(|c| c.matches(commitment)) else {
+            return self.execute(request, None).await;
+        };
+        let mut summary = RequestSummary::new(&request);
+        let started = Instant::now();
+        let result = self.execute(request, Some(&mut summary)).await;
+        summary.finish(started.elapsed(), &result);
+        capture.record(&summary);
+        result
+    }
+
+    /// Sends the retrieval request and reads the blob from the response.
+    async fn execute(
+        &self,
+        request: Request,
+        summary: Option<&mut RequestSummary>,
+    ) -> Result<Vec<u8>, EigenDAProxyError> {
         let response = timeout(
-            self.retrieve_blob_timeout,
-            self.retrieve_client.get(&request_url).send(),
+            self.attempt_timeout(),
+            self.retrieve_client.execute(request),
         )
         .await
-        .map_err(|e| EigenDAProxyError::NetworkError(e.to_string()))?
+        .map_err(|_| EigenDAProxyError::AttemptTimeout(self.attempt_timeout()))?
         .map_err(|e| EigenDAProxyError::RetrieveBlobWithCommitment(e.to_string()))?;
+        if let Some(summary) = summary {
+            summary.response(&response);
+        }
 
         match response.status() {
-            StatusCode::OK => response
-                .bytes()
-                .await
-                .map(|bytes| bytes.to_vec())
-                .map_err(|e| EigenDAProxyError::RetrieveBlobWithCommitment(e.to_string())),
+            StatusCode::OK => {
+                check_content_type(&response)?;
+                let bytes = read_body(response, self.max_response_size, retrieve_error).await?;
+                // An empty blob can never be a valid EigenDA payload, fail here rather than
+                // further down in the cert or blob decoding.
+                if bytes.is_empty() {
+                    return Err(EigenDAProxyError::EmptyResponse);
+                }
+                Ok(bytes)
+            }
             StatusCode::NOT_FOUND => Err(EigenDAProxyError::NotFound),
-            status => Err(EigenDAProxyError::NetworkError(format!(
-                "Failed to get blob with commitment, status: {status}"
-            ))),
+            status => Err(status_error(status)),
         }
     }
+
+    /// Disperses `data` through the proxy, returning the commitment it is retrieved with.
+    ///
+    /// The proxy answers `200 OK` once the blob is dispersed, while a `202 Accepted` without
+    /// a commitment means the dispersal is still in progress and is reported as
+    /// [EigenDAProxyError::DispersalPending]. A dispersal is bounded by the disperse timeout,
+    /// exceeding it being a [EigenDAProxyError::NetworkError], and is never retried since it
+    /// is not idempotent. With [EigenDAProxy::with_dispersal_verification], the blob is then
+    /// retrieved with the returned commitment and compared with `data`.
+    pub async fn disperse_blob(&self, data: &[u8]) -> Result<Vec<u8>, EigenDAProxyError> {
+        let request_error = |e: reqwest::Error| {
+            if e.is_timeout() {
+                EigenDAProxyError::NetworkError(e.to_string())
+            } else {
+                EigenDAProxyError::DisperseBlob(e.to_string())
+            }
+        };
+        let response = self
+            .retrieve_client
+            .post(format!("{}/put", self.proxy_url))
+            .header(CONTENT_TYPE, BLOB_CONTENT_TYPE)
+            .timeout(self.disperse_blob_timeout)
+            .body(data.to_vec())
+            .send()
+            .await
+            .map_err(request_error)?;
+
+        let status = response.status();
+        if !matches!(status, StatusCode::OK | StatusCode::ACCEPTED) {
+            return Err(status_error(status));
+        }
+        let commitment = read_body(response, DEFAULT_MAX_COMMITMENT_LENGTH, request_error).await?;
+        if commitment.is_empty() {
+            return Err(if status == StatusCode::ACCEPTED {
+                EigenDAProxyError::DispersalPending
+            } else {
+                EigenDAProxyError::EmptyResponse
+            });
+        }
+
+        if self.verify_dispersal {
+            let blob = self.retrieve_blob_with_commitment(&commitment).await?;
+            if blob != data {
+                return Err(EigenDAProxyError::DispersalMismatch);
+            }
+        }
+        Ok(commitment)
+    }
+
+    /// Queries the version of the proxy from its `/version` endpoint, which answers either a
+    /// JSON object with a `version` field or the plain version.
+    pub async fn version(&self) -> Result<ProxyVersion, EigenDAProxyError> {
+        let body = self.get_info("/version").await?;
+        let body = String::from_utf8_lossy(&body);
+        let version = serde_json::from_str::<VersionInfo>(&body)
+            .map(|info| info.version)
+            .unwrap_or_else(|_| body.trim().to_string());
+        ProxyVersion::parse(&version).ok_or(EigenDAProxyError::InvalidVersion(version))
+    }
+
+    /// Queries the version of the proxy, failing with [EigenDAProxyError::IncompatibleVersion]
+    /// if it is outside of [MIN_PROXY_VERSION] and [MAX_PROXY_VERSION].
+    pub async fn check_version(&self) -> Result<ProxyVersion, EigenDAProxyError> {
+        let version = self.version().await?;
+        if !version.is_compatible() {
+            return Err(EigenDAProxyError::IncompatibleVersion(format!(
+                "{version}, expected at least {MIN_PROXY_VERSION} and below {MAX_PROXY_VERSION}"
+            )));
+        }
+        Ok(version)
+    }
+
+    /// Queries the capabilities of the proxy from its `/capabilities` endpoint, e.g. to check
+    /// the configuration of the host against them. A proxy which does not advertise its
+    /// capabilities answers [EigenDAProxyError::NotFound].
+    pub async fn capabilities(&self) -> Result<ProxyCapabilities, EigenDAProxyError> {
+        let body = self.get_info("/capabilities").await?;
+        serde_json::from_slice(&body)
+            .map_err(|e| EigenDAProxyError::InvalidCapabilities(e.to_string()))
+    }
+
+    /// Fetches the body of an informational endpoint of the proxy, e.g. `/version`.
+    async fn get_info(&self, path: &str) -> Result<Vec<u8>, EigenDAProxyError> {
+        let response = self
+            .retrieve_client
+            .get(format!("{}{path}", self.proxy_url))
+            .timeout(self.attempt_timeout())
+            .send()
+            .await
+            .map_err(|e| EigenDAProxyError::NetworkError(e.to_string()))?;
+        match response.status() {
+            StatusCode::OK => read_body(response, MAX_INFO_RESPONSE_SIZE, retrieve_error).await,
+            StatusCode::NOT_FOUND => Err(EigenDAProxyError::NotFound),
+            status => Err(status_error(status)),
+        }
+    }
+}
+
+/// The body of the `/version` endpoint of the proxy.
+#[derive(Debug, Deserialize)]
+struct VersionInfo {
+    version: String,
+}
+
+/// Maps an unexpected response status to an error, a `401` or `403` of an auth gateway in front
+/// of the proxy being reported as [EigenDAProxyError::Unauthorized].
+fn status_error(status: StatusCode) -> EigenDAProxyError {
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            EigenDAProxyError::Unauthorized(status.as_u16())
+        }
+        status => EigenDAProxyError::HttpStatus(status.as_u16()),
+    }
+}
+
+/// Returns the builder of the http client of the proxy. With the `compression` feature, the
+/// client advertises `Accept-Encoding: gzip, deflate` and transparently decompresses the
+/// responses encoded with either.
+fn client_builder(timeout: Duration) -> ClientBuilder {
+    let builder = Client::builder().timeout(timeout);
+    #[cfg(feature = "compression")]
+    let builder = builder.gzip(true).deflate(true);
+    builder
 }
 
+/// Checks that the response carries the blob payload rather than a cert envelope.
+///
+/// Proxies that ignore the `Accept` header may answer with the JSON cert metadata, which
+/// must not be mistaken for blob data. Only JSON content types are rejected, a missing or
+/// other content type being accepted as raw bytes.
+fn check_content_type(response: &Response) -> Result<(), EigenDAProxyError> {
+    let Some(content_type) = response.headers().get(CONTENT_TYPE) else {
+        return Ok(());
+    };
+    let content_type = content_type.to_str().unwrap_or_default();
+    let media_type = content_type.split(';').next().unwrap_or_default().trim();
+    let media_type = media_type.to_ascii_lowercase();
+    if media_type == "application/json" || media_type.ends_with("+json") {
+        Err(EigenDAProxyError::UnexpectedContentType(
+            content_type.to_string(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Maps an error reading the body of a retrieval response.
+fn retrieve_error(e: reqwest::Error) -> EigenDAProxyError {
+    EigenDAProxyError::RetrieveBlobWithCommitment(e.to_string())
+}
+
+/// Reads the response body, enforcing `limit` on the bytes actually received and mapping the
+/// errors of the transfer with `transfer_error`.
+///
+/// The `Content-Length` header is only used to fail early, a chunked response or a proxy
+/// lying about the length is still bounded by counting the received bytes. A compressed
+/// response is bounded by its decompressed size.
+async fn read_body(
+    mut response: Response,
+    limit: usize,
+    transfer_error: impl Fn(reqwest::Error) -> EigenDAProxyError,
+) -> Result<Vec<u8>, EigenDAProxyError> {
+    if response
+        .content_length()
+        .is_some_and(|len| len > limit as u64)
+    {
+        return Err(EigenDAProxyError::ResponseTooLarge(limit));
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(&transfer_error)? {
+        if body.len() + chunk.len() > limit {
+            return Err(EigenDAProxyError::ResponseTooLarge(limit));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// How [OnlineEigenDAProvider::get_blobs] handles retrieval errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BatchMode {
+    /// Return the first error and abort the remaining retrievals.
+    #[default]
+    FailFast,
+    /// Wait for all retrievals and return the result of each commitment.
+    CollectAll,
+}
+
+/// The key of a cached blob, the KZG commitment `x | y` of its cert.
+type BlobCacheKey = [u8; 64];
+
 #[derive(Debug, Clone)]
 pub struct OnlineEigenDAProvider {
     /// The EigenDA proxy client.
     pub eigen_da_proxy_client: EigenDAProxy,
+    /// The clients of further proxies, tried in order when a retrieval fails.
+    pub fallback_proxy_clients: Vec<EigenDAProxy>,
+    /// The maximum length of a commitment, longer ones are rejected before any retrieval.
+    pub max_commitment_length: usize,
+    /// Whether retrieved blobs are checked against the cert of the requested commitment.
+    pub check_commitment: bool,
+    /// The retrieved blobs, shared between clones of the provider.
+    blob_cache: Option<Arc<Mutex<LruCache<BlobCacheKey, Vec<u8>>>>>,
+    /// The index of the proxy which served the last blob, tried first by the next retrieval.
+    preferred_proxy: Arc<AtomicUsize>,
 }
 
 impl OnlineEigenDAProvider {
@@ -70,14 +571,1093 @@ impl OnlineEigenDAProvider {
     pub fn new(eigen_da_proxy_client: EigenDAProxy) -> Self {
         Self {
             eigen_da_proxy_client,
+            fallback_proxy_clients: Vec::new(),
+            max_commitment_length: DEFAULT_MAX_COMMITMENT_LENGTH,
+            check_commitment: false,
+            blob_cache: None,
+            preferred_proxy: Arc::new(AtomicUsize::new(0)),
         }
     }
 
+    /// Falls back to the given proxy when the retrieval from the previous ones fails, e.g. for
+    /// redundant proxy deployments.
+    ///
+    /// A blob which is not found on a proxy is also retrieved from the next one, as proxies may
+    /// retain blobs for different periods. The proxy which served the last blob is tried first.
+    pub fn with_fallback_proxy(mut self, eigen_da_proxy_client: EigenDAProxy) -> Self {
+        self.fallback_proxy_clients.push(eigen_da_proxy_client);
+        self
+    }
+
+    /// Sets the maximum length of a commitment.
+    pub fn with_max_commitment_length(mut self, max_commitment_length: usize) -> Self {
+        self.max_commitment_length = max_commitment_length;
+        self
+    }
+
+    /// Checks that every retrieved blob fits the cert of the requested commitment and has its
+    /// KZG commitment, failing with [EigenDAProxyError::CommitmentMismatch] otherwise.
+    ///
+    /// This guards against a buggy or malicious proxy at the provider boundary, before the
+    /// blob is cached or served. The hint handler checks the commitment again when populating
+    /// the preimages, unless the proxy commitment is trusted. The check requires the KZG
+    /// trusted setup.
+    pub fn with_commitment_check(mut self) -> Self {
+        self.check_commitment = true;
+        self
+    }
+
+    /// Caches up to `capacity` retrieved blobs, evicting the least recently used one.
+    ///
+    /// Blobs are cached by the KZG commitment of their cert rather than by commitment bytes,
+    /// so a blob addressed by several commitment encodings, e.g. during a commitment format
+    /// migration, is retrieved once.
+    pub fn with_blob_cache(mut self, capacity: NonZeroUsize) -> Self {
+        self.blob_cache = Some(Arc::new(Mutex::new(LruCache::new(capacity))));
+        self
+    }
+
     /// Retrieves a blob with the given commitment.
     pub async fn get_blob(&self, commitment: &[u8]) -> Result<Vec<u8>, EigenDAProviderError> {
-        self.eigen_da_proxy_client
-            .retrieve_blob_with_commitment(commitment)
+        if commitment.len() > self.max_commitment_length {
+            return Err(EigenDAProviderError::CommitmentTooLarge {
+                length: commitment.len(),
+                max: self.max_commitment_length,
+            });
+        }
+
+        let cache_key = self.blob_cache.as_ref().and(blob_cache_key(commitment));
+        if let Some(blob) = cache_key.and_then(|key| self.cached_blob(&key)) {
+            debug!(
+                target: "eigenda-provider",
+                "Serving blob {} from cache",
+                hex::encode(commitment)
+            );
+            return Ok(blob);
+        }
+
+        let cert = self
+            .check_commitment
+            .then(|| BlobInfo::from_commitment(commitment))
+            .transpose()?;
+        let blob = self.retrieve_blob(commitment, cert.as_ref()).await?;
+
+        if let (Some(cache), Some(key)) = (&self.blob_cache, cache_key) {
+            cache.lock().unwrap().put(key, blob.clone());
+        }
+        Ok(blob)
+    }
+
+    /// Retrieves the blob from the proxies, starting with the preferred one and going on with
+    /// the others in order, and checks it against the cert when given.
+    ///
+    /// The errors of all proxies are reported when none of them serves the blob.
+    async fn retrieve_blob(
+        &self,
+        commitment: &[u8],
+        cert: Option<&BlobInfo>,
+    ) -> Result<Vec<u8>, EigenDAProviderError> {
+        let count = 1 + self.fallback_proxy_clients.len();
+        let preferred = self.preferred_proxy.load(Ordering::Relaxed).min(count - 1);
+        let mut errors = Vec::new();
+        for index in iter::once(preferred).chain((0..count).filter(|&i| i != preferred)) {
+            let client = match index {
+                0 => &self.eigen_da_proxy_client,
+                i => &self.fallback_proxy_clients[i - 1],
+            };
+            let result = client
+                .retrieve_blob_with_commitment(commitment)
+                .await
+                .and_then(|blob| match cert {
+                    Some(cert) => check_blob_commitment(cert, &blob).map(|_| blob),
+                    None => Ok(blob),
+                });
+            match result {
+                Ok(blob) => {
+                    self.preferred_proxy.store(index, Ordering::Relaxed);
+                    return Ok(blob);
+                }
+                Err(e) => {
+                    debug!(
+                        target: "eigenda-provider",
+                        "Blob retrieval from {} failed: {e}",
+                        client.proxy_url
+                    );
+                    errors.push(match count {
+                        1 => e.to_string(),
+                        _ => format!("{}: {e}", client.proxy_url),
+                    });
+                }
+            }
+        }
+        Err(EigenDAProviderError::RetrieveFramesFromDaIndexer(
+            errors.join("; "),
+        ))
+    }
+
+    /// Returns the cached blob of the given cache key, if any.
+    fn cached_blob(&self, key: &BlobCacheKey) -> Option<Vec<u8>> {
+        let cache = self.blob_cache.as_ref()?;
+        cache.lock().unwrap().get(key).cloned()
+    }
+
+    /// Retrieves the blobs of all given commitments concurrently.
+    ///
+    /// The results are in the order of the commitments. With [BatchMode::FailFast] the first
+    /// error is returned and the pending retrievals are aborted, so the inner results are all
+    /// `Ok`. With [BatchMode::CollectAll] the outer result is always `Ok` and carries the
+    /// result of every commitment.
+    pub async fn get_blobs(
+        &self,
+        commitments: &[Vec<u8>],
+        mode: BatchMode,
+    ) -> Result<Vec<Result<Vec<u8>, EigenDAProviderError>>, EigenDAProviderError> {
+        let mut tasks = JoinSet::new();
+        for (index, commitment) in commitments.iter().cloned().enumerate() {
+            let provider = self.clone();
+            tasks.spawn(async move { (index, provider.get_blob(&commitment).await) });
+        }
+
+        let mut results: Vec<Option<Result<Vec<u8>, EigenDAProviderError>>> =
+            (0..commitments.len()).map(|_| None).collect();
+        while let Some(joined) = tasks.join_next().await {
+            let (index, result) = joined.expect("blob retrieval task panicked");
+            match (mode, result) {
+                // dropping the join set aborts the pending retrievals
+                (BatchMode::FailFast, Err(e)) => return Err(e),
+                (_, result) => results[index] = Some(result),
+            }
+        }
+
+        Ok(results
+            .into_iter()
+            .map(|r| r.expect("every retrieval has completed"))
+            .collect())
+    }
+
+    /// Retrieves the blobs of the given commitments into the blob cache, e.g. at startup for a
+    /// predictable latency on a known set of commitments.
+    ///
+    /// The blobs are retrieved concurrently, the results being in the order of the
+    /// commitments. Without a blob cache, see [OnlineEigenDAProvider::with_blob_cache], nothing
+    /// is retrieved and every commitment fails.
+    pub async fn warm_cache(
+        &self,
+        commitments: &[Vec<u8>],
+    ) -> Vec<Result<(), EigenDAProviderError>> {
+        if self.blob_cache.is_none() {
+            return commitments
+                .iter()
+                .map(|_| {
+                    Err(EigenDAProviderError::Backend(
+                        "no blob cache to warm".to_string(),
+                    ))
+                })
+                .collect();
+        }
+
+        let results = self
+            .get_blobs(commitments, BatchMode::CollectAll)
             .await
-            .map_err(|e| EigenDAProviderError::RetrieveFramesFromDaIndexer(e.to_string()))
+            .expect("collecting all results never fails");
+        results
+            .into_iter()
+            .zip(commitments)
+            .map(|(result, commitment)| {
+                result.map(|_| ()).inspect_err(|e| {
+                    warn!(
+                        target: "eigenda-provider",
+                        "Failed to warm the cache with blob {}: {e}",
+                        hex::encode(commitment)
+                    );
+                })
+            })
+            .collect()
+    }
+
+    /// Blocking variant of [OnlineEigenDAProvider::get_blob] for synchronous callers.
+    ///
+    /// When called from inside a multi-threaded tokio runtime, the current worker is handed
+    /// over with [tokio::task::block_in_place] and the ambient runtime drives the request.
+    /// Otherwise a dedicated runtime is lazily created once and reused for all later calls.
+    ///
+    /// Nested runtime pitfalls:
+    /// - Calling this from a `current_thread` runtime (e.g. a plain `#[tokio::test]`) panics,
+    ///   because `block_in_place` needs another worker to take over the blocked one.
+    /// - Calling this from an async task stalls that worker until the blob is retrieved, so
+    ///   prefer the async [OnlineEigenDAProvider::get_blob] whenever an executor is available.
+    #[cfg(feature = "blocking")]
+    pub fn get_blob_blocking(&self, commitment: &[u8]) -> Result<Vec<u8>, EigenDAProviderError> {
+        use std::sync::OnceLock;
+        use tokio::runtime::{Builder, Handle, Runtime};
+
+        static BLOCKING_RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+        match Handle::try_current() {
+            Ok(handle) => {
+                tokio::task::block_in_place(|| handle.block_on(self.get_blob(commitment)))
+            }
+            Err(_) => {
+                let runtime = BLOCKING_RUNTIME.get_or_init(|| {
+                    Builder::new_multi_thread()
+                        .worker_threads(1)
+                        .enable_all()
+                        .build()
+                        .expect("blocking runtime builder failed")
+                });
+                runtime.block_on(self.get_blob(commitment))
+            }
+        }
+    }
+}
+
+/// Returns the cache key of the blob of a commitment, [None] if its cert can't be decoded.
+fn blob_cache_key(commitment: &[u8]) -> Option<BlobCacheKey> {
+    let commitment = BlobInfo::from_commitment(commitment)
+        .ok()?
+        .blob_header
+        .commitment;
+    let mut key = [0u8; 64];
+    key[..32].copy_from_slice(&commitment.x);
+    key[32..].copy_from_slice(&commitment.y);
+    Some(key)
+}
+
+/// Checks that the blob fits the size of the cert and has its KZG commitment.
+fn check_blob_commitment(cert: &BlobInfo, blob: &[u8]) -> Result<(), EigenDAProxyError> {
+    let encoded = EigenDABlobData::encode(blob, BLOB_ENCODING_VERSION_0)
+        .map_err(|e| EigenDAProxyError::CommitmentMismatch(e.to_string()))?
+        .blob;
+    let cert_size = cert.blob_header.data_length as usize * BYTES_PER_FIELD_ELEMENT;
+    if encoded.len() > cert_size {
+        return Err(EigenDAProxyError::CommitmentMismatch(format!(
+            "blob of {} encoded bytes exceeds the {cert_size} bytes of the cert",
+            encoded.len()
+        )));
+    }
+
+    let commitment = &cert.blob_header.commitment;
+    EigenDABlobWitness::new()
+        .push_witness(&encoded, &[commitment.x, commitment.y].concat())
+        .map_err(|e| EigenDAProxyError::CommitmentMismatch(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eigenda::{
+        capture::CaptureFilter,
+        test_utils::{MockProxy, MockResponse},
+    };
+
+    #[tokio::test]
+    async fn test_retrieve_empty_response() {
+        let proxy = MockProxy::with_response(MockResponse::ok(Vec::new()));
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5));
+
+        let result = client.retrieve_blob_with_commitment(&[0xab; 4]).await;
+        assert_eq!(result, Err(EigenDAProxyError::EmptyResponse));
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_chunked_response() {
+        let blob: Vec<u8> = (0..5000u32).map(|i| i as u8).collect();
+        let proxy = MockProxy::with_response(MockResponse {
+            chunked: true,
+            ..MockResponse::ok(blob.clone())
+        });
+
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5));
+        assert_eq!(
+            client.retrieve_blob_with_commitment(&[0xab; 4]).await,
+            Ok(blob.clone())
+        );
+
+        // without a content length the cap applies to the received bytes
+        let client = client.with_max_response_size(4096);
+        assert_eq!(
+            client.retrieve_blob_with_commitment(&[0xab; 4]).await,
+            Err(EigenDAProxyError::ResponseTooLarge(4096))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_requests_blob_payload() {
+        let blob = vec![0xde, 0xad, 0xbe, 0xef];
+        let cert = br#"{"blob_info":{}}"#.to_vec();
+        // serves the cert envelope unless the blob payload is asked for explicitly
+        let served = blob.clone();
+        let proxy = MockProxy::start(move |req| {
+            if req.header("accept") == Some(BLOB_CONTENT_TYPE) {
+                MockResponse {
+                    headers: vec![("Content-Type".into(), BLOB_CONTENT_TYPE.into())],
+                    ..MockResponse::ok(served.clone())
+                }
+            } else {
+                MockResponse {
+                    headers: vec![("Content-Type".into(), "application/json".into())],
+                    ..MockResponse::ok(cert.clone())
+                }
+            }
+        });
+
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5));
+        assert_eq!(
+            client.retrieve_blob_with_commitment(&[0xab; 4]).await,
+            Ok(blob)
+        );
+        assert_eq!(
+            proxy.requests()[0].header("accept"),
+            Some(BLOB_CONTENT_TYPE)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_rejects_cert_envelope() {
+        let proxy = MockProxy::with_response(MockResponse {
+            headers: vec![(
+                "Content-Type".into(),
+                "application/json; charset=utf-8".into(),
+            )],
+            ..MockResponse::ok(br#"{"blob_info":{}}"#.to_vec())
+        });
+
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5));
+        assert_eq!(
+            client.retrieve_blob_with_commitment(&[0xab; 4]).await,
+            Err(EigenDAProxyError::UnexpectedContentType(
+                "application/json; charset=utf-8".to_string()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_accepts_non_json_content_types() {
+        for content_type in [None, Some("text/plain"), Some("application/x-binary")] {
+            let proxy = MockProxy::with_response(MockResponse {
+                headers: content_type
+                    .map(|content_type| ("Content-Type".into(), content_type.into()))
+                    .into_iter()
+                    .collect(),
+                ..MockResponse::ok(vec![0xde, 0xad])
+            });
+            let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5));
+            assert_eq!(
+                client.retrieve_blob_with_commitment(&[0xab; 4]).await,
+                Ok(vec![0xde, 0xad]),
+                "{content_type:?}"
+            );
+        }
+
+        let proxy = MockProxy::with_response(MockResponse {
+            headers: vec![("Content-Type".into(), "application/problem+json".into())],
+            ..MockResponse::ok(br#"{"title":"not found"}"#.to_vec())
+        });
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5));
+        assert!(matches!(
+            client.retrieve_blob_with_commitment(&[0xab; 4]).await,
+            Err(EigenDAProxyError::UnexpectedContentType(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_disperse_and_retrieve() {
+        // stores the dispersed blobs, committing to them by their index
+        let blobs = Mutex::new(Vec::<Vec<u8>>::new());
+        let proxy = MockProxy::start(move |req| {
+            let mut blobs = blobs.lock().unwrap();
+            match (req.method.as_str(), req.path.strip_prefix("/get/0x0100")) {
+                ("POST", _) if req.path == "/put" => {
+                    blobs.push(req.body.clone());
+                    MockResponse::ok(vec![0x01, 0x00, blobs.len() as u8 - 1])
+                }
+                ("GET", Some(index)) => {
+                    match blobs.get(usize::from_str_radix(index, 16).unwrap()) {
+                        Some(blob) => MockResponse::ok(blob.clone()),
+                        None => MockResponse::new(404, Vec::new()),
+                    }
+                }
+                _ => MockResponse::new(400, Vec::new()),
+            }
+        });
+
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5));
+        let blob = vec![0xde, 0xad, 0xbe, 0xef];
+        let commitment = client.disperse_blob(&blob).await.unwrap();
+        assert_eq!(commitment, [0x01, 0x00, 0x00]);
+        assert_eq!(
+            client.retrieve_blob_with_commitment(&commitment).await,
+            Ok(blob)
+        );
+
+        let put = &proxy.requests()[0];
+        assert_eq!(put.header("content-type"), Some(BLOB_CONTENT_TYPE));
+        assert_eq!(put.body, [0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    #[tokio::test]
+    async fn test_disperse_accepted_without_commitment() {
+        let proxy = MockProxy::with_response(MockResponse::new(202, Vec::new()));
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5));
+        assert_eq!(
+            client.disperse_blob(&[1]).await,
+            Err(EigenDAProxyError::DispersalPending)
+        );
+
+        // the commitment may already come with the 202
+        let proxy = MockProxy::with_response(MockResponse::new(202, vec![0x01, 0x00, 0x07]));
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5));
+        assert_eq!(client.disperse_blob(&[1]).await, Ok(vec![0x01, 0x00, 0x07]));
+
+        let proxy = MockProxy::with_response(MockResponse::new(500, Vec::new()));
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5));
+        assert_eq!(
+            client.disperse_blob(&[1]).await,
+            Err(EigenDAProxyError::HttpStatus(500))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disperse_oversized_commitment() {
+        let commitment = vec![0x01; DEFAULT_MAX_COMMITMENT_LENGTH + 1];
+        let proxy = MockProxy::with_response(MockResponse::ok(commitment));
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5));
+        assert_eq!(
+            client.disperse_blob(&[1]).await,
+            Err(EigenDAProxyError::ResponseTooLarge(
+                DEFAULT_MAX_COMMITMENT_LENGTH
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disperse_read_back_mismatch() {
+        let proxy = MockProxy::start(|req| match req.method.as_str() {
+            "POST" => MockResponse::ok(vec![0x01, 0x00, 0x07]),
+            _ => MockResponse::ok(vec![0xde, 0xad]),
+        });
+
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5));
+        assert_eq!(
+            client.disperse_blob(&[0xde, 0xad]).await,
+            Ok(vec![0x01, 0x00, 0x07])
+        );
+        assert_eq!(
+            client.disperse_blob(&[0xbe, 0xef]).await,
+            Ok(vec![0x01, 0x00, 0x07])
+        );
+        // no blob is read back unless asked to
+        assert_eq!(proxy.requests().len(), 2);
+
+        let client = client.with_dispersal_verification();
+        assert_eq!(
+            client.disperse_blob(&[0xde, 0xad]).await,
+            Ok(vec![0x01, 0x00, 0x07])
+        );
+        assert_eq!(
+            client.disperse_blob(&[0xbe, 0xef]).await,
+            Err(EigenDAProxyError::DispersalMismatch)
+        );
+        assert_eq!(proxy.requests()[3].path, "/get/0x010007");
+    }
+
+    #[tokio::test]
+    async fn test_disperse_timeout() {
+        let proxy = MockProxy::with_response(MockResponse {
+            delay: Some(Duration::from_millis(500)),
+            ..MockResponse::ok(vec![0x01, 0x00, 0x07])
+        });
+
+        // the dispersal is not bound by the retrieve timeout
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_millis(100))
+            .with_disperse_timeout(Duration::from_secs(5));
+        assert_eq!(client.disperse_blob(&[1]).await, Ok(vec![0x01, 0x00, 0x07]));
+
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5))
+            .with_disperse_timeout(Duration::from_millis(100));
+        let err = client.disperse_blob(&[1]).await.unwrap_err();
+        assert!(matches!(err, EigenDAProxyError::NetworkError(_)), "{err}");
+    }
+
+    /// A proxy answering `404` to the first `misses` requests.
+    fn late_proxy(misses: usize) -> MockProxy {
+        let requests = AtomicUsize::new(0);
+        MockProxy::start(move |_| {
+            if requests.fetch_add(1, Ordering::SeqCst) < misses {
+                MockResponse::new(404, Vec::new())
+            } else {
+                MockResponse::ok(vec![1u8, 2, 3])
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_long_poll_not_yet_available_blob() {
+        let long_poll = LongPoll {
+            deadline: Duration::from_secs(5),
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+        };
+
+        // without long poll the first 404 is final
+        let proxy = late_proxy(2);
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5));
+        let result = client.retrieve_blob_with_commitment(&[0xab; 4]).await;
+        assert_eq!(result, Err(EigenDAProxyError::NotFound));
+
+        let proxy = late_proxy(2);
+        let client =
+            EigenDAProxy::new(proxy.url(), Duration::from_secs(5)).with_long_poll(long_poll);
+        assert_eq!(
+            client.retrieve_blob_with_commitment(&[0xab; 4]).await,
+            Ok(vec![1u8, 2, 3])
+        );
+        assert_eq!(proxy.requests().len(), 3);
+
+        // the deadline bounds the polling
+        let proxy = late_proxy(usize::MAX);
+        let client =
+            EigenDAProxy::new(proxy.url(), Duration::from_secs(5)).with_long_poll(LongPoll {
+                deadline: Duration::from_millis(100),
+                ..long_poll
+            });
+        let result = client.retrieve_blob_with_commitment(&[0xab; 4]).await;
+        assert_eq!(result, Err(EigenDAProxyError::NotFound));
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_errors() {
+        let retry = RetryPolicy {
+            initial_backoff: Duration::from_millis(10),
+            ..RetryPolicy::new(3)
+        };
+        // a proxy failing with `503` twice
+        let unavailable = || {
+            let requests = AtomicUsize::new(0);
+            MockProxy::start(move |_| {
+                if requests.fetch_add(1, Ordering::SeqCst) < 2 {
+                    MockResponse::new(503, Vec::new())
+                } else {
+                    MockResponse::ok(vec![1u8, 2, 3])
+                }
+            })
+        };
+
+        let proxy = unavailable();
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5)).with_retry(retry);
+        assert_eq!(
+            client.retrieve_blob_with_commitment(&[0xab; 4]).await,
+            Ok(vec![1u8, 2, 3])
+        );
+        assert_eq!(proxy.requests().len(), 3);
+
+        // the attempts are bounded
+        let proxy = unavailable();
+        let client =
+            EigenDAProxy::new(proxy.url(), Duration::from_secs(5)).with_retry(RetryPolicy {
+                max_attempts: 2,
+                ..retry
+            });
+        let result = client.retrieve_blob_with_commitment(&[0xab; 4]).await;
+        assert_eq!(result, Err(EigenDAProxyError::HttpStatus(503)));
+        assert_eq!(proxy.requests().len(), 2);
+
+        // a missing blob is not retried
+        let proxy = late_proxy(1);
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5)).with_retry(retry);
+        let result = client.retrieve_blob_with_commitment(&[0xab; 4]).await;
+        assert_eq!(result, Err(EigenDAProxyError::NotFound));
+        assert_eq!(proxy.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_bounded_by_retrieve_timeout() {
+        let proxy = MockProxy::with_response(MockResponse::new(503, Vec::new()));
+        let retry = RetryPolicy {
+            initial_backoff: Duration::from_secs(10),
+            ..RetryPolicy::new(5)
+        };
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_millis(500)).with_retry(retry);
+        // each attempt gets a share of the retrieve timeout
+        assert_eq!(client.attempt_timeout(), Duration::from_millis(100));
+
+        let started = Instant::now();
+        let result = client.retrieve_blob_with_commitment(&[0xab; 4]).await;
+        assert!(started.elapsed() < Duration::from_secs(2));
+        // the status of the last attempt is preserved
+        assert_eq!(
+            result,
+            Err(EigenDAProxyError::DeadlineExceeded(
+                "retrieval exceeded 500ms, last error: EigenDA proxy responded with status 503"
+                    .to_string()
+            ))
+        );
+        assert_eq!(proxy.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_attempt_timeout_vs_deadline_exceeded() {
+        let retry = RetryPolicy {
+            initial_backoff: Duration::from_millis(10),
+            ..RetryPolicy::new(2)
+        };
+        let slow = || MockResponse {
+            delay: Some(Duration::from_millis(800)),
+            ..MockResponse::ok(vec![1u8, 2, 3])
+        };
+
+        // a slow attempt times out and is retried within the deadline
+        let requests = AtomicUsize::new(0);
+        let proxy = MockProxy::start(move |_| {
+            if requests.fetch_add(1, Ordering::SeqCst) == 0 {
+                slow()
+            } else {
+                MockResponse::ok(vec![1u8, 2, 3])
+            }
+        });
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(1)).with_retry(retry);
+        assert_eq!(client.attempt_timeout(), Duration::from_millis(500));
+        assert_eq!(
+            client.retrieve_blob_with_commitment(&[0xab; 4]).await,
+            Ok(vec![1u8, 2, 3])
+        );
+        assert_eq!(proxy.requests().len(), 2);
+
+        // repeated timeouts exhaust the deadline
+        let proxy = MockProxy::with_response(slow());
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(1)).with_retry(retry);
+        let result = client.retrieve_blob_with_commitment(&[0xab; 4]).await;
+        assert_eq!(
+            result,
+            Err(EigenDAProxyError::DeadlineExceeded(
+                "retrieval exceeded 1s, last error: EigenDA proxy request attempt timed out after \
+                 500ms"
+                    .to_string()
+            ))
+        );
+        assert_eq!(proxy.requests().len(), 2);
+    }
+
+    fn batch_proxy() -> MockProxy {
+        // commitments starting with 0xff are unknown to the proxy
+        MockProxy::start(|req| {
+            if req.path.starts_with("/get/0xff") {
+                MockResponse::new(404, Vec::new())
+            } else {
+                MockResponse::ok(req.path.as_bytes().to_vec())
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_get_blobs_fail_fast() {
+        let proxy = batch_proxy();
+        let provider =
+            OnlineEigenDAProvider::new(EigenDAProxy::new(proxy.url(), Duration::from_secs(5)));
+
+        let commitments = vec![vec![0x01; 4], vec![0x02; 4], vec![0x03; 4]];
+        let results = provider
+            .get_blobs(&commitments, BatchMode::FailFast)
+            .await
+            .unwrap();
+        let blobs: Vec<Vec<u8>> = results.into_iter().map(Result::unwrap).collect();
+        assert_eq!(
+            blobs,
+            vec![
+                b"/get/0x01010101".to_vec(),
+                b"/get/0x02020202".to_vec(),
+                b"/get/0x03030303".to_vec(),
+            ]
+        );
+
+        let commitments = vec![vec![0x01; 4], vec![0xff; 4], vec![0x03; 4]];
+        let result = provider.get_blobs(&commitments, BatchMode::FailFast).await;
+        assert!(matches!(
+            result,
+            Err(EigenDAProviderError::RetrieveFramesFromDaIndexer(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_blobs_collect_all() {
+        let proxy = batch_proxy();
+        let provider =
+            OnlineEigenDAProvider::new(EigenDAProxy::new(proxy.url(), Duration::from_secs(5)));
+
+        let commitments = vec![
+            vec![0xff; 4],
+            vec![0x02; 4],
+            vec![0xff, 0x01],
+            vec![0x03; 4],
+        ];
+        let results = provider
+            .get_blobs(&commitments, BatchMode::CollectAll)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 4);
+        assert!(results[0].is_err());
+        assert_eq!(results[1], Ok(b"/get/0x02020202".to_vec()));
+        assert!(results[2].is_err());
+        assert_eq!(results[3], Ok(b"/get/0x03030303".to_vec()));
+
+        assert!(provider
+            .get_blobs(&[], BatchMode::CollectAll)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failover_between_proxies() {
+        let unavailable = MockProxy::with_response(MockResponse::new(503, Vec::new()));
+        let pruned = MockProxy::with_response(MockResponse::new(404, Vec::new()));
+        let archive = MockProxy::with_response(MockResponse::ok(vec![1u8, 2, 3]));
+        let client = |proxy: &MockProxy| EigenDAProxy::new(proxy.url(), Duration::from_secs(5));
+        let provider = OnlineEigenDAProvider::new(client(&unavailable))
+            .with_fallback_proxy(client(&pruned))
+            .with_fallback_proxy(client(&archive));
+
+        // a missing blob is retrieved from the next proxy as well
+        assert_eq!(
+            provider.get_blob(&[0xab; 4]).await.unwrap(),
+            vec![1u8, 2, 3]
+        );
+        assert_eq!(unavailable.requests().len(), 1);
+        assert_eq!(pruned.requests().len(), 1);
+        assert_eq!(archive.requests().len(), 1);
+
+        // the proxy which served the last blob is tried first, by clones as well
+        assert_eq!(
+            provider.clone().get_blob(&[0xcd; 4]).await.unwrap(),
+            vec![1u8, 2, 3]
+        );
+        assert_eq!(unavailable.requests().len(), 1);
+        assert_eq!(archive.requests().len(), 2);
+
+        // the errors of all proxies are reported
+        let provider =
+            OnlineEigenDAProvider::new(client(&unavailable)).with_fallback_proxy(client(&pruned));
+        let err = provider.get_blob(&[0xab; 4]).await.unwrap_err().to_string();
+        let status = format!(
+            "{}: EigenDA proxy responded with status 503",
+            unavailable.url()
+        );
+        assert!(err.contains(&status), "{err}");
+        assert!(err.contains(&format!("{}: ", pruned.url())), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_warm_cache() {
+        let proxy = batch_proxy();
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5));
+        let provider = OnlineEigenDAProvider::new(client.clone())
+            .with_blob_cache(NonZeroUsize::new(4).unwrap());
+
+        let mut certs = [BlobInfo::default(), BlobInfo::default()];
+        certs[0].blob_header.commitment.x = [0x11; 32];
+        certs[1].blob_header.commitment.x = [0x22; 32];
+        let commitments: Vec<Vec<u8>> = certs
+            .iter()
+            .map(|cert| cert_commitment([0x01, 0x00, 0x00], cert))
+            .collect();
+        let unknown = vec![0xff; 4];
+
+        let results = provider
+            .warm_cache(&[commitments.clone(), vec![unknown]].concat())
+            .await;
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok() && results[1].is_ok());
+        assert!(results[2].is_err());
+        assert_eq!(proxy.requests().len(), 3);
+
+        // the warmed blobs are served from the cache
+        for commitment in &commitments {
+            let blob = provider.get_blob(commitment).await.unwrap();
+            assert_eq!(
+                blob,
+                format!("/get/0x{}", hex::encode(commitment)).into_bytes()
+            );
+        }
+        assert_eq!(proxy.requests().len(), 3);
+
+        // nothing is retrieved without a cache
+        let results = OnlineEigenDAProvider::new(client)
+            .warm_cache(&commitments)
+            .await;
+        assert!(results.iter().all(Result::is_err));
+        assert_eq!(proxy.requests().len(), 3);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_get_blob_blocking_without_runtime() {
+        let proxy = MockProxy::with_response(MockResponse::ok(vec![1u8, 2, 3]));
+        let provider =
+            OnlineEigenDAProvider::new(EigenDAProxy::new(proxy.url(), Duration::from_secs(5)));
+
+        let blob = provider.get_blob_blocking(&[0xab; 4]).unwrap();
+        assert_eq!(blob, vec![1u8, 2, 3]);
+        // the dedicated runtime is reused by subsequent calls
+        let blob = provider.get_blob_blocking(&[0xab; 4]).unwrap();
+        assert_eq!(blob, vec![1u8, 2, 3]);
+
+        let requests = proxy.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].path, "/get/0xabababab");
+    }
+
+    /// Encodes the data as a zlib stream of a single stored deflate block.
+    #[cfg(feature = "compression")]
+    fn zlib_stored(data: &[u8]) -> Vec<u8> {
+        let len = data.len() as u16;
+        let mut out = vec![0x78, 0x01, 0x01];
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(data);
+        let (a, b) = data.iter().fold((1u32, 0u32), |(a, b), &byte| {
+            let a = (a + byte as u32) % 65521;
+            (a, (b + a) % 65521)
+        });
+        out.extend(((b << 16) | a).to_be_bytes());
+        out
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn test_compressed_response_decompressed() {
+        let blob: Vec<u8> = (0..1024u32).map(|i| i as u8).collect();
+        let proxy = MockProxy::with_response(MockResponse {
+            headers: vec![("Content-Encoding".into(), "deflate".into())],
+            ..MockResponse::ok(zlib_stored(&blob))
+        });
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5))
+            .with_max_response_size(blob.len());
+
+        assert_eq!(
+            client.retrieve_blob_with_commitment(&[0xab; 4]).await,
+            Ok(blob.clone())
+        );
+        let requests = proxy.requests();
+        let encodings = requests[0].header("accept-encoding").unwrap();
+        assert!(
+            encodings.contains("gzip") && encodings.contains("deflate"),
+            "{encodings}"
+        );
+
+        // the size limit applies to the decompressed blob
+        let client = client.with_max_response_size(blob.len() - 1);
+        assert_eq!(
+            client.retrieve_blob_with_commitment(&[0xab; 4]).await,
+            Err(EigenDAProxyError::ResponseTooLarge(blob.len() - 1))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_blob_commitment_too_large() {
+        let proxy = MockProxy::with_response(MockResponse::ok(vec![1u8]));
+        let provider =
+            OnlineEigenDAProvider::new(EigenDAProxy::new(proxy.url(), Duration::from_secs(5)))
+                .with_max_commitment_length(64);
+
+        let err = provider.get_blob(&[0x01; 65]).await.unwrap_err();
+        assert_eq!(
+            err,
+            EigenDAProviderError::CommitmentTooLarge {
+                length: 65,
+                max: 64
+            }
+        );
+        assert!(proxy.requests().is_empty());
+
+        assert_eq!(provider.get_blob(&[0x01; 64]).await.unwrap(), vec![1u8]);
+    }
+
+    fn cert_commitment(prefix: [u8; 3], cert: &BlobInfo) -> Vec<u8> {
+        [prefix.as_slice(), &alloy_rlp::encode(cert)].concat()
+    }
+
+    #[tokio::test]
+    async fn test_blob_cache_shared_across_commitment_encodings() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let proxy = MockProxy::start(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            MockResponse::ok(vec![0xab; 8])
+        });
+        let provider =
+            OnlineEigenDAProvider::new(EigenDAProxy::new(proxy.url(), Duration::from_secs(5)))
+                .with_blob_cache(NonZeroUsize::new(4).unwrap());
+
+        let mut cert = BlobInfo::default();
+        cert.blob_header.commitment.x = [0x11; 32];
+        cert.blob_header.commitment.y = [0x22; 32];
+        let legacy = cert_commitment([0x01, 0x00, 0x00], &cert);
+        let current = cert_commitment([0x01, 0x01, 0x00], &cert);
+
+        assert_eq!(provider.get_blob(&legacy).await.unwrap(), vec![0xab; 8]);
+        assert_eq!(provider.get_blob(&current).await.unwrap(), vec![0xab; 8]);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        // a different cert is fetched
+        cert.blob_header.commitment.y = [0x33; 32];
+        provider
+            .get_blob(&cert_commitment([0x01, 0x00, 0x00], &cert))
+            .await
+            .unwrap();
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_commitment_mismatch() {
+        // a blob far larger than the single field element of the cert
+        let proxy = MockProxy::with_response(MockResponse::ok(vec![0xab; 1024]));
+        let provider =
+            OnlineEigenDAProvider::new(EigenDAProxy::new(proxy.url(), Duration::from_secs(5)))
+                .with_commitment_check()
+                .with_blob_cache(NonZeroUsize::new(4).unwrap());
+        let mut cert = BlobInfo::default();
+        cert.blob_header.data_length = 1;
+        let commitment = cert_commitment([0x01, 0x00, 0x00], &cert);
+
+        let err = provider
+            .get_blob(&commitment)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("not matching the requested commitment"),
+            "{err}"
+        );
+        assert!(err.contains("exceeds the 32 bytes of the cert"), "{err}");
+        // the mismatched blob is not cached
+        assert!(provider.get_blob(&commitment).await.is_err());
+        assert_eq!(proxy.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_capture_request() {
+        let proxy = MockProxy::start(|req| {
+            if req.path.starts_with("/get/0x02") {
+                MockResponse::new(404, Vec::new())
+            } else {
+                MockResponse {
+                    headers: vec![("Content-Type".into(), BLOB_CONTENT_TYPE.into())],
+                    ..MockResponse::ok(vec![0xab; 16])
+                }
+            }
+        });
+        let path = std::env::temp_dir().join(format!("hydro-capture-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let capture = RequestCapture::new(path.clone(), CaptureFilter::Commitment(vec![0x01; 4]));
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5)).with_capture(capture);
+
+        client
+            .retrieve_blob_with_commitment(&[0x01; 4])
+            .await
+            .unwrap();
+        client
+            .retrieve_blob_with_commitment(&[0x02; 4])
+            .await
+            .unwrap_err();
+
+        let captured = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines: Vec<serde_json::Value> = captured
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 1);
+        let summary = &lines[0];
+        assert_eq!(summary["method"], "GET");
+        assert_eq!(summary["url"], format!("{}/get/0x01010101", proxy.url()));
+        assert_eq!(summary["request_headers"][0][0], "accept");
+        assert_eq!(summary["request_headers"][0][1], BLOB_CONTENT_TYPE);
+        assert_eq!(summary["status"], 200);
+        assert_eq!(summary["response_size"], 16);
+        assert!(summary["error"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_auth_token() {
+        let proxy = MockProxy::start(|req| match req.header("authorization") {
+            Some("Bearer secret") => MockResponse::ok(vec![1u8, 2, 3]),
+            Some(_) => MockResponse::new(403, Vec::new()),
+            None => MockResponse::new(401, Vec::new()),
+        });
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5));
+        let result = client.retrieve_blob_with_commitment(&[0xab; 4]).await;
+        assert_eq!(result, Err(EigenDAProxyError::Unauthorized(401)));
+
+        let wrong = client
+            .clone()
+            .with_auth_token(&"wrong".parse().unwrap())
+            .unwrap();
+        let result = wrong.retrieve_blob_with_commitment(&[0xab; 4]).await;
+        assert_eq!(result, Err(EigenDAProxyError::Unauthorized(403)));
+
+        let token = "secret".parse().unwrap();
+        let client = client.with_auth_token(&token).unwrap();
+        assert_eq!(
+            client.retrieve_blob_with_commitment(&[0xab; 4]).await,
+            Ok(vec![1, 2, 3])
+        );
+        // the token is redacted when formatted
+        assert!(!format!("{token:?} {client:?}").contains("secret"));
+    }
+
+    #[tokio::test]
+    async fn test_capabilities() {
+        let proxy = MockProxy::start(|req| match req.path.as_str() {
+            "/capabilities" => MockResponse::ok(
+                r#"{"commitment_modes":["optimism_generic"],"max_blob_size":1024,
+                    "api_version":"v1"}"#,
+            ),
+            _ => MockResponse::ok("not json"),
+        });
+        let client = EigenDAProxy::new(proxy.url(), Duration::from_secs(5));
+        assert_eq!(
+            client.capabilities().await,
+            Ok(ProxyCapabilities {
+                commitment_modes: vec!["optimism_generic".to_string()],
+                max_blob_size: 1024,
+                api_version: "v1".to_string(),
+            })
+        );
+
+        let client = EigenDAProxy::new(format!("{}/other", proxy.url()), Duration::from_secs(5));
+        assert!(matches!(
+            client.capabilities().await,
+            Err(EigenDAProxyError::InvalidCapabilities(_))
+        ));
+    }
+
+    async fn version(body: &'static str) -> Result<ProxyVersion, EigenDAProxyError> {
+        let proxy = MockProxy::start(move |req| match req.path.as_str() {
+            "/version" => MockResponse::ok(body),
+            _ => MockResponse::new(404, Vec::new()),
+        });
+        EigenDAProxy::new(proxy.url(), Duration::from_secs(5))
+            .check_version()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_check_version() {
+        assert_eq!(
+            version(r#"{"version":"v1.6.2"}"#).await,
+            Ok(ProxyVersion::new(1, 6, 2))
+        );
+        assert_eq!(
+            version("1.4.0-rc.1\n").await,
+            Ok(ProxyVersion::new(1, 4, 0))
+        );
+        assert!(matches!(
+            version(r#"{"version":"v2.1.0"}"#).await,
+            Err(EigenDAProxyError::IncompatibleVersion(_))
+        ));
+        assert_eq!(
+            version("unknown").await,
+            Err(EigenDAProxyError::InvalidVersion("unknown".to_string()))
+        );
     }
 }
@@ -0,0 +1,133 @@
+//! The worker pool running the CPU-bound EigenDA blob reconstruction of the host.
+
+use core::fmt;
+use std::{io, sync::Arc};
+use tokio::{
+    runtime::{Builder, Runtime},
+    task::JoinError,
+};
+
+/// The pool on which the host reconstructs the preimages of EigenDA blobs.
+///
+/// Encoding blobs and computing their KZG witness is CPU-bound. With a dedicated pool of
+/// `threads` workers, at most that many blobs are reconstructed concurrently and the runtime
+/// serving the preimage server stays responsive. Without one, the work runs on the blocking
+/// pool of the ambient runtime.
+#[derive(Clone, Default)]
+pub struct DaWorkerPool {
+    runtime: Option<Arc<PoolRuntime>>,
+}
+
+impl fmt::Debug for DaWorkerPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DaWorkerPool")
+            .field("threads", &self.threads())
+            .finish()
+    }
+}
+
+impl DaWorkerPool {
+    /// Creates a dedicated pool of `threads` workers.
+    pub fn new(threads: usize) -> io::Result<Self> {
+        if threads == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the EigenDA worker pool needs at least one thread",
+            ));
+        }
+        let runtime = Builder::new_multi_thread()
+            .worker_threads(threads)
+            .thread_name("eigenda-worker")
+            .build()?;
+        Ok(Self {
+            runtime: Some(Arc::new(PoolRuntime {
+                runtime: Some(runtime),
+                threads,
+            })),
+        })
+    }
+
+    /// The number of workers of the dedicated pool, [None] if the ambient runtime is used.
+    pub fn threads(&self) -> Option<usize> {
+        self.runtime.as_ref().map(|pool| pool.threads)
+    }
+
+    /// Runs the CPU-bound function `f` on the pool and waits for its result.
+    pub async fn run<F, T>(&self, f: F) -> Result<T, JoinError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        match self.runtime.as_ref().and_then(|pool| pool.runtime.as_ref()) {
+            // running `f` as a task keeps it on one of the workers of the pool
+            Some(runtime) => runtime.spawn(async move { f() }).await,
+            None => tokio::task::spawn_blocking(f).await,
+        }
+    }
+}
+
+/// A dedicated runtime, shut down in the background when the last pool handle is dropped.
+struct PoolRuntime {
+    runtime: Option<Runtime>,
+    threads: usize,
+}
+
+impl Drop for PoolRuntime {
+    fn drop(&mut self) {
+        // dropping a runtime blocks, which panics from within an async context
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        thread,
+        time::Duration,
+    };
+    use tokio::task::JoinSet;
+
+    #[test]
+    fn test_zero_threads_rejected() {
+        assert!(DaWorkerPool::new(0).is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_pool_size_honored() {
+        let pool = DaWorkerPool::new(2).unwrap();
+        assert_eq!(pool.threads(), Some(2));
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let mut jobs = JoinSet::new();
+        for _ in 0..8 {
+            let pool = pool.clone();
+            let (running, max_running) = (running.clone(), max_running.clone());
+            jobs.spawn(async move {
+                pool.run(move || {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(50));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+                .await
+            });
+        }
+        while let Some(result) = jobs.join_next().await {
+            result.unwrap().unwrap();
+        }
+
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_ambient_runtime_fallback() {
+        let pool = DaWorkerPool::default();
+        assert_eq!(pool.threads(), None);
+        assert_eq!(pool.run(|| 21 * 2).await.unwrap(), 42);
+    }
+}
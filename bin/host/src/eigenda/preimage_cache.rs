@@ -0,0 +1,127 @@
+//! Contains the [CachingHostBackend], serving repeated reads of EigenDA preimages from memory.
+
+use alloy_primitives::B256;
+use async_trait::async_trait;
+use core::num::NonZeroUsize;
+use kona_preimage::{
+    errors::PreimageOracleResult, HintRouter, PreimageFetcher, PreimageKey, PreimageKeyType,
+};
+use lru::LruCache;
+use std::sync::Mutex;
+
+/// A host backend keeping the EigenDA preimages read through it in memory, so that repeated
+/// client reads of the same field elements do not reach the backend, e.g. its key-value store
+/// on disk, again.
+///
+/// Only the [GlobalGeneric](PreimageKeyType::GlobalGeneric) preimages, which the EigenDA
+/// preimages are, are cached, up to a number of them, evicting the least recently read one.
+/// Preimages never change once written, so the hints are routed to the backend without
+/// invalidating the cache.
+#[derive(Debug)]
+pub struct CachingHostBackend<B> {
+    inner: B,
+    cache: Mutex<LruCache<B256, Vec<u8>>>,
+}
+
+impl<B> CachingHostBackend<B> {
+    /// Creates a new [CachingHostBackend] over `inner`, caching up to `capacity` preimages.
+    pub fn new(inner: B, capacity: NonZeroUsize) -> Self {
+        Self {
+            inner,
+            cache: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+}
+
+#[async_trait]
+impl<B: HintRouter + Send + Sync> HintRouter for CachingHostBackend<B> {
+    async fn route_hint(&self, hint: String) -> PreimageOracleResult<()> {
+        self.inner.route_hint(hint).await
+    }
+}
+
+#[async_trait]
+impl<B: PreimageFetcher + Send + Sync> PreimageFetcher for CachingHostBackend<B> {
+    async fn get_preimage(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+        if key.key_type() != PreimageKeyType::GlobalGeneric {
+            return self.inner.get_preimage(key).await;
+        }
+        let cache_key: B256 = key.into();
+        if let Some(preimage) = self.cache.lock().unwrap().get(&cache_key) {
+            return Ok(preimage.clone());
+        }
+        let preimage = self.inner.get_preimage(key).await?;
+        self.cache.lock().unwrap().put(cache_key, preimage.clone());
+        Ok(preimage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kona_preimage::errors::PreimageOracleError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A backend serving a preimage for every key, counting the reads.
+    #[derive(Debug, Default)]
+    struct CountingBackend {
+        reads: AtomicUsize,
+        hints: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl HintRouter for CountingBackend {
+        async fn route_hint(&self, _hint: String) -> PreimageOracleResult<()> {
+            self.hints.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl PreimageFetcher for CountingBackend {
+        async fn get_preimage(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            if key == PreimageKey::new([0xff; 32], PreimageKeyType::GlobalGeneric) {
+                return Err(PreimageOracleError::KeyNotFound);
+            }
+            Ok(B256::from(key).to_vec())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repeated_reads_served_from_cache() {
+        let backend = CachingHostBackend::new(CountingBackend::default(), NonZeroUsize::MIN);
+        let reads = || backend.inner.reads.load(Ordering::SeqCst);
+
+        let key = PreimageKey::new([0x01; 32], PreimageKeyType::GlobalGeneric);
+        let expected = B256::from(key).to_vec();
+        assert_eq!(backend.get_preimage(key).await.unwrap(), expected);
+        assert_eq!(backend.get_preimage(key).await.unwrap(), expected);
+        assert_eq!(reads(), 1);
+
+        // hints are routed to the backend and keep the cache
+        backend
+            .route_hint("eigen-da-blob 0x00".to_string())
+            .await
+            .unwrap();
+        assert_eq!(backend.inner.hints.load(Ordering::SeqCst), 1);
+        assert_eq!(backend.get_preimage(key).await.unwrap(), expected);
+        assert_eq!(reads(), 1);
+
+        // other preimages are always read from the backend
+        let keccak_key = PreimageKey::new([0x01; 32], PreimageKeyType::Keccak256);
+        backend.get_preimage(keccak_key).await.unwrap();
+        backend.get_preimage(keccak_key).await.unwrap();
+        assert_eq!(reads(), 3);
+
+        // missing preimages are not cached, and the least recently read one is evicted
+        let missing = PreimageKey::new([0xff; 32], PreimageKeyType::GlobalGeneric);
+        assert!(backend.get_preimage(missing).await.is_err());
+        assert!(backend.get_preimage(missing).await.is_err());
+        assert_eq!(reads(), 5);
+        let other = PreimageKey::new([0x02; 32], PreimageKeyType::GlobalGeneric);
+        backend.get_preimage(other).await.unwrap();
+        backend.get_preimage(key).await.unwrap();
+        assert_eq!(reads(), 7);
+    }
+}
@@ -0,0 +1,952 @@
+//! Writes the preimages of an EigenDA blob to the key-value store.
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, Result};
+use hydro_eigenda::common::{
+    blob_complete_key, eigenda_field_element_key, kzg_commitment_key, kzg_opening_key, BlobInfo,
+    EigenDABlobData, KeyHasher, BLOB_ENCODING_VERSION_0, BYTES_PER_FIELD_ELEMENT,
+    POPULATION_PROGRESS_KEY_TAG,
+};
+use hydro_proofs::witness::{EigenDABlobWitness, WitnessError};
+use kona_host::{KeyValueStore, SharedKeyValueStore};
+use kona_preimage::{PreimageKey, PreimageKeyType};
+use std::{num::NonZeroUsize, sync::mpsc};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Ratio of the encoded blob size to the size implied by the cert above which the blob is
+/// reported as suspiciously close to the limit.
+const BLOB_SIZE_WARN_RATIO: f64 = 0.95;
+
+/// Number of preimages written between two updates of the population progress, see
+/// [write_preimages].
+const PROGRESS_INTERVAL: usize = 1024;
+
+/// The error of a blob which does not match its cert, e.g. exceeding the size it implies or
+/// committing to a different polynomial.
+///
+/// Unlike a failure of the proxy or the store, retrying cannot help, see
+/// [populate_preimages_or_sentinel].
+#[derive(Debug)]
+pub(crate) struct InvalidCert(String);
+
+impl std::fmt::Display for InvalidCert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EigenDA blob does not match its cert: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidCert {}
+
+/// Writes all preimages the client needs to reconstruct `blob` from its cert: the encoded
+/// field elements, the KZG opening at the Fiat-Shamir challenge, the KZG commitment and the
+/// completion marker, with keys derived by `H` as documented in [kzg_opening_key].
+///
+/// This is what the [EigenDAChainHintHandler](crate::eigenda::EigenDAChainHintHandler) does
+/// after fetching a blob, usable on its own to build fixtures without a live proxy. Unless
+/// `trust_proxy_commitment` is set, the commitment of the blob is recomputed and checked
+/// against the cert, which requires the KZG trusted setup.
+///
+/// The blob is checked before anything is written, and the completion marker is written
+/// last: a blob failing the checks writes nothing, and a failing write leaves the marker
+/// missing, which the client reads before any field element of the blob.
+pub fn populate_preimages<H: KeyHasher>(
+    kv: &mut dyn KeyValueStore,
+    cert_blob_info: &BlobInfo,
+    blob: &[u8],
+    trust_proxy_commitment: bool,
+) -> Result<()> {
+    // Proxy should return a cert whose data_length measured in symbol (i.e. 32 Bytes)
+    let blob_length = cert_blob_info.blob_header.data_length as u64;
+
+    let eigenda_blob = EigenDABlobData::encode(blob, BLOB_ENCODING_VERSION_0)?;
+
+    let ratio = blob_size_ratio(eigenda_blob.blob.len(), blob_length);
+    debug!(
+        target: "eigenda-preimages",
+        encoded_size = eigenda_blob.blob.len(),
+        cert_size = blob_length as usize * BYTES_PER_FIELD_ELEMENT,
+        ratio,
+        "EigenDA blob size relative to cert"
+    );
+    if is_near_size_limit(ratio) {
+        warn!(
+            target: "eigenda-preimages",
+            encoded_size = eigenda_blob.blob.len(),
+            cert_size = blob_length as usize * BYTES_PER_FIELD_ELEMENT,
+            ratio,
+            "EigenDA blob size is close to the size implied by the cert, check the blob encoding"
+        );
+    }
+
+    if eigenda_blob.blob.len() > blob_length as usize * BYTES_PER_FIELD_ELEMENT {
+        return Err(InvalidCert(format!(
+            "blob size ({}) exceeds expected size ({})",
+            eigenda_blob.blob.len(),
+            blob_length as usize * BYTES_PER_FIELD_ELEMENT
+        ))
+        .into());
+    }
+
+    // check the blob against the cert before writing any preimage
+    let witness = blob_witness(trust_proxy_commitment, cert_blob_info, &eigenda_blob.blob)?;
+    let opening = witness
+        .as_ref()
+        .map(|witness| kzg_opening(cert_blob_info, witness))
+        .transpose()?;
+
+    // Write all the field elements to the key-value store, under the keys of
+    // [eigenda_field_element_key].
+    let commitment = &cert_blob_info.blob_header.commitment;
+    for i in 0..blob_length {
+        let blob_key = eigenda_field_element_key(&commitment.x, &commitment.y, i);
+        let blob_key_hash = H::hash_key(blob_key.as_ref());
+
+        kv.set(
+            PreimageKey::new(*blob_key_hash, PreimageKeyType::Keccak256).into(),
+            blob_key.into(),
+        )?;
+
+        let start = (i as usize) << 5;
+        let end = start + 32;
+        let actual_end = eigenda_blob.blob.len().min(end);
+        let data_slice = if start >= eigenda_blob.blob.len() {
+            vec![0u8; 32]
+        } else {
+            let mut padded_data = vec![0u8; 32];
+            padded_data[..(actual_end - start)]
+                .copy_from_slice(&eigenda_blob.blob[start..actual_end]);
+            padded_data
+        };
+        kv.set(
+            PreimageKey::new(*blob_key_hash, PreimageKeyType::GlobalGeneric).into(),
+            data_slice.into(),
+        )?;
+    }
+
+    // the proof opens the blob polynomial at the Fiat-Shamir challenge, which the client
+    // derives from the blob and the commitment to find it
+    if let Some((opening_key, opening_value)) = opening {
+        let opening_key_hash = H::hash_key(opening_key.as_ref());
+        kv.set(
+            PreimageKey::new(*opening_key_hash, PreimageKeyType::Keccak256).into(),
+            opening_key.into(),
+        )?;
+        kv.set(
+            PreimageKey::new(*opening_key_hash, PreimageKeyType::GlobalGeneric).into(),
+            opening_value,
+        )?;
+    }
+
+    // the commitment recomputed from the field elements, if any, was checked to be the one of
+    // the cert, which the keys derive from and the client verifies the KZG opening against:
+    // the cert commitment is stored either way, so the stored commitment and the one the
+    // client checks are the same bytes
+    write_commitment_preimage::<H>(kv, cert_blob_info, cert_commitment(cert_blob_info))?;
+    write_completion_marker::<H>(kv, cert_blob_info)
+}
+
+/// Writes the preimages of a cert alone, the KZG commitment of its blob keyed as by
+/// [populate_preimages], for clients validating a cert without reading its blob.
+///
+/// The inclusion proof of the cert is verified first, an invalid cert writing nothing. The
+/// blob is neither retrieved nor reconstructed, so neither its field elements nor its KZG
+/// opening are written.
+pub fn populate_cert_preimages<H: KeyHasher>(
+    kv: &mut dyn KeyValueStore,
+    cert_blob_info: &BlobInfo,
+) -> Result<()> {
+    cert_blob_info
+        .verify_inclusion()
+        .map_err(|e| anyhow!("Invalid EigenDA cert: {e}"))?;
+    write_commitment_preimage::<H>(kv, cert_blob_info, cert_commitment(cert_blob_info))
+}
+
+/// Returns the commitment of the cert, its x and y coordinates.
+fn cert_commitment(cert_blob_info: &BlobInfo) -> Vec<u8> {
+    [
+        cert_blob_info.blob_header.commitment.x,
+        cert_blob_info.blob_header.commitment.y,
+    ]
+    .concat()
+}
+
+/// Writes `commitment` as the KZG commitment preimage of the cert, keyed by the
+/// [kzg_commitment_key] of the cert.
+fn write_commitment_preimage<H: KeyHasher>(
+    kv: &mut dyn KeyValueStore,
+    cert_blob_info: &BlobInfo,
+    commitment: Vec<u8>,
+) -> Result<()> {
+    let commitment_key = kzg_commitment_key(&cert_blob_info.blob_header.commitment);
+    let commitment_key_hash = H::hash_key(commitment_key.as_ref());
+
+    kv.set(
+        PreimageKey::new(*commitment_key_hash, PreimageKeyType::Keccak256).into(),
+        commitment_key.into(),
+    )?;
+
+    // proof to be done
+    kv.set(
+        PreimageKey::new(*commitment_key_hash, PreimageKeyType::GlobalGeneric).into(),
+        commitment.into(),
+    )?;
+
+    Ok(())
+}
+
+/// Writes the completion marker of the blob of the cert, its data length keyed by the
+/// [blob_complete_key] of the cert, once all other preimages of the blob are written.
+fn write_completion_marker<H: KeyHasher>(
+    kv: &mut dyn KeyValueStore,
+    cert_blob_info: &BlobInfo,
+) -> Result<()> {
+    let marker_key = blob_complete_key(&cert_blob_info.blob_header.commitment);
+    let marker_key_hash = H::hash_key(marker_key.as_ref());
+    let data_length = cert_blob_info.blob_header.data_length as u64;
+
+    kv.set(
+        PreimageKey::new(*marker_key_hash, PreimageKeyType::Keccak256).into(),
+        marker_key.into(),
+    )?;
+    kv.set(
+        PreimageKey::new(*marker_key_hash, PreimageKeyType::GlobalGeneric).into(),
+        data_length.to_be_bytes().to_vec(),
+    )
+}
+
+/// Like [populate_preimages], but a blob which does not match its cert writes the invalid cert
+/// sentinel instead of failing, see [write_invalid_cert_sentinel].
+pub(crate) fn populate_preimages_or_sentinel<H: KeyHasher>(
+    kv: &mut dyn KeyValueStore,
+    cert_blob_info: &BlobInfo,
+    blob: &[u8],
+    trust_proxy_commitment: bool,
+) -> Result<()> {
+    match populate_preimages::<H>(kv, cert_blob_info, blob, trust_proxy_commitment) {
+        Err(e) if e.is::<InvalidCert>() => {
+            warn!(target: "eigenda-preimages", "{e}, writing the invalid cert sentinel");
+            write_invalid_cert_sentinel::<H>(kv, cert_blob_info)
+        }
+        populated => populated,
+    }
+}
+
+/// Writes an all-zero first field element for the cert, which the client reports as an
+/// invalid cert, followed by the completion marker.
+///
+/// Every encoded blob starts with a non-zero header, so the sentinel cannot be confused with
+/// the preimages of an actual blob.
+pub(crate) fn write_invalid_cert_sentinel<H: KeyHasher>(
+    kv: &mut dyn KeyValueStore,
+    cert_blob_info: &BlobInfo,
+) -> Result<()> {
+    let commitment = &cert_blob_info.blob_header.commitment;
+    let blob_key = eigenda_field_element_key(&commitment.x, &commitment.y, 0);
+    let blob_key_hash = H::hash_key(blob_key.as_ref());
+    kv.set(
+        PreimageKey::new(*blob_key_hash, PreimageKeyType::Keccak256).into(),
+        blob_key.into(),
+    )?;
+    kv.set(
+        PreimageKey::new(*blob_key_hash, PreimageKeyType::GlobalGeneric).into(),
+        vec![0u8; BYTES_PER_FIELD_ELEMENT],
+    )?;
+    write_completion_marker::<H>(kv, cert_blob_info)
+}
+
+/// A [KeyValueStore] collecting the preimages written to it, in write order.
+///
+/// Lets the preimages of a blob be computed away from the shared store, which is then only
+/// locked to copy them over.
+#[derive(Debug, Default)]
+pub(crate) struct PreimageBatch {
+    written: Vec<(B256, Vec<u8>)>,
+}
+
+impl PreimageBatch {
+    /// Returns the preimages written so far, in write order.
+    pub(crate) fn into_written(self) -> Vec<(B256, Vec<u8>)> {
+        self.written
+    }
+}
+
+impl KeyValueStore for PreimageBatch {
+    fn get(&self, key: B256) -> Option<Vec<u8>> {
+        self.written
+            .iter()
+            .rev()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.clone())
+    }
+
+    fn set(&mut self, key: B256, value: Vec<u8>) -> Result<()> {
+        self.written.push((key, value));
+        Ok(())
+    }
+}
+
+/// Returns the key under which the progress of the population of the blob of the cert is
+/// recorded, see [write_preimages].
+pub(crate) fn progress_key<H: KeyHasher>(cert_blob_info: &BlobInfo) -> B256 {
+    let mut key = [0u8; 65];
+    key[..64].copy_from_slice(&cert_commitment(cert_blob_info));
+    key[64] = POPULATION_PROGRESS_KEY_TAG;
+    PreimageKey::new(*H::hash_key(&key), PreimageKeyType::GlobalGeneric).into()
+}
+
+/// Writes the preimages to the given store, in order.
+///
+/// Preimages staged by [populate_preimages] end with the completion marker of the blob, so if
+/// a write fails, the marker is missing and the client does not read the partial blob.
+///
+/// A preimage already stored with different data fails the write instead of overwriting it:
+/// the keys of two blobs colliding means the key derivation cannot tell them apart, e.g. two
+/// certs sharing the commitment coordinates the keys are derived from.
+///
+/// With a `progress` key, the number of preimages written is recorded under it every
+/// [PROGRESS_INTERVAL] preimages and once all are written. A population interrupted e.g. by a
+/// crash of a host with a persistent store then resumes from the recorded progress: the
+/// preimages before it are only checked to be stored, and written again if missing.
+pub(crate) fn write_preimages(
+    kv: &mut dyn KeyValueStore,
+    progress: Option<B256>,
+    preimages: impl IntoIterator<Item = (B256, Vec<u8>)>,
+) -> Result<()> {
+    let resumed = progress.map_or(0, |progress| recorded_progress(kv, progress));
+    if resumed > 0 {
+        info!(target: "eigenda-preimages", "Resuming preimage writes after {resumed} preimages");
+    }
+
+    let mut count = 0;
+    for (written, (key, value)) in preimages.into_iter().enumerate() {
+        count = written + 1;
+        let stored = kv.get(key);
+        if stored.as_ref().is_some_and(|stored| *stored != value) {
+            warn!(
+                target: "eigenda-preimages",
+                "Preimage key collision after {written} preimages, the blob is incomplete"
+            );
+            return Err(anyhow!(
+                "Preimage {key} is already stored with different data"
+            ));
+        }
+        if written < resumed && stored.is_some() {
+            continue;
+        }
+        kv.set(key, value).map_err(|e| {
+            warn!(
+                target: "eigenda-preimages",
+                "Preimage write failed after {written} preimages, the blob is incomplete"
+            );
+            anyhow!("Failed to write preimage {key}: {e}")
+        })?;
+        if let Some(progress) = progress.filter(|_| count % PROGRESS_INTERVAL == 0) {
+            record_progress(kv, progress, count)?;
+        }
+    }
+    match progress {
+        Some(progress) if count > resumed => record_progress(kv, progress, count),
+        _ => Ok(()),
+    }
+}
+
+/// Returns the number of preimages recorded under `progress` as written, 0 if none is.
+fn recorded_progress(kv: &dyn KeyValueStore, progress: B256) -> usize {
+    kv.get(progress)
+        .and_then(|count| <[u8; 8]>::try_from(count).ok())
+        .map_or(0, |count| u64::from_be_bytes(count) as usize)
+}
+
+/// Records under `progress` that `count` preimages are written.
+fn record_progress(kv: &mut dyn KeyValueStore, progress: B256, count: usize) -> Result<()> {
+    kv.set(progress, (count as u64).to_be_bytes().to_vec())
+        .map_err(|e| anyhow!("Failed to record the preimage write progress: {e}"))
+}
+
+/// A [KeyValueStore] handing the preimages written to it over to the writer task spawned by
+/// [spawn_preimage_writer].
+///
+/// Writes block while the channel to the writer is full, so it must be used off the async
+/// executor, e.g. on the worker pool computing the preimages.
+#[derive(Debug)]
+pub(crate) struct PreimageSender(mpsc::SyncSender<(B256, Vec<u8>)>);
+
+impl KeyValueStore for PreimageSender {
+    fn get(&self, _key: B256) -> Option<Vec<u8>> {
+        None
+    }
+
+    fn set(&mut self, key: B256, value: Vec<u8>) -> Result<()> {
+        self.0
+            .send((key, value))
+            .map_err(|_| anyhow!("EigenDA preimage writer stopped"))
+    }
+}
+
+/// Spawns a task writing the preimages sent through the returned [PreimageSender] to `kv`, in
+/// order, so that computing the next preimages overlaps with writing the previous ones.
+///
+/// At most `capacity` preimages are buffered between the two, bounding the memory of large
+/// blobs. The store is only locked once the first preimage arrives, and the task completes
+/// once the sender is dropped. A failing write stops the task and fails later sends, see
+/// [write_preimages], which records the progress under `progress` if any. With `keep_written`,
+/// the task returns the written preimages, e.g. to mirror them.
+pub(crate) fn spawn_preimage_writer(
+    kv: SharedKeyValueStore,
+    progress: Option<B256>,
+    capacity: NonZeroUsize,
+    keep_written: bool,
+) -> (PreimageSender, JoinHandle<Result<Vec<(B256, Vec<u8>)>>>) {
+    let (sender, receiver) = mpsc::sync_channel(capacity.get());
+    let writer = tokio::task::spawn_blocking(move || {
+        let mut receiver = receiver.into_iter().peekable();
+        let mut written = Vec::new();
+        if receiver.peek().is_none() {
+            return Ok(written);
+        }
+        let mut kv = kv.blocking_write();
+        let preimages = receiver.inspect(|preimage| {
+            if keep_written {
+                written.push(preimage.clone());
+            }
+        });
+        write_preimages(&mut *kv, progress, preimages)?;
+        Ok(written)
+    });
+    (PreimageSender(sender), writer)
+}
+
+/// Writes the preimages to the mirror store in the background, so that a standby host is
+/// warm on failover. Failures are logged rather than returned, the mirror is best effort.
+pub(crate) fn mirror_preimages(
+    mirror: SharedKeyValueStore,
+    preimages: Vec<(B256, Vec<u8>)>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut mirror = mirror.write().await;
+        for (key, value) in preimages {
+            if let Err(e) = mirror.set(key, value) {
+                warn!(target: "eigenda-preimages", "Failed to mirror preimage {key}: {e}");
+                return;
+            }
+        }
+    })
+}
+
+/// Returns the ratio of the encoded blob size to the size implied by the cert data length.
+fn blob_size_ratio(encoded_size: usize, data_length: u64) -> f64 {
+    let cert_size = data_length as usize * BYTES_PER_FIELD_ELEMENT;
+    if cert_size == 0 {
+        return f64::INFINITY;
+    }
+    encoded_size as f64 / cert_size as f64
+}
+
+/// Whether the blob size ratio is suspiciously close to, or at, the limit implied by the cert.
+fn is_near_size_limit(ratio: f64) -> bool {
+    ratio >= BLOB_SIZE_WARN_RATIO
+}
+
+/// Computes the witness of the encoded blob, whose commitment is checked to be the one of the
+/// cert.
+///
+/// Returns [None] without any KZG work when the proxy commitment is trusted.
+fn blob_witness(
+    trust_proxy_commitment: bool,
+    cert_blob_info: &BlobInfo,
+    blob: &[u8],
+) -> Result<Option<EigenDABlobWitness>> {
+    if trust_proxy_commitment {
+        return Ok(None);
+    }
+
+    let mut witness = EigenDABlobWitness::new();
+    witness
+        .push_witness(blob, &cert_commitment(cert_blob_info))
+        .map_err(|e| match e {
+            WitnessError::CommitmentMismatch { .. } => InvalidCert(e.to_string()).into(),
+            e => anyhow!("eigen da blob push witness error {e}"),
+        })?;
+    Ok(Some(witness))
+}
+
+/// Returns the key preimage and the value of the KZG opening of the witness, the evaluation at
+/// the challenge followed by the proof.
+fn kzg_opening(
+    cert_blob_info: &BlobInfo,
+    witness: &EigenDABlobWitness,
+) -> Result<([u8; 97], Vec<u8>)> {
+    let (Some(challenge), Some(evaluation), Some(proof)) = (
+        witness.challenges.last(),
+        witness.evaluations.last(),
+        witness.proofs.last(),
+    ) else {
+        return Err(anyhow!("witness contains no KZG opening"));
+    };
+    let challenge = B256::try_from(challenge.as_ref())
+        .map_err(|_| anyhow!("invalid KZG challenge of {} bytes", challenge.len()))?;
+    let key = kzg_opening_key(&cert_blob_info.blob_header.commitment, &challenge);
+    Ok((key, [evaluation.as_ref(), proof.as_ref()].concat()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eigenda::verify::StoreOracle;
+    use alloy_primitives::{keccak256, Bytes};
+    use alloy_rlp::Encodable;
+    use hydro_eigenda::{
+        common::{BlobHeader, G1Commitment, Keccak256Hasher, KZG_COMMITMENT_KEY_TAG},
+        derive::EigenDAProvider,
+    };
+    use hydro_oracle::{provider::OracleEigenDaProvider, EigenDAClientError};
+    use kona_host::MemoryKeyValueStore;
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+    use tokio::sync::RwLock;
+
+    fn test_cert() -> BlobInfo {
+        BlobInfo {
+            blob_header: BlobHeader {
+                commitment: G1Commitment {
+                    x: [0x11; 32],
+                    y: [0x22; 32],
+                },
+                data_length: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_blob_size_near_limit() {
+        // comfortably under the cert implied size
+        assert!(!is_near_size_limit(blob_size_ratio(32 * 50, 100)));
+        // within limits, but close to it
+        let ratio = blob_size_ratio(32 * 99, 100);
+        assert!(ratio <= 1.0);
+        assert!(is_near_size_limit(ratio));
+        // exactly at the limit
+        assert!(is_near_size_limit(blob_size_ratio(32 * 100, 100)));
+        assert!(is_near_size_limit(blob_size_ratio(32, 0)));
+    }
+
+    #[test]
+    fn test_trusted_proxy_commitment_skips_check() {
+        // the blob does not match the cert, but nothing is recomputed
+        let witness = blob_witness(true, &test_cert(), &[0xff; 32]).unwrap();
+        assert!(witness.is_none());
+    }
+
+    #[test]
+    fn test_stored_commitment_matches_recomputed() {
+        let cert = test_cert();
+        // the commitment the host recomputes from the field elements of a matching blob, which
+        // the witness requires to be exactly the one of the cert
+        let recomputed = [[0x11; 32], [0x22; 32]].concat();
+
+        let mut kv = MemoryKeyValueStore::new();
+        populate_preimages::<Keccak256Hasher>(&mut kv, &cert, &[], true).unwrap();
+        let mut commitment_key = [0u8; 65];
+        commitment_key[..64].copy_from_slice(&recomputed);
+        commitment_key[64] = KZG_COMMITMENT_KEY_TAG;
+        let hash = Keccak256Hasher::hash_key(&commitment_key);
+        let key = PreimageKey::new(*hash, PreimageKeyType::GlobalGeneric);
+        assert_eq!(kv.get(key.into()).unwrap(), recomputed);
+    }
+
+    #[test]
+    fn test_kzg_opening() {
+        let cert = test_cert();
+        let witness = EigenDABlobWitness {
+            proofs: vec![Bytes::from(vec![0x44; 64])],
+            challenges: vec![Bytes::from(vec![0x33; 32])],
+            evaluations: vec![Bytes::from(vec![0x55; 32])],
+            ..Default::default()
+        };
+        let (key, value) = kzg_opening(&cert, &witness).unwrap();
+        assert_eq!(key[..64], [[0x11; 32], [0x22; 32]].concat());
+        assert_eq!(key[64..96], [0x33; 32]);
+        assert_eq!(value, [vec![0x55; 32], vec![0x44; 64]].concat());
+
+        assert!(kzg_opening(&cert, &EigenDABlobWitness::new()).is_err());
+        let truncated = EigenDABlobWitness {
+            challenges: vec![Bytes::from(vec![0x33; 31])],
+            ..witness
+        };
+        assert!(kzg_opening(&cert, &truncated).is_err());
+    }
+
+    #[test]
+    fn test_populate_and_reconstruct() {
+        let blob = b"hello eigenda, this rollup data spans more than one field element".to_vec();
+        let encoded_len = EigenDABlobData::encode(&blob, BLOB_ENCODING_VERSION_0)
+            .unwrap()
+            .blob
+            .len();
+        let mut cert = test_cert();
+        cert.blob_header.data_length = encoded_len.div_ceil(BYTES_PER_FIELD_ELEMENT) as u32;
+
+        let mut kv = MemoryKeyValueStore::new();
+        populate_preimages::<Keccak256Hasher>(&mut kv, &cert, &blob, true).unwrap();
+
+        // read back every field element the way the client does
+        let commitment = &cert.blob_header.commitment;
+        let mut encoded = Vec::new();
+        for i in 0..cert.blob_header.data_length as u64 {
+            let blob_key = eigenda_field_element_key(&commitment.x, &commitment.y, i);
+            let hash = Keccak256Hasher::hash_key(&blob_key);
+            let key = PreimageKey::new(*hash, PreimageKeyType::GlobalGeneric);
+            encoded.extend(kv.get(key.into()).unwrap());
+        }
+        let decoded = EigenDABlobData::new(encoded.into()).decode().unwrap();
+        assert_eq!(decoded.as_ref(), blob.as_slice());
+
+        // the commitment is the one of the trusted cert
+        let mut commitment_key = [0u8; 65];
+        commitment_key[..32].copy_from_slice(&commitment.x);
+        commitment_key[32..64].copy_from_slice(&commitment.y);
+        let hash = Keccak256Hasher::hash_key(&commitment_key);
+        let key = PreimageKey::new(*hash, PreimageKeyType::GlobalGeneric);
+        assert_eq!(
+            kv.get(key.into()).unwrap(),
+            [[0x11; 32], [0x22; 32]].concat()
+        );
+    }
+
+    #[test]
+    fn test_expected_keys_written() {
+        let blob = vec![0xab; 100];
+        let encoded_len = EigenDABlobData::encode(&blob, BLOB_ENCODING_VERSION_0)
+            .unwrap()
+            .blob
+            .len();
+        let mut cert = test_cert();
+        cert.blob_header.data_length = encoded_len.div_ceil(BYTES_PER_FIELD_ELEMENT) as u32;
+
+        let mut batch = PreimageBatch::default();
+        populate_preimages::<Keccak256Hasher>(&mut batch, &cert, &blob, true).unwrap();
+        let written: Vec<B256> = batch
+            .into_written()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        let expected: Vec<B256> = hydro_oracle::expected_keys(&cert)
+            .into_iter()
+            .map(B256::from)
+            .collect();
+        assert_eq!(written, expected);
+    }
+
+    #[test]
+    fn test_populate_cert_preimages() {
+        let mut cert = test_cert();
+        // a single leaf tree, whose root is the leaf
+        cert.blob_verification_proof
+            .batch_medatada
+            .batch_header
+            .batch_root = keccak256(cert.blob_header.hash()).to_vec().into();
+
+        let mut batch = PreimageBatch::default();
+        populate_cert_preimages::<Keccak256Hasher>(&mut batch, &cert).unwrap();
+        // only the commitment is written
+        let written = batch.into_written();
+        assert_eq!(written.len(), 2);
+        let hash = Keccak256Hasher::hash_key(&kzg_commitment_key(&cert.blob_header.commitment));
+        let key = PreimageKey::new(*hash, PreimageKeyType::GlobalGeneric);
+        assert_eq!(written[1], (key.into(), [[0x11; 32], [0x22; 32]].concat()));
+
+        // a cert whose inclusion proof does not verify writes nothing
+        let mut batch = PreimageBatch::default();
+        assert!(populate_cert_preimages::<Keccak256Hasher>(&mut batch, &test_cert()).is_err());
+        assert!(batch.into_written().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_cert_sentinel() {
+        let blob = vec![0xab; 100];
+        // the cert is too short for the blob
+        let cert = test_cert();
+
+        let mut kv = MemoryKeyValueStore::new();
+        let err = populate_preimages::<Keccak256Hasher>(&mut kv, &cert, &blob, true).unwrap_err();
+        assert!(err.is::<InvalidCert>(), "{err}");
+
+        populate_preimages_or_sentinel::<Keccak256Hasher>(&mut kv, &cert, &blob, true).unwrap();
+        let commitment = &cert.blob_header.commitment;
+        let blob_key = eigenda_field_element_key(&commitment.x, &commitment.y, 0);
+        let hash = Keccak256Hasher::hash_key(&blob_key);
+        let key = PreimageKey::new(*hash, PreimageKeyType::GlobalGeneric);
+        assert_eq!(kv.get(key.into()).unwrap(), [0u8; 32]);
+
+        // a blob matching its cert writes its preimages
+        let mut batch = PreimageBatch::default();
+        let mut cert = test_cert();
+        cert.blob_header.data_length = 8;
+        populate_preimages_or_sentinel::<Keccak256Hasher>(&mut batch, &cert, &blob, true).unwrap();
+        assert_ne!(batch.get(key.into()).unwrap(), [0u8; 32]);
+    }
+
+    #[tokio::test]
+    async fn test_mirror_preimages() {
+        let blob = b"mirrored rollup data".to_vec();
+        let mut cert = test_cert();
+        cert.blob_header.data_length = 2;
+
+        let mut batch = PreimageBatch::default();
+        populate_preimages::<Keccak256Hasher>(&mut batch, &cert, &blob, true).unwrap();
+        let written = batch.into_written();
+        let mut primary = MemoryKeyValueStore::new();
+        write_preimages(&mut primary, None, written.clone()).unwrap();
+
+        let mirror: SharedKeyValueStore = Arc::new(RwLock::new(MemoryKeyValueStore::new()));
+        mirror_preimages(mirror.clone(), written.clone())
+            .await
+            .unwrap();
+
+        // two field elements, the commitment, the completion marker, and their key preimages
+        assert_eq!(written.len(), 8);
+        let mirror = mirror.read().await;
+        for (key, value) in written {
+            assert_eq!(mirror.get(key), Some(value.clone()));
+            assert_eq!(primary.get(key), Some(value));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pipelined_preimage_writes() {
+        let blob = b"pipelined rollup data spanning several field elements".to_vec();
+        let mut cert = test_cert();
+        cert.blob_header.data_length = 4;
+        let mut batch = PreimageBatch::default();
+        populate_preimages::<Keccak256Hasher>(&mut batch, &cert, &blob, true).unwrap();
+        let expected = batch.into_written();
+
+        let kv: SharedKeyValueStore = Arc::new(RwLock::new(MemoryKeyValueStore::new()));
+        let (mut sender, writer) =
+            spawn_preimage_writer(kv.clone(), None, NonZeroUsize::new(2).unwrap(), true);
+        tokio::task::spawn_blocking(move || {
+            populate_preimages::<Keccak256Hasher>(&mut sender, &cert, &blob, true)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(writer.await.unwrap().unwrap(), expected);
+        let kv = kv.read().await;
+        for (key, value) in expected {
+            assert_eq!(kv.get(key), Some(value));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pipelined_preimages_bounded() {
+        let capacity = 3;
+        let kv: SharedKeyValueStore = Arc::new(RwLock::new(MemoryKeyValueStore::new()));
+        let (mut sender, writer) = spawn_preimage_writer(
+            kv.clone(),
+            None,
+            NonZeroUsize::new(capacity).unwrap(),
+            false,
+        );
+
+        // stall the writer on the store lock
+        let lock = kv.write().await;
+        let sent = Arc::new(AtomicUsize::new(0));
+        let counter = sent.clone();
+        let producer = std::thread::spawn(move || {
+            for i in 0..20u8 {
+                sender.set(B256::repeat_byte(i), vec![i]).unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        // the buffered preimages and the one held by the writer
+        let in_flight = sent.load(Ordering::SeqCst);
+        assert!(
+            in_flight > 0 && in_flight <= capacity + 1,
+            "{in_flight} preimages in flight"
+        );
+
+        drop(lock);
+        producer.join().unwrap();
+        assert!(writer.await.unwrap().unwrap().is_empty());
+        assert_eq!(sent.load(Ordering::SeqCst), 20);
+        assert_eq!(kv.read().await.get(B256::repeat_byte(19)), Some(vec![19]));
+    }
+
+    #[tokio::test]
+    async fn test_pipelined_writer_failure() {
+        let kv: SharedKeyValueStore = Arc::new(RwLock::new(FailingStore {
+            inner: MemoryKeyValueStore::new(),
+            capacity: 1,
+        }));
+        let (mut sender, writer) =
+            spawn_preimage_writer(kv, None, NonZeroUsize::new(1).unwrap(), false);
+
+        let producer = std::thread::spawn(move || {
+            (0..4u8).try_for_each(|i| sender.set(B256::repeat_byte(i), vec![i]))
+        });
+        let err = writer.await.unwrap().unwrap_err();
+        assert!(
+            err.to_string().contains("Failed to write preimage"),
+            "{err}"
+        );
+        // the producer stops once the writer is gone
+        let err = producer.join().unwrap().unwrap_err();
+        assert_eq!(err.to_string(), "EigenDA preimage writer stopped");
+    }
+
+    /// A store failing every write after the first `capacity` ones.
+    struct FailingStore {
+        inner: MemoryKeyValueStore,
+        capacity: usize,
+    }
+
+    impl KeyValueStore for FailingStore {
+        fn get(&self, key: B256) -> Option<Vec<u8>> {
+            self.inner.get(key)
+        }
+
+        fn set(&mut self, key: B256, value: Vec<u8>) -> Result<()> {
+            anyhow::ensure!(self.capacity > 0, "store is full");
+            self.capacity -= 1;
+            self.inner.set(key, value)
+        }
+    }
+
+    #[test]
+    fn test_colliding_preimages_rejected() {
+        // two certs sharing the commitment coordinates of their keys, with different blobs
+        let mut cert = test_cert();
+        cert.blob_header.data_length = 3;
+        let mut other = cert.clone();
+        other.blob_header.data_length = 4;
+
+        let stage = |cert: &BlobInfo, blob: &[u8]| {
+            let mut batch = PreimageBatch::default();
+            populate_preimages::<Keccak256Hasher>(&mut batch, cert, blob, true).unwrap();
+            batch.into_written()
+        };
+        let first = stage(&cert, b"first blob");
+        let mut kv = MemoryKeyValueStore::new();
+        write_preimages(&mut kv, None, first.clone()).unwrap();
+        // writing the same blob again is harmless
+        write_preimages(&mut kv, None, first.clone()).unwrap();
+
+        let err = write_preimages(&mut kv, None, stage(&other, b"second blob")).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("already stored with different data"),
+            "{err}"
+        );
+        // the first blob is left untouched
+        for (key, value) in first {
+            assert_eq!(kv.get(key), Some(value));
+        }
+    }
+
+    /// Reconstructs the blob of the commitment from the store the way the client does.
+    async fn reconstruct(
+        store: MemoryKeyValueStore,
+        commitment: &[u8],
+    ) -> Result<Vec<u8>, EigenDAClientError> {
+        let oracle = StoreOracle(Arc::new(RwLock::new(store)));
+        OracleEigenDaProvider::new(Arc::new(oracle))
+            .without_commitment_check()
+            .blob_get(commitment)
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_partially_written_blob_not_read() {
+        let blob = b"partially written rollup data".to_vec();
+        let mut cert = test_cert();
+        cert.blob_header.data_length = 3;
+        let mut commitment = vec![0x01, 0x00, 0x00];
+        cert.encode(&mut commitment);
+
+        let mut batch = PreimageBatch::default();
+        populate_preimages::<Keccak256Hasher>(&mut batch, &cert, &blob, true).unwrap();
+        let preimages = batch.into_written();
+
+        // fail on every write, up to the completion marker itself
+        for capacity in 0..preimages.len() {
+            let mut store = FailingStore {
+                inner: MemoryKeyValueStore::new(),
+                capacity,
+            };
+            assert!(write_preimages(&mut store, None, preimages.clone()).is_err());
+            // nothing is written past the failure
+            for (key, _) in &preimages[capacity..] {
+                assert_eq!(store.get(*key), None);
+            }
+            // the client does not read the field elements written before the failure
+            let err = reconstruct(store.inner, &commitment).await.unwrap_err();
+            assert!(matches!(err, EigenDAClientError::Oracle(_)), "{err}");
+        }
+
+        let mut store = FailingStore {
+            inner: MemoryKeyValueStore::new(),
+            capacity: preimages.len(),
+        };
+        write_preimages(&mut store, None, preimages.clone()).unwrap();
+        assert_eq!(reconstruct(store.inner, &commitment).await.unwrap(), blob);
+
+        // a marker of another data length is not read as the completion of this blob
+        let mut store = MemoryKeyValueStore::new();
+        write_preimages(&mut store, None, preimages).unwrap();
+        let marker = Keccak256Hasher::hash_key(&blob_complete_key(&cert.blob_header.commitment));
+        let key = PreimageKey::new(*marker, PreimageKeyType::GlobalGeneric);
+        store.set(key.into(), 4u64.to_be_bytes().to_vec()).unwrap();
+        let err = reconstruct(store, &commitment).await.unwrap_err();
+        assert!(
+            matches!(err, EigenDAClientError::InvariantBreach(_)),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_resume_interrupted_writes() {
+        let blob = vec![0xab; 20 * 1024];
+        let mut cert = test_cert();
+        // enough field elements for the progress to be recorded before the interruption
+        cert.blob_header.data_length = PROGRESS_INTERVAL as u32;
+
+        let mut batch = PreimageBatch::default();
+        populate_preimages::<Keccak256Hasher>(&mut batch, &cert, &blob, true).unwrap();
+        let preimages = batch.into_written();
+        let progress = progress_key::<Keccak256Hasher>(&cert);
+
+        // the host stops after writing the progress and some more preimages
+        let mut store = FailingStore {
+            inner: MemoryKeyValueStore::new(),
+            capacity: PROGRESS_INTERVAL + 500,
+        };
+        assert!(write_preimages(&mut store, Some(progress), preimages.clone()).is_err());
+        assert_eq!(recorded_progress(&store, progress), PROGRESS_INTERVAL);
+
+        // the restart only writes the preimages past the progress, and two progress records
+        let mut store = FailingStore {
+            inner: store.inner,
+            capacity: preimages.len(),
+        };
+        write_preimages(&mut store, Some(progress), preimages.clone()).unwrap();
+        assert_eq!(store.capacity, PROGRESS_INTERVAL - 2);
+        assert_eq!(recorded_progress(&store, progress), preimages.len());
+        for (key, value) in &preimages {
+            assert_eq!(store.get(*key).as_ref(), Some(value));
+        }
+
+        // a completed population writes nothing again
+        write_preimages(&mut store, Some(progress), preimages).unwrap();
+        assert_eq!(store.capacity, PROGRESS_INTERVAL - 2);
+    }
+}
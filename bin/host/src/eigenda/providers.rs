@@ -1,16 +1,32 @@
-use crate::eigenda::OnlineEigenDAProvider;
+use crate::eigenda::{DaWorkerPool, OnlineEigenDAProvider};
 use alloy_provider::RootProvider;
-use kona_host::single::SingleChainProviders;
+use core::fmt;
+use kona_host::{single::SingleChainProviders, SharedKeyValueStore};
 use kona_providers_alloy::{OnlineBeaconClient, OnlineBlobProvider};
 use op_alloy_network::Optimism;
 
 /// The combined providers for EigenDA and single chain operations
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct EigenDAChainProviders {
     /// The original single chain providers
     pub inner_providers: SingleChainProviders,
     /// The EigenDA provider
     pub eigen_da: OnlineEigenDAProvider,
+    /// A secondary store receiving a copy of the EigenDA preimages, for a warm standby host
+    pub mirror_store: Option<SharedKeyValueStore>,
+    /// The pool reconstructing the EigenDA preimages
+    pub da_pool: DaWorkerPool,
+}
+
+impl fmt::Debug for EigenDAChainProviders {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EigenDAChainProviders")
+            .field("inner_providers", &self.inner_providers)
+            .field("eigen_da", &self.eigen_da)
+            .field("mirror_store", &self.mirror_store.is_some())
+            .field("da_pool", &self.da_pool)
+            .finish()
+    }
 }
 
 impl EigenDAChainProviders {
@@ -19,9 +35,23 @@ impl EigenDAChainProviders {
         Self {
             inner_providers,
             eigen_da,
+            mirror_store: None,
+            da_pool: DaWorkerPool::default(),
         }
     }
 
+    /// Mirror the EigenDA preimages to the given store
+    pub fn with_mirror_store(mut self, mirror_store: SharedKeyValueStore) -> Self {
+        self.mirror_store = Some(mirror_store);
+        self
+    }
+
+    /// Reconstruct the EigenDA preimages on the given pool
+    pub fn with_da_pool(mut self, da_pool: DaWorkerPool) -> Self {
+        self.da_pool = da_pool;
+        self
+    }
+
     /// Access the L1 provider from the inner providers
     pub fn l1(&self) -> &RootProvider {
         &self.inner_providers.l1
@@ -42,4 +72,4 @@ impl From<EigenDAChainProviders> for SingleChainProviders {
     fn from(providers: EigenDAChainProviders) -> Self {
         providers.inner_providers
     }
-}
\ No newline at end of file
+}
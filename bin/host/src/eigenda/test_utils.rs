@@ -0,0 +1,185 @@
+//! Test helpers for the EigenDA host backend.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+/// A request received by the [MockProxy].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MockRequest {
+    /// The HTTP method.
+    pub method: String,
+    /// The request path, including the query string.
+    pub path: String,
+    /// The request headers, with lower-cased names.
+    pub headers: Vec<(String, String)>,
+    /// The request body.
+    pub body: Vec<u8>,
+}
+
+impl MockRequest {
+    /// Returns the value of the given (lower-cased) header, if present.
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A canned response served by the [MockProxy].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MockResponse {
+    /// The HTTP status code.
+    pub status: u16,
+    /// Extra response headers.
+    pub headers: Vec<(String, String)>,
+    /// The response body.
+    pub body: Vec<u8>,
+    /// Whether to send the body with `Transfer-Encoding: chunked`.
+    pub chunked: bool,
+    /// Delay before the response is written.
+    pub delay: Option<Duration>,
+}
+
+impl MockResponse {
+    /// A response with the given status and body.
+    pub(crate) fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+            ..Default::default()
+        }
+    }
+
+    /// A `200 OK` response with the given body.
+    pub(crate) fn ok(body: impl Into<Vec<u8>>) -> Self {
+        Self::new(200, body)
+    }
+}
+
+type Handler = dyn Fn(&MockRequest) -> MockResponse + Send + Sync;
+
+/// A minimal blocking HTTP/1.1 server standing in for the EigenDA proxy.
+///
+/// Every connection is served on its own thread and closed after one response, which is
+/// enough to drive `reqwest` from both async and blocking tests.
+pub(crate) struct MockProxy {
+    url: String,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+
+impl MockProxy {
+    /// Starts a server answering every request with the given handler.
+    pub(crate) fn start<H>(handler: H) -> Self
+    where
+        H: Fn(&MockRequest) -> MockResponse + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("mock proxy bind failed");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let recorded = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let handler = handler.clone();
+                let recorded = recorded.clone();
+                thread::spawn(move || serve(stream, handler.as_ref(), &recorded));
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    /// Starts a server answering every request with the same response.
+    pub(crate) fn with_response(response: MockResponse) -> Self {
+        Self::start(move |_| response.clone())
+    }
+
+    /// The base url of the server.
+    pub(crate) fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// The requests received so far.
+    pub(crate) fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+fn serve(stream: TcpStream, handler: &Handler, recorded: &Mutex<Vec<MockRequest>>) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let Some(request) = read_request(&mut reader) else {
+        return;
+    };
+    recorded.lock().unwrap().push(request.clone());
+
+    let response = handler(&request);
+    if let Some(delay) = response.delay {
+        thread::sleep(delay);
+    }
+    let _ = write_response(stream, &response);
+}
+
+fn read_request(reader: &mut impl BufRead) -> Option<MockRequest> {
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let path = parts.next()?.to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((k, v)) = line.split_once(':') {
+            headers.push((k.trim().to_ascii_lowercase(), v.trim().to_string()));
+        }
+    }
+
+    let content_length = headers
+        .iter()
+        .find(|(k, _)| k == "content-length")
+        .and_then(|(_, v)| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).ok()?;
+
+    Some(MockRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+fn write_response(mut stream: TcpStream, response: &MockResponse) -> std::io::Result<()> {
+    let mut head = format!("HTTP/1.1 {} MOCK\r\nConnection: close\r\n", response.status);
+    for (k, v) in &response.headers {
+        head.push_str(&format!("{k}: {v}\r\n"));
+    }
+    if response.chunked {
+        head.push_str("Transfer-Encoding: chunked\r\n\r\n");
+        stream.write_all(head.as_bytes())?;
+        for chunk in response.body.chunks(1024) {
+            stream.write_all(format!("{:x}\r\n", chunk.len()).as_bytes())?;
+            stream.write_all(chunk)?;
+            stream.write_all(b"\r\n")?;
+        }
+        stream.write_all(b"0\r\n\r\n")?;
+    } else {
+        head.push_str(&format!("Content-Length: {}\r\n\r\n", response.body.len()));
+        stream.write_all(head.as_bytes())?;
+        stream.write_all(&response.body)?;
+    }
+    stream.flush()
+}
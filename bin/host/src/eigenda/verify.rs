@@ -0,0 +1,290 @@
+//! A self-check of the EigenDA integration, verifying that the client reconstructs the blob
+//! the host retrieved for a commitment.
+
+use crate::eigenda::{populate_preimages, EigenDACfg, OnlineEigenDAProvider};
+use alloy_primitives::Bytes;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use clap::Parser;
+use core::fmt;
+use hydro_eigenda::{
+    common::{BlobInfo, Keccak256Hasher},
+    derive::EigenDAProvider,
+};
+use hydro_oracle::provider::OracleEigenDaProvider;
+use kona_host::{KeyValueStore, MemoryKeyValueStore};
+use kona_preimage::{
+    errors::{PreimageOracleError, PreimageOracleResult},
+    HintWriterClient, PreimageKey, PreimageOracleClient,
+};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Verifies that the blob of a commitment is reconstructed by the client as retrieved by the
+/// host, i.e. `hydro da verify <commitment>`.
+#[derive(Parser, Clone, Debug)]
+pub struct DaVerifyCommand {
+    /// The hex encoded commitment, as posted by the batcher.
+    pub commitment: Bytes,
+    #[command(flatten)]
+    pub eigen_da_args: EigenDACfg,
+}
+
+impl DaVerifyCommand {
+    /// Runs the verification against the configured EigenDA Proxy.
+    pub async fn run(&self) -> Result<Verification> {
+        let provider = self.eigen_da_args.create_provider()?;
+        verify_commitment(
+            &provider,
+            &self.commitment,
+            self.eigen_da_args.trust_proxy_commitment,
+        )
+        .await
+    }
+}
+
+/// The outcome of [verify_commitment].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verification {
+    /// The reconstructed blob is the retrieved one.
+    Agree {
+        /// The size of the blob.
+        size: usize,
+    },
+    /// The reconstructed blob differs from the retrieved one.
+    Mismatch {
+        /// The size of the blob retrieved from the proxy.
+        proxy_size: usize,
+        /// The size of the blob reconstructed from the preimages.
+        reconstructed_size: usize,
+        /// The offset of the first differing byte.
+        first_difference: usize,
+    },
+}
+
+impl fmt::Display for Verification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Agree { size } => write!(f, "host and client agree on the {size} bytes blob"),
+            Self::Mismatch {
+                proxy_size,
+                reconstructed_size,
+                first_difference,
+            } => write!(
+                f,
+                "host and client disagree: proxy blob of {proxy_size} bytes, reconstructed blob \
+                 of {reconstructed_size} bytes, first difference at byte {first_difference}"
+            ),
+        }
+    }
+}
+
+/// Retrieves the blob of the commitment, writes its preimages to a temporary store the way
+/// the hint handler does, and reconstructs it from there the way the client does.
+pub async fn verify_commitment(
+    provider: &OnlineEigenDAProvider,
+    commitment: &[u8],
+    trust_proxy_commitment: bool,
+) -> Result<Verification> {
+    let blob = provider
+        .get_blob(commitment)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch blob: {e}"))?;
+    let cert = BlobInfo::from_commitment(commitment)
+        .map_err(|e| anyhow!("Failed to decode blob info: {e}"))?;
+
+    let mut store = MemoryKeyValueStore::new();
+    populate_preimages::<Keccak256Hasher>(&mut store, &cert, &blob, trust_proxy_commitment)?;
+
+    // the KZG opening checked by the client is only served when the proxy is not trusted
+    let oracle = StoreOracle(Arc::new(RwLock::new(store)));
+    let mut client = OracleEigenDaProvider::new(Arc::new(oracle));
+    if trust_proxy_commitment {
+        client = client.without_commitment_check();
+    }
+    let reconstructed = client
+        .blob_get(commitment)
+        .await
+        .map_err(|e| anyhow!("Failed to reconstruct blob: {e}"))?;
+
+    Ok(compare(&blob, &reconstructed))
+}
+
+fn compare(proxy_blob: &[u8], reconstructed: &[u8]) -> Verification {
+    let first_difference = proxy_blob
+        .iter()
+        .zip(reconstructed)
+        .position(|(a, b)| a != b)
+        .unwrap_or(proxy_blob.len().min(reconstructed.len()));
+
+    if proxy_blob.len() == reconstructed.len() && first_difference == proxy_blob.len() {
+        Verification::Agree {
+            size: proxy_blob.len(),
+        }
+    } else {
+        Verification::Mismatch {
+            proxy_size: proxy_blob.len(),
+            reconstructed_size: reconstructed.len(),
+            first_difference,
+        }
+    }
+}
+
+/// A preimage oracle serving the preimages of a local store, ignoring hints.
+#[derive(Debug, Clone)]
+pub(crate) struct StoreOracle(pub(crate) Arc<RwLock<MemoryKeyValueStore>>);
+
+#[async_trait]
+impl PreimageOracleClient for StoreOracle {
+    async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+        self.0
+            .read()
+            .await
+            .get(key.into())
+            .ok_or(PreimageOracleError::KeyNotFound)
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
+        let value = self.get(key).await?;
+        if value.len() != buf.len() {
+            return Err(PreimageOracleError::BufferLengthMismatch(
+                buf.len(),
+                value.len(),
+            ));
+        }
+        buf.copy_from_slice(&value);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HintWriterClient for StoreOracle {
+    async fn write(&self, _hint: &str) -> PreimageOracleResult<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eigenda::{
+        test_utils::{MockProxy, MockResponse},
+        EigenDAProxy,
+    };
+    use alloy_rlp::Encodable;
+    use core::time::Duration;
+    use hydro_eigenda::common::{
+        BlobHeader, EigenDABlobData, G1Commitment, BLOB_ENCODING_VERSION_0,
+    };
+    use hydro_proofs::witness::{set_default_srs, EigenDABlobWitness, SrsConfig, WitnessError};
+
+    const G1_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../resources/g1.point");
+
+    #[tokio::test]
+    async fn test_verify_commitment() {
+        let blob = b"rollup data verified end to end".to_vec();
+        let cert = BlobInfo {
+            blob_header: BlobHeader {
+                commitment: G1Commitment {
+                    x: [0x11; 32],
+                    y: [0x22; 32],
+                },
+                data_length: EigenDABlobData::encode(&blob, BLOB_ENCODING_VERSION_0)
+                    .unwrap()
+                    .blob
+                    .len()
+                    .div_ceil(32) as u32,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut commitment = vec![0x01, 0x00, 0x00];
+        cert.encode(&mut commitment);
+
+        let proxy = MockProxy::with_response(MockResponse::ok(blob.clone()));
+        let provider =
+            OnlineEigenDAProvider::new(EigenDAProxy::new(proxy.url(), Duration::from_secs(5)));
+
+        let verification = verify_commitment(&provider, &commitment, true)
+            .await
+            .unwrap();
+        assert_eq!(verification, Verification::Agree { size: blob.len() });
+    }
+
+    #[tokio::test]
+    async fn test_verify_commitment_checks_kzg_opening() {
+        let blob = b"rollup data verified against its cert".to_vec();
+        let encoded = EigenDABlobData::encode(&blob, BLOB_ENCODING_VERSION_0)
+            .unwrap()
+            .blob;
+
+        // the host computes the witnesses with the default trusted setup
+        let srs = SrsConfig {
+            points_to_load: 64,
+            ..SrsConfig::from_path(G1_PATH)
+        }
+        .load()
+        .unwrap();
+        let _ = set_default_srs(srs.clone());
+        let err = EigenDABlobWitness::new()
+            .push_witness_with_srs(&encoded, &[0u8; 64], &srs)
+            .unwrap_err();
+        let WitnessError::CommitmentMismatch { computed } = err else {
+            panic!("unexpected error {err}");
+        };
+
+        let cert = BlobInfo {
+            blob_header: BlobHeader {
+                commitment: G1Commitment {
+                    x: computed[..32].try_into().unwrap(),
+                    y: computed[32..].try_into().unwrap(),
+                },
+                data_length: encoded.len().div_ceil(32) as u32,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut commitment = vec![0x01, 0x00, 0x00];
+        cert.encode(&mut commitment);
+
+        let proxy = MockProxy::with_response(MockResponse::ok(blob.clone()));
+        let provider =
+            OnlineEigenDAProvider::new(EigenDAProxy::new(proxy.url(), Duration::from_secs(5)));
+
+        // the host serves the KZG opening, which the client verifies against the cert
+        let verification = verify_commitment(&provider, &commitment, false)
+            .await
+            .unwrap();
+        assert_eq!(verification, Verification::Agree { size: blob.len() });
+
+        // without the opening the client refuses to serve the blob
+        let mut store = MemoryKeyValueStore::new();
+        populate_preimages::<Keccak256Hasher>(&mut store, &cert, &blob, true).unwrap();
+        let oracle = StoreOracle(Arc::new(RwLock::new(store)));
+        assert!(OracleEigenDaProvider::new(Arc::new(oracle))
+            .blob_get(&commitment)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(compare(b"abc", b"abc"), Verification::Agree { size: 3 });
+        assert_eq!(
+            compare(b"abc", b"abd"),
+            Verification::Mismatch {
+                proxy_size: 3,
+                reconstructed_size: 3,
+                first_difference: 2
+            }
+        );
+        assert_eq!(
+            compare(b"abc", b"ab"),
+            Verification::Mismatch {
+                proxy_size: 3,
+                reconstructed_size: 2,
+                first_difference: 2
+            }
+        );
+    }
+}
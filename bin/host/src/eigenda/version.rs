@@ -0,0 +1,104 @@
+//! Contains the [ProxyVersion] of an EigenDA Proxy and its startup check.
+
+use clap::ValueEnum;
+use serde::Serialize;
+use std::fmt;
+
+/// The oldest EigenDA Proxy version known to be compatible, inclusive.
+pub const MIN_PROXY_VERSION: ProxyVersion = ProxyVersion::new(1, 4, 0);
+
+/// The first EigenDA Proxy version known to be incompatible, exclusive.
+pub const MAX_PROXY_VERSION: ProxyVersion = ProxyVersion::new(2, 0, 0);
+
+/// The semantic version of an EigenDA Proxy, pre-release and build suffixes left out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ProxyVersion {
+    /// The major version.
+    pub major: u64,
+    /// The minor version.
+    pub minor: u64,
+    /// The patch version.
+    pub patch: u64,
+}
+
+impl ProxyVersion {
+    /// Creates a new [ProxyVersion].
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parses a version such as `v1.6.2` or `1.6.2-rc.1`, the leading `v`, a missing patch
+    /// and any pre-release or build suffix being accepted.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let input = input.strip_prefix('v').unwrap_or(input);
+        let core = input.split(['-', '+']).next()?;
+        let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+        let (Some(Some(major)), Some(Some(minor)), patch, None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+        Some(Self::new(major, minor, patch.unwrap_or(Some(0))?))
+    }
+
+    /// Returns whether the version is within [MIN_PROXY_VERSION] and [MAX_PROXY_VERSION].
+    pub fn is_compatible(&self) -> bool {
+        (MIN_PROXY_VERSION..MAX_PROXY_VERSION).contains(self)
+    }
+}
+
+impl fmt::Display for ProxyVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// How the version of the EigenDA Proxies is checked at startup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyVersionCheck {
+    /// The version is not queried.
+    #[default]
+    Off,
+    /// An incompatible or unknown version is logged as a warning.
+    Warn,
+    /// An incompatible or unknown version fails the startup.
+    Strict,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(
+            ProxyVersion::parse("v1.6.2"),
+            Some(ProxyVersion::new(1, 6, 2))
+        );
+        assert_eq!(
+            ProxyVersion::parse("1.6.2-rc.1+abc"),
+            Some(ProxyVersion::new(1, 6, 2))
+        );
+        assert_eq!(
+            ProxyVersion::parse(" 2.0 "),
+            Some(ProxyVersion::new(2, 0, 0))
+        );
+        assert_eq!(ProxyVersion::parse("1"), None);
+        assert_eq!(ProxyVersion::parse("1.2.3.4"), None);
+        assert_eq!(ProxyVersion::parse("1.x.0"), None);
+    }
+
+    #[test]
+    fn test_compatible_range() {
+        assert!(MIN_PROXY_VERSION.is_compatible());
+        assert!(ProxyVersion::new(1, 9, 9).is_compatible());
+        assert!(!ProxyVersion::new(1, 3, 9).is_compatible());
+        assert!(!MAX_PROXY_VERSION.is_compatible());
+    }
+}
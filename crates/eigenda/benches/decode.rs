@@ -0,0 +1,84 @@
+//! Benchmarks decoding a 16 MiB [EigenDABlobData], whole with `decode` and in chunks with
+//! `decode_chunks`, and reports the peak heap memory allocated by each.
+//!
+//! Run with `cargo bench -p hydro-eigenda --bench decode`.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use hydro_eigenda::common::{EigenDABlobData, BLOB_ENCODING_VERSION_0};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Size of the encoded blob.
+const BLOB_SIZE: usize = 16 * 1024 * 1024;
+
+/// The [System] allocator, tracking the peak of the allocated bytes.
+struct PeakAlloc {
+    current: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = self.current.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            self.peak.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        self.current.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc {
+    current: AtomicUsize::new(0),
+    peak: AtomicUsize::new(0),
+};
+
+/// Returns the peak of the heap memory allocated by `f` on top of what was already allocated.
+fn peak_memory<T>(f: impl FnOnce() -> T) -> usize {
+    let base = ALLOC.current.load(Ordering::Relaxed);
+    ALLOC.peak.store(base, Ordering::Relaxed);
+    black_box(f());
+    ALLOC.peak.load(Ordering::Relaxed) - base
+}
+
+/// Feeds the decoded data to a consumer hashing it, without keeping it.
+fn consume(data: &[u8]) -> u64 {
+    data.iter().fold(0, |acc, b| acc.rotate_left(5) ^ *b as u64)
+}
+
+fn bench_decode(c: &mut Criterion) {
+    // the largest rollup data fitting in the blob after the header and the padding
+    let data_size = (BLOB_SIZE / 32 - 1) * 31;
+    let rollup_data: Vec<u8> = (0..data_size).map(|i| i as u8).collect();
+    let blob = EigenDABlobData::encode(&rollup_data, BLOB_ENCODING_VERSION_0).unwrap();
+    drop(rollup_data);
+
+    let decode = || consume(&blob.decode().unwrap());
+    let decode_chunks = || {
+        blob.decode_chunks()
+            .unwrap()
+            .map(|chunk| consume(chunk.unwrap()))
+            .fold(0, |acc, hash| acc ^ hash)
+    };
+    let (whole, chunked) = (peak_memory(decode), peak_memory(decode_chunks));
+    println!("peak memory: decode {whole} bytes, decode_chunks {chunked} bytes");
+
+    let mut group = c.benchmark_group("eigenda_blob_decode");
+    group.throughput(Throughput::Bytes(BLOB_SIZE as u64));
+    group.sample_size(10);
+    group.bench_function("decode", |b| b.iter(decode));
+    group.bench_function("decode_chunks", |b| b.iter(decode_chunks));
+    group.finish();
+}
+
+criterion_group!(benches, bench_decode);
+criterion_main!(benches);
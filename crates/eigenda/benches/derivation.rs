@@ -0,0 +1,97 @@
+//! Benchmarks [EigenDASource] over a synthetic block of frame-ref transactions.
+//!
+//! Run with `cargo bench -p hydro-eigenda --features test-utils`. The number of frame refs per
+//! block can be overridden with a comma separated list in `HYDRO_BENCH_FRAME_REFS`.
+
+use alloy_primitives::{Bytes, B256};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use hydro_eigenda::{
+    test_utils::{
+        batcher_address, batcher_tx, encode_commitment, encode_frames, frame_ref_calldata,
+        test_cert, TestEigenDAProvider, TEST_BATCH_INBOX,
+    },
+    EigenDASource,
+};
+use kona_derive::{
+    test_utils::{TestBlobProvider, TestChainProvider},
+    traits::DataAvailabilityProvider,
+};
+use kona_protocol::BlockInfo;
+use tokio::runtime::Runtime;
+
+/// Frames carried by each EigenDA blob.
+const FRAMES_PER_BLOB: usize = 4;
+/// Size of each frame in bytes.
+const FRAME_SIZE: usize = 16 * 1024;
+
+type BenchSource = EigenDASource<TestChainProvider, TestBlobProvider, TestEigenDAProvider>;
+
+/// Builds a source over a single block with `frame_refs` frame-ref transactions.
+fn setup(frame_refs: usize) -> (BenchSource, BlockInfo) {
+    let block = BlockInfo {
+        hash: B256::repeat_byte(0xbb),
+        number: 1,
+        ..Default::default()
+    };
+
+    let mut eigen_da = TestEigenDAProvider::default();
+    let mut txs = Vec::with_capacity(frame_refs);
+    for i in 0..frame_refs {
+        let commitment = encode_commitment(&test_cert(i as u32 + 1));
+        let frames = vec![vec![i as u8; FRAME_SIZE]; FRAMES_PER_BLOB];
+        let blob = encode_frames(&frames);
+        txs.push(batcher_tx(
+            frame_ref_calldata(&commitment, blob.len() as u32, vec![0, 1]),
+            i as u64,
+        ));
+        eigen_da.insert_blob(commitment, blob);
+    }
+
+    let mut chain = TestChainProvider::default();
+    chain.insert_block_with_transactions(block.number, block, txs);
+
+    let source = EigenDASource::new(
+        chain,
+        TestBlobProvider::default(),
+        eigen_da,
+        TEST_BATCH_INBOX,
+    );
+    (source, block)
+}
+
+/// Drains all data of the block through `next`.
+async fn drain(source: &mut BenchSource, block: &BlockInfo) -> Vec<Bytes> {
+    let batcher = batcher_address();
+    let mut out = Vec::new();
+    while let Ok(data) = source.next(block, batcher).await {
+        out.push(data);
+    }
+    source.clear();
+    out
+}
+
+fn frame_ref_counts() -> Vec<usize> {
+    std::env::var("HYDRO_BENCH_FRAME_REFS")
+        .ok()
+        .map(|v| v.split(',').filter_map(|n| n.trim().parse().ok()).collect())
+        .unwrap_or_else(|| vec![1, 16, 64])
+}
+
+fn bench_frame_refs(c: &mut Criterion) {
+    let runtime = Runtime::new().expect("runtime builder failed");
+    let mut group = c.benchmark_group("eigenda_source_next");
+
+    for frame_refs in frame_ref_counts() {
+        let (mut source, block) = setup(frame_refs);
+        group.throughput(Throughput::Elements((frame_refs * FRAMES_PER_BLOB) as u64));
+        group.bench_with_input(
+            BenchmarkId::new("frame_refs", frame_refs),
+            &frame_refs,
+            |b, _| b.iter(|| runtime.block_on(drain(&mut source, &block))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_frame_refs);
+criterion_main!(benches);
@@ -1,19 +1,23 @@
+use crate::{
+    common::{field_elements_size, STALE_GAP},
+    errors::EigenDAProviderError,
+};
 use alloc::vec::Vec;
-use alloy_primitives::Bytes;
-use alloy_rlp::{RlpDecodable, RlpEncodable};
+use alloy_primitives::{keccak256, Bytes, B256};
+use alloy_rlp::{Decodable, RlpDecodable, RlpEncodable};
 
 // TODO: use prost to generate struct from proto file
 // see seggestion, https://github.com/Layr-Labs/hokulea/pull/17#discussion_r1901102921
 
-#[allow(unnameable_types)]
-#[derive(Debug, PartialEq, Clone, RlpEncodable, RlpDecodable)]
+/// bn254 G1 point of a kzg commitment
+#[derive(Debug, Default, PartialEq, Clone, RlpEncodable, RlpDecodable)]
 pub struct G1Commitment {
     pub x: [u8; 32],
     pub y: [u8; 32],
 }
 
-#[allow(unnameable_types)]
-#[derive(Debug, PartialEq, Clone, RlpEncodable, RlpDecodable)]
+/// eigenda v1 per quorum blob params
+#[derive(Debug, Default, PartialEq, Clone, RlpEncodable, RlpDecodable)]
 pub struct BlobQuorumParam {
     pub quorum_number: u32,
     pub adversary_threshold_percentage: u32,
@@ -22,16 +26,45 @@ pub struct BlobQuorumParam {
 }
 
 /// eigenda v1 blob header
-#[allow(unnameable_types)]
-#[derive(Debug, PartialEq, Clone, RlpEncodable, RlpDecodable)]
+#[derive(Debug, Default, PartialEq, Clone, RlpEncodable, RlpDecodable)]
 pub struct BlobHeader {
     pub commitment: G1Commitment,
     pub data_length: u32,
     pub blob_quorum_params: Vec<BlobQuorumParam>,
 }
 
-#[allow(unnameable_types)]
-#[derive(Debug, PartialEq, Clone, RlpEncodable, RlpDecodable)]
+impl BlobHeader {
+    /// Returns the hash of the blob header, the keccak256 hash of its ABI encoding as in
+    /// `EigenDAHasher.hashBlobHeader` of the EigenDA contracts.
+    pub fn hash(&self) -> B256 {
+        let mut encoded = Vec::with_capacity((6 + 4 * self.blob_quorum_params.len()) * 32);
+        // offset of the dynamic tuple
+        encoded.extend_from_slice(&abi_word(0x20));
+        encoded.extend_from_slice(&self.commitment.x);
+        encoded.extend_from_slice(&self.commitment.y);
+        encoded.extend_from_slice(&abi_word(self.data_length as u64));
+        // offset of the quorum params, following the four head words of the tuple
+        encoded.extend_from_slice(&abi_word(0x80));
+        encoded.extend_from_slice(&abi_word(self.blob_quorum_params.len() as u64));
+        for param in &self.blob_quorum_params {
+            encoded.extend_from_slice(&abi_word(param.quorum_number as u64));
+            encoded.extend_from_slice(&abi_word(param.adversary_threshold_percentage as u64));
+            encoded.extend_from_slice(&abi_word(param.confirmation_threshold_percentage as u64));
+            encoded.extend_from_slice(&abi_word(param.chunk_length as u64));
+        }
+        keccak256(encoded)
+    }
+}
+
+/// Returns the 32 byte ABI encoding of an unsigned integer.
+fn abi_word(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// eigenda v1 batch header
+#[derive(Debug, Default, PartialEq, Clone, RlpEncodable, RlpDecodable)]
 pub struct BatchHeader {
     pub batch_root: Bytes,
     pub quorum_numbers: Bytes,
@@ -39,8 +72,8 @@ pub struct BatchHeader {
     pub reference_block_number: u32,
 }
 
-#[allow(unnameable_types)]
-#[derive(Debug, PartialEq, Clone, RlpEncodable, RlpDecodable)]
+/// eigenda v1 batch metadata
+#[derive(Debug, Default, PartialEq, Clone, RlpEncodable, RlpDecodable)]
 pub struct BatchMetadata {
     pub batch_header: BatchHeader,
     pub signatory_record_hash: Bytes,
@@ -50,8 +83,7 @@ pub struct BatchMetadata {
 }
 
 /// eigenda v1 blob verification proof
-#[allow(unnameable_types)]
-#[derive(Debug, PartialEq, Clone, RlpEncodable, RlpDecodable)]
+#[derive(Debug, Default, PartialEq, Clone, RlpEncodable, RlpDecodable)]
 pub struct BlobVerificationProof {
     pub batch_id: u32,
     pub blob_index: u32,
@@ -61,10 +93,203 @@ pub struct BlobVerificationProof {
 }
 
 /// eigenda v1 certificate
-#[derive(Debug, PartialEq, Clone, RlpEncodable, RlpDecodable)]
+#[derive(Debug, Default, PartialEq, Clone, RlpEncodable, RlpDecodable)]
 pub struct BlobInfo {
     /// v1 blob header
     pub blob_header: BlobHeader,
     /// v1 blob verification proof with merkle tree
     pub blob_verification_proof: BlobVerificationProof,
 }
+
+impl BlobInfo {
+    /// Decodes the cert from a commitment as posted by the batcher.
+    ///
+    /// The first three bytes of the commitment are metadata, like the cert version and the OP
+    /// generic commitment type,
+    /// see <https://github.com/Layr-Labs/eigenda-proxy/blob/main/commitments/mode.go#L39>
+    pub fn from_commitment(commitment: &[u8]) -> Result<Self, alloy_rlp::Error> {
+        let mut cert = commitment.get(3..).ok_or(alloy_rlp::Error::InputTooShort)?;
+        Self::decode(&mut cert)
+    }
+
+    /// Returns the cert hash, the keccak256 hash of the RLP encoded cert, i.e. of the
+    /// commitment without its three metadata bytes. Tooling may key blobs by it rather than
+    /// by commitment.
+    pub fn cert_hash(&self) -> B256 {
+        keccak256(alloy_rlp::encode(self))
+    }
+
+    /// Verifies the inclusion proof of the cert, i.e. that the blob header is the leaf at
+    /// `blob_index` of the Merkle tree whose root is the batch root of the batch header.
+    ///
+    /// This mirrors `Merkle.verifyInclusionKeccak` as used by the EigenDA V1 cert
+    /// verification, the leaf being the keccak256 hash of the blob header hash. Neither the
+    /// batch header signature nor its confirmation on L1 are checked.
+    pub fn verify_inclusion(&self) -> Result<(), EigenDAProviderError> {
+        let proof = &self.blob_verification_proof;
+        let batch_root = &proof.batch_medatada.batch_header.batch_root;
+        if batch_root.len() != 32 {
+            return Err(EigenDAProviderError::InvalidInclusionProof(alloc::format!(
+                "batch root has {} bytes, expected 32",
+                batch_root.len()
+            )));
+        }
+        if proof.inclusion_proof.len() % 32 != 0 {
+            return Err(EigenDAProviderError::InvalidInclusionProof(alloc::format!(
+                "inclusion proof length {} is not a multiple of 32",
+                proof.inclusion_proof.len()
+            )));
+        }
+
+        let mut index = proof.blob_index;
+        let mut node = keccak256(self.blob_header.hash());
+        for sibling in proof.inclusion_proof.chunks_exact(32) {
+            node = if index % 2 == 0 {
+                keccak256([node.as_slice(), sibling].concat())
+            } else {
+                keccak256([sibling, node.as_slice()].concat())
+            };
+            index /= 2;
+        }
+
+        if node.as_slice() != batch_root.as_ref() {
+            return Err(EigenDAProviderError::InvalidInclusionProof(alloc::format!(
+                "computed root {node} does not match the batch root"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns the number of the L1 block the cert references, from its batch header.
+    pub fn reference_block_number(&self) -> u64 {
+        self.blob_verification_proof
+            .batch_medatada
+            .batch_header
+            .reference_block_number as u64
+    }
+
+    /// Checks that the cert is not stale at the L1 block `block_number` carrying its frame
+    /// ref, i.e. that the block is at most [STALE_GAP] blocks after the reference block of the
+    /// cert.
+    pub fn check_recency(&self, block_number: u64) -> Result<(), EigenDAProviderError> {
+        let reference_block_number = self.reference_block_number();
+        if block_number > reference_block_number.saturating_add(STALE_GAP) {
+            return Err(EigenDAProviderError::StaleCert {
+                reference_block_number,
+                block_number,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the blob params of the given quorum, if the blob was dispersed to it.
+    pub fn quorum_param(&self, quorum_number: u32) -> Option<&BlobQuorumParam> {
+        self.blob_header
+            .blob_quorum_params
+            .iter()
+            .find(|param| param.quorum_number == quorum_number)
+    }
+
+    /// Returns the size in bytes of the blob as dispersed to the given quorum, failing with
+    /// [EigenDAProviderError::MissingQuorumParams] if the cert carries no blob params for it.
+    ///
+    /// The params of a v1 cert only differ in the chunking of the blob on each quorum, the
+    /// blob itself being the `data_length` field elements of the header on all of them.
+    pub fn quorum_blob_size(&self, quorum_number: u32) -> Result<usize, EigenDAProviderError> {
+        self.quorum_param(quorum_number)
+            .ok_or(EigenDAProviderError::MissingQuorumParams(quorum_number))?;
+        field_elements_size(self.blob_header.data_length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::BYTES_PER_FIELD_ELEMENT;
+
+    /// A cert whose blob header is the leaf at `blob_index` of a four leaf tree, with the
+    /// batch root and inclusion proof set accordingly.
+    fn cert_with_inclusion(blob_index: u32) -> BlobInfo {
+        let mut cert = BlobInfo::default();
+        cert.blob_header.commitment.x = [0x11; 32];
+        cert.blob_header.data_length = 4;
+        cert.blob_header.blob_quorum_params = alloc::vec![BlobQuorumParam {
+            quorum_number: 0,
+            adversary_threshold_percentage: 33,
+            confirmation_threshold_percentage: 55,
+            chunk_length: 1,
+        }];
+
+        let mut leaves: Vec<B256> = (0u8..4).map(|i| keccak256([i])).collect();
+        leaves[blob_index as usize] = keccak256(cert.blob_header.hash());
+        let hash_pair = |a: &B256, b: &B256| keccak256([a.as_slice(), b.as_slice()].concat());
+        let left = hash_pair(&leaves[0], &leaves[1]);
+        let right = hash_pair(&leaves[2], &leaves[3]);
+
+        let (sibling, uncle) = match blob_index {
+            0 => (leaves[1], right),
+            1 => (leaves[0], right),
+            2 => (leaves[3], left),
+            _ => (leaves[2], left),
+        };
+        let proof = &mut cert.blob_verification_proof;
+        proof.blob_index = blob_index;
+        proof.inclusion_proof = [sibling.as_slice(), uncle.as_slice()].concat().into();
+        proof.batch_medatada.batch_header.batch_root = hash_pair(&left, &right).to_vec().into();
+        cert
+    }
+
+    #[test]
+    fn test_quorum_blob_size() {
+        let cert = cert_with_inclusion(0);
+        assert_eq!(cert.quorum_blob_size(0), Ok(4 * BYTES_PER_FIELD_ELEMENT));
+        assert_eq!(
+            cert.quorum_blob_size(1),
+            Err(EigenDAProviderError::MissingQuorumParams(1))
+        );
+    }
+
+    #[test]
+    fn test_cert_hash() {
+        let cert = cert_with_inclusion(0);
+        let commitment = [&[0x01, 0x00, 0x00][..], &alloy_rlp::encode(&cert)].concat();
+        assert_eq!(cert.cert_hash(), keccak256(&commitment[3..]));
+        assert_ne!(cert.cert_hash(), cert_with_inclusion(1).cert_hash());
+    }
+
+    #[test]
+    fn test_verify_inclusion() {
+        for blob_index in 0..4 {
+            assert_eq!(cert_with_inclusion(blob_index).verify_inclusion(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_verify_inclusion_rejects_invalid_proof() {
+        // tampered proof
+        let mut cert = cert_with_inclusion(2);
+        let mut proof = cert.blob_verification_proof.inclusion_proof.to_vec();
+        proof[0] ^= 1;
+        cert.blob_verification_proof.inclusion_proof = proof.into();
+        assert!(cert.verify_inclusion().is_err());
+
+        // wrong leaf index
+        let mut cert = cert_with_inclusion(2);
+        cert.blob_verification_proof.blob_index = 3;
+        assert!(cert.verify_inclusion().is_err());
+
+        // blob header not in the batch
+        let mut cert = cert_with_inclusion(1);
+        cert.blob_header.data_length += 1;
+        assert!(cert.verify_inclusion().is_err());
+
+        // malformed proof and root
+        let mut cert = cert_with_inclusion(0);
+        cert.blob_verification_proof.inclusion_proof = Bytes::from_static(&[0u8; 33]);
+        assert!(matches!(
+            cert.verify_inclusion(),
+            Err(EigenDAProviderError::InvalidInclusionProof(_))
+        ));
+        assert!(BlobInfo::default().verify_inclusion().is_err());
+    }
+}
@@ -1,7 +1,14 @@
 /// This minimal blob encoding contains a 32 byte header = [0x00, version byte, uint32 len of data, 0x00, 0x00,...]
 /// followed by the encoded data [0x00, 31 bytes of data, 0x00, 31 bytes of data,...]
 pub const BLOB_ENCODING_VERSION_0: u8 = 0x0;
+/// The next blob encoding version, reserved until EigenDA specifies its payload encoding.
+/// Blobs of this version are rejected as of an unknown version until then.
+pub const BLOB_ENCODING_VERSION_1: u8 = 0x1;
 /// TODO: make it part of rollup config
 pub const STALE_GAP: u64 = 100;
 /// Number of fields for field element on bn254
 pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+/// Maximum size in bytes of an EigenDA blob
+pub const MAX_BLOB_SIZE: usize = 16 * 1024 * 1024;
+/// Default maximum size in bytes of a commitment, well above the size of any cert
+pub const DEFAULT_MAX_COMMITMENT_LENGTH: usize = 16 * 1024;
@@ -1,6 +1,10 @@
-use crate::common::{BLOB_ENCODING_VERSION_0, BYTES_PER_FIELD_ELEMENT};
-use alloy_primitives::Bytes;
+use crate::{
+    common::{BLOB_ENCODING_VERSION_0, BYTES_PER_FIELD_ELEMENT},
+    errors::EigenDADataError,
+};
 use alloc::vec;
+use alloc::vec::Vec;
+use alloy_primitives::Bytes;
 use bytes::buf::Buf;
 use kona_derive::errors::BlobDecodingError;
 use rust_kzg_bn254_primitives::helpers;
@@ -19,69 +23,80 @@ impl EigenDABlobData {
     }
 
     /// Decodes the blob into raw byte data. Reverse of the encode function below
-    /// Returns a [BlobDecodingError] if the blob is invalid.
-    pub fn decode(&self) -> Result<Bytes, BlobDecodingError> {
+    /// Returns an [EigenDADataError] if the blob is invalid.
+    ///
+    /// The layout of the blob is the one of the encoding version in its header, failing with
+    /// [EigenDADataError::UnknownEncodingVersion] for versions this crate does not know.
+    ///
+    /// The data is collected from [EigenDABlobData::decode_chunks], which large blobs should
+    /// be decoded with to avoid holding the whole data at once.
+    pub fn decode(&self) -> Result<Bytes, EigenDADataError> {
+        let chunks = self.decode_chunks()?;
+        let mut data = Vec::with_capacity(chunks.remaining);
+        for chunk in chunks {
+            data.extend_from_slice(chunk?);
+        }
+        Ok(data.into())
+    }
+
+    /// Decodes the blob lazily, yielding the raw byte data one field element at a time as
+    /// slices of the blob, without allocating.
+    ///
+    /// The header is checked upfront, failing like [EigenDABlobData::decode]. Each field
+    /// element is checked as it is reached, the iterator yielding the error of the first
+    /// invalid one and ending there, so the data yielded before an error must be discarded.
+    pub fn decode_chunks(&self) -> Result<EigenDABlobChunks<'_>, EigenDADataError> {
         let blob = &self.blob;
         if blob.len() < 32 {
-            return Err(BlobDecodingError::InvalidLength);
+            return Err(BlobDecodingError::InvalidLength.into());
         }
 
         // blob must have multiple of 32 bytes
         if blob.len() % BYTES_PER_FIELD_ELEMENT != 0 {
-            return Err(BlobDecodingError::InvalidLength);
+            return Err(BlobDecodingError::InvalidLength.into());
         }
 
         // Validate header format
         // The first byte must be 0 to comply with bn254 field element constraint
         if blob[0] != 0 {
-            return Err(BlobDecodingError::InvalidLength);
+            return Err(BlobDecodingError::InvalidLength.into());
         }
 
-        // The second byte must be the expected encoding version
-        if blob[1] != BLOB_ENCODING_VERSION_0 {
-            return Err(BlobDecodingError::InvalidLength);
+        // The second byte is the encoding version
+        match blob[1] {
+            BLOB_ENCODING_VERSION_0 => self.decode_padded(),
+            // version 1 is reserved, its payload encoding is not specified yet
+            version => Err(EigenDADataError::UnknownEncodingVersion(version)),
         }
+    }
+
+    /// Decodes a blob whose header is followed by the rollup data padded with an empty byte
+    /// every 31 bytes, the layout of [BLOB_ENCODING_VERSION_0].
+    fn decode_padded(&self) -> Result<EigenDABlobChunks<'_>, EigenDADataError> {
+        let blob = &self.blob;
 
         // see https://github.com/Layr-Labs/eigenda/blob/f8b0d31d65b29e60172507074922668f4ca89420/api/clients/codecs/default_blob_codec.go#L44
-        let content_size = blob.slice(2..6).get_u32();
+        let content_size = blob.slice(2..6).get_u32() as usize;
 
         // the first 32 Bytes are reserved as the header field element
-        let codec_data = blob.slice(32..);
+        let codec_data = &blob[32..];
 
-        // verify there is an empty byte for every 31 bytes
-        // this is a harder constraint than field element range check.
-        for chunk in codec_data.chunks_exact(BYTES_PER_FIELD_ELEMENT) {
-            // very conservative check on Field element range. It allows us to detect
-            // mishaving at the host side when providing the field element. So we can stop early.
-            // the field element of on bn254 curve is some number less than 2^254
-            // that means both 255 and 254 th bits must be 0. iut of conservation, we require the
-            // 253 bit to be 0. It aligns with our encoding scheme below that the first 8bits
-            // should be 0.
-            // Field elements are interpreted as big endian
-            if chunk[0] & 0b1110_0000 != 0 {
-                return Err(BlobDecodingError::InvalidFieldElement);
-            }
-
-            // field elements are interpreted as big endian. It can happen either because
-            // the host is misbehaving, or the op-batcher is not following the eigenda
-            // encoding standard
-            if chunk[0] != 0x0 {
-                return Err(BlobDecodingError::InvalidEncodingVersion.into());
-            }
+        // every field element carries 31 bytes of data after its empty byte
+        let capacity = codec_data.len() / BYTES_PER_FIELD_ELEMENT * (BYTES_PER_FIELD_ELEMENT - 1);
+        if capacity < content_size {
+            return Err(EigenDADataError::InsufficientData {
+                expected: content_size,
+                actual: capacity,
+            });
         }
-
-        // rust kzg bn254 impl already
-        let blob_content =
-            helpers::remove_empty_byte_from_padded_bytes_unchecked(codec_data.as_ref());
-        let blob_content: Bytes = blob_content.into();
-
-        if blob_content.len() < content_size as usize {
-            return Err(BlobDecodingError::InvalidLength);
-        }
-        Ok(blob_content.slice(..content_size as usize))
+        Ok(EigenDABlobChunks {
+            field_elements: codec_data.chunks_exact(BYTES_PER_FIELD_ELEMENT),
+            remaining: content_size,
+        })
     }
 
-    /// The encode function accepts an input of opaque rollup data array into an EigenDABlobData.
+    /// The encode function accepts an input of opaque rollup data array into an EigenDABlobData
+    /// of the given encoding version, [BLOB_ENCODING_VERSION_0] being the only supported one.
     /// EigenDABlobData contains a header of 32 bytes and a transformation of input data
     /// The 0 index byte of header is always 0, to comply to bn254 field element constraint
     /// The 1 index byte of header is proxy encoding version.
@@ -92,7 +107,13 @@ impl EigenDABlobData {
     ///
     /// The length of (header + payload) by the encode function is always multiple of 32
     /// The eigenda proxy does not take such constraint.
-    pub fn encode(rollup_data: &[u8]) -> Self {
+    ///
+    /// Fails with [EigenDADataError::UnknownEncodingVersion] for versions this crate does not
+    /// know.
+    pub fn encode(rollup_data: &[u8], version: u8) -> Result<Self, EigenDADataError> {
+        if version != BLOB_ENCODING_VERSION_0 {
+            return Err(EigenDADataError::UnknownEncodingVersion(version));
+        }
         let rollup_data_size = rollup_data.len() as u32;
 
         // encode to become raw blob
@@ -108,29 +129,85 @@ impl EigenDABlobData {
 
         let mut raw_blob = vec![0u8; blob_size as usize];
 
-        raw_blob[1] = BLOB_ENCODING_VERSION_0;
+        raw_blob[1] = version;
         raw_blob[2..6].copy_from_slice(&rollup_data_size.to_be_bytes());
 
         // encode length as uint32
         raw_blob[BYTES_PER_FIELD_ELEMENT..(BYTES_PER_FIELD_ELEMENT + blob_payload_size as usize)]
             .copy_from_slice(&codec_rollup_data);
 
-        Self {
+        Ok(Self {
             blob: Bytes::from(raw_blob),
+        })
+    }
+}
+
+/// The raw byte data of an [EigenDABlobData], yielded one field element at a time by
+/// [EigenDABlobData::decode_chunks].
+///
+/// The field elements past the data, padding the blob, are checked but yield nothing.
+#[derive(Debug, Clone)]
+pub struct EigenDABlobChunks<'a> {
+    /// The field elements not checked yet.
+    field_elements: core::slice::ChunksExact<'a, u8>,
+    /// The number of bytes of data not yielded yet.
+    remaining: usize,
+}
+
+impl<'a> Iterator for EigenDABlobChunks<'a> {
+    type Item = Result<&'a [u8], EigenDADataError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for chunk in self.field_elements.by_ref() {
+            // very conservative check on Field element range. It allows us to detect
+            // mishaving at the host side when providing the field element. So we can stop early.
+            // the field element of on bn254 curve is some number less than 2^254
+            // that means both 255 and 254 th bits must be 0. iut of conservation, we require the
+            // 253 bit to be 0. It aligns with our encoding scheme below that the first 8bits
+            // should be 0.
+            // Field elements are interpreted as big endian
+            let error = if chunk[0] & 0b1110_0000 != 0 {
+                Some(BlobDecodingError::InvalidFieldElement)
+            } else if chunk[0] != 0x0 {
+                // field elements are interpreted as big endian. It can happen either because
+                // the host is misbehaving, or the op-batcher is not following the eigenda
+                // encoding standard
+                Some(BlobDecodingError::InvalidEncodingVersion)
+            } else {
+                None
+            };
+            if let Some(error) = error {
+                let exhausted: &[u8] = &[];
+                self.field_elements = exhausted.chunks_exact(BYTES_PER_FIELD_ELEMENT);
+                self.remaining = 0;
+                return Some(Err(error.into()));
+            }
+
+            let len = self.remaining.min(BYTES_PER_FIELD_ELEMENT - 1);
+            if len > 0 {
+                self.remaining -= len;
+                return Some(Ok(&chunk[1..1 + len]));
+            }
         }
+        None
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::BLOB_ENCODING_VERSION_1;
     use alloy_primitives::Bytes;
     use kona_derive::errors::BlobDecodingError;
 
+    fn encode_v0(rollup_data: &[u8]) -> EigenDABlobData {
+        EigenDABlobData::encode(rollup_data, BLOB_ENCODING_VERSION_0).unwrap()
+    }
+
     #[test]
     fn test_encode_and_decode_success() {
         let rollup_data = vec![0u8; 50000];
-        let eigenda_blob = EigenDABlobData::encode(&rollup_data);
+        let eigenda_blob = encode_v0(&rollup_data);
         let data_len = eigenda_blob.blob.len();
 
         assert!(data_len % BYTES_PER_FIELD_ELEMENT == 0);
@@ -143,7 +220,7 @@ mod tests {
     #[test]
     fn test_encode_and_decode_success_empty() {
         let rollup_data = vec![];
-        let eigenda_blob = EigenDABlobData::encode(&rollup_data);
+        let eigenda_blob = encode_v0(&rollup_data);
         let data_len = eigenda_blob.blob.len();
         // 32 is eigenda blob header size
         assert!(data_len == 32);
@@ -156,38 +233,139 @@ mod tests {
     #[test]
     fn test_encode_and_decode_error_invalid_length() {
         let rollup_data = vec![1, 2, 3, 4];
-        let mut eigenda_blob = EigenDABlobData::encode(&rollup_data);
+        let mut eigenda_blob = encode_v0(&rollup_data);
         eigenda_blob.blob.truncate(33);
         let result = eigenda_blob.decode();
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), BlobDecodingError::InvalidLength);
+        assert_eq!(result.unwrap_err(), BlobDecodingError::InvalidLength.into());
     }
 
     #[test]
     fn test_decode_error_invalid_header_first_byte() {
         let rollup_data = vec![1, 2, 3, 4];
-        let mut eigenda_blob = EigenDABlobData::encode(&rollup_data);
+        let mut eigenda_blob = encode_v0(&rollup_data);
         // Modify the first byte to be non-zero (invalid)
         let mut blob_bytes = eigenda_blob.blob.to_vec();
         blob_bytes[0] = 1;
         eigenda_blob.blob = Bytes::from(blob_bytes);
-        
+
         let result = eigenda_blob.decode();
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), BlobDecodingError::InvalidLength);
+        assert_eq!(result.unwrap_err(), BlobDecodingError::InvalidLength.into());
     }
 
     #[test]
     fn test_decode_error_invalid_encoding_version() {
         let rollup_data = vec![1, 2, 3, 4];
-        let mut eigenda_blob = EigenDABlobData::encode(&rollup_data);
+        let mut eigenda_blob = encode_v0(&rollup_data);
         // Modify the encoding version byte to be invalid
         let mut blob_bytes = eigenda_blob.blob.to_vec();
-        blob_bytes[1] = 1; // Invalid version (should be 0)
+        blob_bytes[1] = 2; // Unknown version
         eigenda_blob.blob = Bytes::from(blob_bytes);
-        
+
         let result = eigenda_blob.decode();
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), BlobDecodingError::InvalidLength);
+        assert_eq!(
+            result.unwrap_err(),
+            EigenDADataError::UnknownEncodingVersion(2)
+        );
+        assert_eq!(
+            EigenDABlobData::encode(&rollup_data, 2).unwrap_err(),
+            EigenDADataError::UnknownEncodingVersion(2)
+        );
+    }
+
+    #[test]
+    fn test_version_0_layout_unchanged() {
+        let rollup_data = vec![0xab; 40];
+        let eigenda_blob = encode_v0(&rollup_data);
+        // the header, then the data padded with an empty byte every 31 bytes
+        let mut expected = vec![0u8; 96];
+        expected[5] = 40;
+        expected[33..64].fill(0xab);
+        expected[65..74].fill(0xab);
+        assert_eq!(eigenda_blob.blob, Bytes::from(expected));
+        assert_eq!(eigenda_blob.decode().unwrap(), Bytes::from(rollup_data));
+    }
+
+    #[test]
+    fn test_version_1_unsupported() {
+        let rollup_data = vec![7u8; 100];
+        assert_eq!(
+            EigenDABlobData::encode(&rollup_data, BLOB_ENCODING_VERSION_1).unwrap_err(),
+            EigenDADataError::UnknownEncodingVersion(BLOB_ENCODING_VERSION_1)
+        );
+
+        // a version 0 layout under the version 1 byte is not decoded as version 0
+        let mut blob = encode_v0(&rollup_data).blob.to_vec();
+        blob[1] = BLOB_ENCODING_VERSION_1;
+        assert_eq!(
+            EigenDABlobData::new(Bytes::from(blob))
+                .decode()
+                .unwrap_err(),
+            EigenDADataError::UnknownEncodingVersion(BLOB_ENCODING_VERSION_1)
+        );
+    }
+
+    #[test]
+    fn test_decode_error_insufficient_data() {
+        let rollup_data = vec![7u8; 100];
+        let mut eigenda_blob = encode_v0(&rollup_data);
+        // drop the last field element, the header still claims 100 bytes
+        eigenda_blob
+            .blob
+            .truncate(eigenda_blob.blob.len() - BYTES_PER_FIELD_ELEMENT);
+
+        assert_eq!(
+            eigenda_blob.decode(),
+            Err(EigenDADataError::InsufficientData {
+                expected: 100,
+                actual: 93
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_chunks() {
+        let rollup_data: Vec<u8> = (0..100).collect();
+        let eigenda_blob = encode_v0(&rollup_data);
+
+        let chunks = eigenda_blob
+            .decode_chunks()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let lengths = chunks.iter().map(|chunk| chunk.len()).collect::<Vec<_>>();
+        assert_eq!(lengths, [31, 31, 31, 7]);
+        assert_eq!(chunks.concat(), rollup_data);
+
+        // the data before an invalid field element is yielded, the iterator ends on the error
+        let mut blob_bytes = eigenda_blob.blob.to_vec();
+        blob_bytes[64] = 1;
+        let eigenda_blob = EigenDABlobData::new(Bytes::from(blob_bytes));
+        let mut chunks = eigenda_blob.decode_chunks().unwrap();
+        assert_eq!(chunks.next(), Some(Ok(&rollup_data[..31])));
+        assert_eq!(
+            chunks.next(),
+            Some(Err(BlobDecodingError::InvalidEncodingVersion.into()))
+        );
+        assert_eq!(chunks.next(), None);
+        assert_eq!(
+            eigenda_blob.decode(),
+            Err(BlobDecodingError::InvalidEncodingVersion.into())
+        );
+    }
+
+    #[test]
+    fn test_decode_checks_padding() {
+        let rollup_data = vec![7u8; 10];
+        let mut blob_bytes = encode_v0(&rollup_data).blob.to_vec();
+        // a field element past the data, padding the blob
+        blob_bytes.extend([0xff; BYTES_PER_FIELD_ELEMENT]);
+        let eigenda_blob = EigenDABlobData::new(Bytes::from(blob_bytes));
+        assert_eq!(
+            eigenda_blob.decode(),
+            Err(BlobDecodingError::InvalidFieldElement.into())
+        );
     }
 }
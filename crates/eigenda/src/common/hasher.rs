@@ -0,0 +1,20 @@
+use alloy_primitives::{keccak256, B256};
+
+/// Hash function used to derive the preimage keys of EigenDA blobs.
+///
+/// The host writes the blob preimages under the keys produced by this hasher and the client
+/// looks them up the same way, so both sides must be instantiated with the same implementation.
+pub trait KeyHasher {
+    /// Hashes the given key preimage into a 32 byte preimage key.
+    fn hash_key(data: &[u8]) -> B256;
+}
+
+/// The default [KeyHasher], deriving keys with keccak256.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Keccak256Hasher;
+
+impl KeyHasher for Keccak256Hasher {
+    fn hash_key(data: &[u8]) -> B256 {
+        keccak256(data)
+    }
+}
@@ -0,0 +1,50 @@
+//! Checked conversions of the lengths carried by certs and frame refs to `usize`.
+//!
+//! Fault proof VMs may be 32-bit targets, on which a length close to `u32::MAX` field
+//! elements does not fit a `usize` and a plain `as` cast would silently truncate it.
+
+use crate::{common::BYTES_PER_FIELD_ELEMENT, errors::EigenDAProviderError};
+
+/// Converts a length to `usize`, [EigenDAProviderError::LengthOverflow] if it exceeds the
+/// address space of the target.
+pub fn checked_usize(length: u64) -> Result<usize, EigenDAProviderError> {
+    checked_length(length)
+}
+
+/// Returns the size in bytes of `data_length` field elements, see [checked_usize].
+pub fn field_elements_size(data_length: u32) -> Result<usize, EigenDAProviderError> {
+    checked_usize(u64::from(data_length) * BYTES_PER_FIELD_ELEMENT as u64)
+}
+
+/// Converts a length to the given index type, letting the conversion to a narrower `usize` be
+/// tested on any target.
+fn checked_length<T: TryFrom<u64>>(length: u64) -> Result<T, EigenDAProviderError> {
+    T::try_from(length).map_err(|_| EigenDAProviderError::LengthOverflow(length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_length() {
+        assert_eq!(field_elements_size(4), Ok(4 * BYTES_PER_FIELD_ELEMENT));
+        assert_eq!(checked_usize(u32::MAX as u64), Ok(u32::MAX as usize));
+
+        // a 32-bit usize
+        assert_eq!(checked_length::<u32>(u32::MAX as u64), Ok(u32::MAX));
+        assert_eq!(
+            checked_length::<u32>(u32::MAX as u64 + 1),
+            Err(EigenDAProviderError::LengthOverflow(u32::MAX as u64 + 1))
+        );
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "32")]
+    fn test_field_elements_size_overflow() {
+        assert_eq!(
+            field_elements_size(u32::MAX),
+            Err(EigenDAProviderError::LengthOverflow(u32::MAX as u64 * 32))
+        );
+    }
+}
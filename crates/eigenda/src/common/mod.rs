@@ -1,10 +1,29 @@
 mod constant;
 pub use constant::BLOB_ENCODING_VERSION_0;
+pub use constant::BLOB_ENCODING_VERSION_1;
 pub use constant::BYTES_PER_FIELD_ELEMENT;
+pub use constant::DEFAULT_MAX_COMMITMENT_LENGTH;
+pub use constant::MAX_BLOB_SIZE;
 pub use constant::STALE_GAP;
 
 mod eigenda_data;
-pub use eigenda_data::EigenDABlobData;
+pub use eigenda_data::{EigenDABlobChunks, EigenDABlobData};
 
 mod certificate;
-pub use certificate::BlobInfo;
+pub use certificate::{
+    BatchHeader, BatchMetadata, BlobHeader, BlobInfo, BlobQuorumParam, BlobVerificationProof,
+    G1Commitment,
+};
+
+mod length;
+pub use length::{checked_usize, field_elements_size};
+
+mod hasher;
+pub use hasher::{Keccak256Hasher, KeyHasher};
+
+mod preimage_keys;
+pub use preimage_keys::{
+    blob_complete_key, eigenda_field_element_key, kzg_commitment_key, kzg_opening_key,
+    BLOB_COMPLETE_KEY_TAG, FIELD_ELEMENT_KEY_SIZE, KZG_COMMITMENT_KEY_TAG, KZG_OPENING_KEY_TAG,
+    POPULATION_PROGRESS_KEY_TAG,
+};
@@ -0,0 +1,135 @@
+//! The preimage keys under which the host serves the EigenDA blobs to the client.
+//!
+//! Every key preimage is hashed with the [KeyHasher](crate::common::KeyHasher), the hash being
+//! both the `Keccak256` key of the key preimage itself and the `GlobalGeneric` key of the
+//! value. `x` and `y` are the 32 byte big endian coordinates of the KZG commitment of the cert.
+//!
+//! | value                        | key preimage                     | length |
+//! |------------------------------|----------------------------------|--------|
+//! | field element `i`            | `x ‖ y ‖ 0u8 * 24 ‖ u64 i`       | 96     |
+//! | KZG commitment `x ‖ y`       | `x ‖ y ‖ 0x00`                   | 65     |
+//! | KZG opening `value ‖ proof`  | `x ‖ y ‖ z ‖ 0x01`               | 97     |
+//! | host population progress     | `x ‖ y ‖ 0x02`                   | 65     |
+//! | blob completion marker       | `x ‖ y ‖ 0x03`                   | 65     |
+//!
+//! The KZG opening proves the evaluation of the blob polynomial at the Fiat-Shamir challenge
+//! `z`, the 32 byte big endian field element derived by `compute_challenge` of rust-kzg-bn254
+//! from the blob field elements and the commitment. The client, holding both, derives `z` the
+//! same way to look up the opening. Its value is the 32 byte big endian evaluation at `z`,
+//! followed by the `x ‖ y` coordinates of the proof.
+//!
+//! The population progress, the number of preimages of the blob written so far as a u64, is
+//! only read back by the host to resume an interrupted population. Its key preimage is not
+//! written.
+//!
+//! The completion marker, the data length of the cert as a big endian u64, is written once
+//! every other preimage of the blob is. The client reads it before any field element, so that
+//! the preimages of a population interrupted midway are not read as a blob.
+//!
+//! A blob which does not match its cert, e.g. exceeding its size or committing to another
+//! polynomial, is served as a single all-zero field element `0`. The header of an encoded blob
+//! is never zero, so the client reads this sentinel as an invalid cert.
+
+use crate::common::G1Commitment;
+use alloy_primitives::B256;
+
+/// The length of the key preimage of a blob field element.
+pub const FIELD_ELEMENT_KEY_SIZE: usize = 96;
+
+/// The tag ending the key preimage of a KZG commitment.
+pub const KZG_COMMITMENT_KEY_TAG: u8 = 0x00;
+
+/// The tag ending the key preimage of a KZG opening.
+pub const KZG_OPENING_KEY_TAG: u8 = 0x01;
+
+/// The tag ending the key preimage of the population progress of a blob.
+pub const POPULATION_PROGRESS_KEY_TAG: u8 = 0x02;
+
+/// The tag ending the key preimage of the completion marker of a blob.
+pub const BLOB_COMPLETE_KEY_TAG: u8 = 0x03;
+
+/// Returns the key preimage of the field element `index` of the blob with the given commitment
+/// coordinates: `x ‖ y`, 24 zero bytes and the 8 byte big endian `index`.
+///
+/// The host and the client must derive it identically, else the oracle lookups miss.
+pub fn eigenda_field_element_key(
+    commitment_x: &[u8; 32],
+    commitment_y: &[u8; 32],
+    index: u64,
+) -> [u8; FIELD_ELEMENT_KEY_SIZE] {
+    let mut key = [0u8; FIELD_ELEMENT_KEY_SIZE];
+    key[..32].copy_from_slice(commitment_x);
+    key[32..64].copy_from_slice(commitment_y);
+    key[88..].copy_from_slice(&index.to_be_bytes());
+    key
+}
+
+/// Returns the key preimage of the KZG commitment of the blob with the given commitment.
+pub fn kzg_commitment_key(commitment: &G1Commitment) -> [u8; 65] {
+    let mut key = [0u8; 65];
+    key[..32].copy_from_slice(&commitment.x);
+    key[32..64].copy_from_slice(&commitment.y);
+    key[64] = KZG_COMMITMENT_KEY_TAG;
+    key
+}
+
+/// Returns the key preimage of the completion marker of the blob with the given commitment.
+pub fn blob_complete_key(commitment: &G1Commitment) -> [u8; 65] {
+    let mut key = [0u8; 65];
+    key[..32].copy_from_slice(&commitment.x);
+    key[32..64].copy_from_slice(&commitment.y);
+    key[64] = BLOB_COMPLETE_KEY_TAG;
+    key
+}
+
+/// Returns the key preimage of the KZG opening of the blob with the given commitment at the
+/// challenge `z`.
+pub fn kzg_opening_key(commitment: &G1Commitment, z: &B256) -> [u8; 97] {
+    let mut key = [0u8; 97];
+    key[..32].copy_from_slice(&commitment.x);
+    key[32..64].copy_from_slice(&commitment.y);
+    key[64..96].copy_from_slice(z.as_slice());
+    key[96] = KZG_OPENING_KEY_TAG;
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_element_key_layout() {
+        let key = eigenda_field_element_key(&[0x11; 32], &[0x22; 32], 0x0102_0304_0506_0708);
+        assert_eq!(key[..64], [[0x11; 32], [0x22; 32]].concat());
+        assert_eq!(key[64..88], [0; 24]);
+        assert_eq!(key[88..], [1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn test_kzg_commitment_key_layout() {
+        let commitment = G1Commitment {
+            x: [0x11; 32],
+            y: [0x22; 32],
+        };
+        let key = kzg_commitment_key(&commitment);
+        assert_eq!(key[..64], [[0x11; 32], [0x22; 32]].concat());
+        assert_eq!(key[64], KZG_COMMITMENT_KEY_TAG);
+
+        // the completion marker only differs by its tag
+        let marker = blob_complete_key(&commitment);
+        assert_eq!(marker[..64], key[..64]);
+        assert_eq!(marker[64], BLOB_COMPLETE_KEY_TAG);
+    }
+
+    #[test]
+    fn test_kzg_opening_key_layout() {
+        let commitment = G1Commitment {
+            x: [0x11; 32],
+            y: [0x22; 32],
+        };
+        let key = kzg_opening_key(&commitment, &B256::repeat_byte(0x33));
+        assert_eq!(key[..64], [[0x11; 32], [0x22; 32]].concat());
+        assert_eq!(key[64..96], [0x33; 32]);
+        assert_eq!(key[96], KZG_OPENING_KEY_TAG);
+    }
+}
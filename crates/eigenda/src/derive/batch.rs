@@ -0,0 +1,267 @@
+//! Contains the [BatchingChainProvider].
+
+use crate::derive::BatchChainProvider;
+use alloc::{boxed::Box, collections::BTreeMap, vec::Vec};
+use alloy_consensus::{Header, Receipt, TxEnvelope};
+use alloy_primitives::B256;
+use async_trait::async_trait;
+use kona_derive::traits::ChainProvider;
+use kona_protocol::BlockInfo;
+
+/// A [ChainProvider] looking up blocks by number in batches, to cut the round trips of a
+/// backfill over many blocks.
+///
+/// A lookup by number of a block which is not buffered loads it along with the following
+/// blocks, up to the batch size, in one [BatchChainProvider] request. The transactions of the
+/// buffered blocks are then served by hash. Paired with the prefetch window of the
+/// [EigenDASource](crate::EigenDASource), with a batch size matching the prefetch depth, the
+/// blocks of a window are loaded in a single request.
+///
+/// Blocks are buffered by number and served by hash, so the buffered transactions of a
+/// reorged block are not served. Blocks below the one looked up are evicted.
+#[derive(Debug, Clone)]
+pub struct BatchingChainProvider<P> {
+    /// The underlying chain provider.
+    pub inner: P,
+    /// The number of blocks loaded per request.
+    pub batch_size: u64,
+    /// The blocks loaded ahead, keyed by number.
+    buffered: BTreeMap<u64, (BlockInfo, Vec<TxEnvelope>)>,
+}
+
+impl<P> BatchingChainProvider<P> {
+    /// Creates a new [BatchingChainProvider] loading `batch_size` blocks per request.
+    pub const fn new(inner: P, batch_size: u64) -> Self {
+        Self {
+            inner,
+            batch_size,
+            buffered: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of the buffered block with the given hash.
+    fn buffered_number(&self, hash: B256) -> Option<u64> {
+        self.buffered
+            .iter()
+            .find(|(_, (info, _))| info.hash == hash)
+            .map(|(number, _)| *number)
+    }
+}
+
+#[async_trait]
+impl<P> ChainProvider for BatchingChainProvider<P>
+where
+    P: BatchChainProvider + Send,
+{
+    type Error = P::Error;
+
+    async fn header_by_hash(&mut self, hash: B256) -> Result<Header, Self::Error> {
+        self.inner.header_by_hash(hash).await
+    }
+
+    async fn block_info_by_number(&mut self, number: u64) -> Result<BlockInfo, Self::Error> {
+        if let Some((info, _)) = self.buffered.get(&number) {
+            return Ok(*info);
+        }
+        if self.batch_size > 1 {
+            let last = number.saturating_add(self.batch_size - 1);
+            let blocks = self.inner.blocks_with_transactions(number, last).await?;
+            self.buffered = self.buffered.split_off(&number);
+            self.buffered
+                .extend(blocks.into_iter().map(|block| (block.0.number, block)));
+            if let Some((info, _)) = self.buffered.get(&number) {
+                return Ok(*info);
+            }
+        }
+        self.inner.block_info_by_number(number).await
+    }
+
+    async fn receipts_by_hash(&mut self, hash: B256) -> Result<Vec<Receipt>, Self::Error> {
+        self.inner.receipts_by_hash(hash).await
+    }
+
+    async fn block_info_and_transactions_by_hash(
+        &mut self,
+        hash: B256,
+    ) -> Result<(BlockInfo, Vec<TxEnvelope>), Self::Error> {
+        match self.buffered_number(hash) {
+            Some(number) => Ok(self.buffered.remove(&number).expect("buffered block")),
+            None => self.inner.block_info_and_transactions_by_hash(hash).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        derive::EigenDASourceConfig,
+        test_utils::{
+            batcher_address, batcher_tx, frame_calldata, TestEigenDAProvider, TEST_BATCH_INBOX,
+        },
+        EigenDASource,
+    };
+    use alloc::sync::Arc;
+    use alloy_primitives::Bytes;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use kona_derive::{
+        test_utils::{TestBlobProvider, TestChainProvider},
+        traits::DataAvailabilityProvider,
+    };
+
+    /// A [TestChainProvider] supporting batch requests, counting the requests it and its
+    /// clones serve.
+    #[derive(Debug, Clone, Default)]
+    struct CountingChainProvider {
+        inner: TestChainProvider,
+        requests: Arc<AtomicUsize>,
+    }
+
+    impl CountingChainProvider {
+        fn requests(&self) -> usize {
+            self.requests.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl ChainProvider for CountingChainProvider {
+        type Error = <TestChainProvider as ChainProvider>::Error;
+
+        async fn header_by_hash(&mut self, hash: B256) -> Result<Header, Self::Error> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            self.inner.header_by_hash(hash).await
+        }
+
+        async fn block_info_by_number(&mut self, number: u64) -> Result<BlockInfo, Self::Error> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            self.inner.block_info_by_number(number).await
+        }
+
+        async fn receipts_by_hash(&mut self, hash: B256) -> Result<Vec<Receipt>, Self::Error> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            self.inner.receipts_by_hash(hash).await
+        }
+
+        async fn block_info_and_transactions_by_hash(
+            &mut self,
+            hash: B256,
+        ) -> Result<(BlockInfo, Vec<TxEnvelope>), Self::Error> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            self.inner.block_info_and_transactions_by_hash(hash).await
+        }
+    }
+
+    #[async_trait]
+    impl BatchChainProvider for CountingChainProvider {
+        async fn blocks_with_transactions(
+            &mut self,
+            first: u64,
+            last: u64,
+        ) -> Result<Vec<(BlockInfo, Vec<TxEnvelope>)>, Self::Error> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            let mut blocks = Vec::new();
+            for number in first..=last {
+                let Ok(info) = self.inner.block_info_by_number(number).await else {
+                    break;
+                };
+                blocks.push(
+                    self.inner
+                        .block_info_and_transactions_by_hash(info.hash)
+                        .await?,
+                );
+            }
+            Ok(blocks)
+        }
+    }
+
+    fn test_blocks() -> (CountingChainProvider, Vec<BlockInfo>) {
+        let mut chain = CountingChainProvider::default();
+        let blocks: Vec<BlockInfo> = (10..30)
+            .map(|number| BlockInfo {
+                hash: B256::repeat_byte(number as u8),
+                number,
+                ..Default::default()
+            })
+            .collect();
+        for block in &blocks {
+            let tx = batcher_tx(frame_calldata(&[block.number as u8; 4]), 0);
+            chain
+                .inner
+                .insert_block_with_transactions(block.number, *block, alloc::vec![tx]);
+        }
+        (chain, blocks)
+    }
+
+    /// Derives the blocks with a prefetch depth of 4, returning the data and the chain provider.
+    async fn derive_blocks<F>(chain: F, blocks: &[BlockInfo]) -> (Vec<Bytes>, F)
+    where
+        F: ChainProvider + Clone + Send + 'static,
+    {
+        let config = EigenDASourceConfig {
+            prefetch_depth: 4,
+            ..Default::default()
+        };
+        let mut source = EigenDASource::new(
+            chain,
+            TestBlobProvider::default(),
+            TestEigenDAProvider::default(),
+            TEST_BATCH_INBOX,
+        )
+        .with_config(config);
+
+        let mut data = Vec::new();
+        for block in blocks {
+            while let Ok(item) = source.next(block, batcher_address()).await {
+                data.push(item);
+            }
+            source.clear();
+        }
+        (data, source.chain_provider)
+    }
+
+    #[tokio::test]
+    async fn test_batching_reduces_requests() {
+        let (chain, blocks) = test_blocks();
+        let (unbatched_data, unbatched) = derive_blocks(chain, &blocks).await;
+        let (chain, _) = test_blocks();
+        let (batched_data, batched) =
+            derive_blocks(BatchingChainProvider::new(chain, 4), &blocks).await;
+
+        let expected: Vec<Bytes> = blocks
+            .iter()
+            .map(|block| Bytes::from([block.number as u8; 4]))
+            .collect();
+        assert_eq!(unbatched_data, expected);
+        assert_eq!(batched_data, expected);
+        assert!(
+            batched.inner.requests() * 2 < unbatched.requests(),
+            "{} batched requests, {} unbatched",
+            batched.inner.requests(),
+            unbatched.requests()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reorged_block_not_served_from_batch() {
+        let (chain, blocks) = test_blocks();
+        let mut provider = BatchingChainProvider::new(chain, 4);
+        assert_eq!(provider.block_info_by_number(10).await.unwrap(), blocks[0]);
+        assert_eq!(provider.inner.requests(), 1);
+
+        // buffered blocks are served without a request
+        let (info, txs) = provider
+            .block_info_and_transactions_by_hash(blocks[1].hash)
+            .await
+            .unwrap();
+        assert_eq!((info, txs.len()), (blocks[1], 1));
+        assert_eq!(provider.inner.requests(), 1);
+
+        // a block with another hash is looked up
+        let reorged = B256::repeat_byte(0xee);
+        assert!(provider
+            .block_info_and_transactions_by_hash(reorged)
+            .await
+            .is_err());
+        assert_eq!(provider.inner.requests(), 2);
+    }
+}
@@ -0,0 +1,76 @@
+//! Contains the [SharedBlobCache].
+
+use alloc::{sync::Arc, vec::Vec};
+use core::num::NonZeroUsize;
+use lru::LruCache;
+use spin::Mutex;
+
+/// A cache of EigenDA blobs keyed by commitment, shared by the
+/// [EigenDASource](crate::EigenDASource)s and
+/// [CachingEigenDAProvider](crate::derive::CachingEigenDAProvider)s it is passed to.
+///
+/// Clones share the same blobs, so that a source recreated after a pipeline reset does not
+/// retrieve the blobs of its predecessor again. At most `capacity` blobs are kept, the least
+/// recently used being evicted first.
+#[derive(Debug, Clone)]
+pub struct SharedBlobCache {
+    inner: Arc<Mutex<LruCache<Vec<u8>, Vec<u8>>>>,
+}
+
+impl SharedBlobCache {
+    /// Creates an empty cache keeping at most `capacity` blobs.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(LruCache::new(capacity))),
+        }
+    }
+
+    /// Returns the cached blob of the commitment, if any, marking it as recently used.
+    pub fn get(&self, commitment: &[u8]) -> Option<Vec<u8>> {
+        self.inner.lock().get(commitment).cloned()
+    }
+
+    /// Caches the blob of the commitment, evicting the least recently used blob when full.
+    pub fn insert(&self, commitment: Vec<u8>, blob: Vec<u8>) {
+        self.inner.lock().put(commitment, blob);
+    }
+
+    /// Returns the number of cached blobs.
+    pub fn len(&self) -> usize {
+        self.inner.lock().len()
+    }
+
+    /// Returns whether no blob is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_shared_between_clones() {
+        let cache = SharedBlobCache::new(NonZeroUsize::new(2).unwrap());
+        let clone = cache.clone();
+        cache.insert(vec![1], vec![0xaa]);
+        assert_eq!(clone.get(&[1]), Some(vec![0xaa]));
+        assert_eq!(clone.get(&[2]), None);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = SharedBlobCache::new(NonZeroUsize::new(2).unwrap());
+        cache.insert(vec![1], vec![1]);
+        cache.insert(vec![2], vec![2]);
+        // reading the oldest blob keeps it over the other one
+        assert_eq!(cache.get(&[1]), Some(vec![1]));
+        cache.insert(vec![3], vec![3]);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&[2]), None);
+        assert_eq!(cache.get(&[1]), Some(vec![1]));
+        assert_eq!(cache.get(&[3]), Some(vec![3]));
+    }
+}
@@ -0,0 +1,164 @@
+//! Contains the [CachingEigenDAProvider].
+
+use crate::derive::{EigenDAProvider, SharedBlobCache};
+use alloc::{boxed::Box, vec::Vec};
+use async_trait::async_trait;
+use core::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// An [EigenDAProvider] memoizing the blobs retrieved from the wrapped provider, keyed by
+/// commitment, in a [SharedBlobCache].
+///
+/// At most `capacity` blobs are kept, the least recently used being evicted first. Failed
+/// retrievals are not cached, the next request of the commitment retrying the wrapped
+/// provider.
+#[derive(Debug)]
+pub struct CachingEigenDAProvider<E> {
+    /// The provider the blobs are retrieved from on a miss.
+    pub inner: E,
+    cache: SharedBlobCache,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<E> CachingEigenDAProvider<E> {
+    /// Creates a new [CachingEigenDAProvider] keeping at most `capacity` blobs retrieved from
+    /// `inner`.
+    pub fn new(inner: E, capacity: NonZeroUsize) -> Self {
+        Self::with_cache(inner, SharedBlobCache::new(capacity))
+    }
+
+    /// Creates a new [CachingEigenDAProvider] keeping the blobs retrieved from `inner` in the
+    /// given cache, e.g. the one of the [EigenDASource](crate::EigenDASource)s.
+    pub const fn with_cache(inner: E, cache: SharedBlobCache) -> Self {
+        Self {
+            inner,
+            cache,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the cache of the provider.
+    pub const fn cache(&self) -> &SharedBlobCache {
+        &self.cache
+    }
+
+    /// Returns the number of retrievals served from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of retrievals delegated to the wrapped provider.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl<E> EigenDAProvider for CachingEigenDAProvider<E>
+where
+    E: EigenDAProvider + Send + Sync,
+{
+    type Error = E::Error;
+
+    async fn blob_get(&self, commitment: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        if let Some(blob) = self.cache.get(commitment) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(blob);
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let blob = self.inner.blob_get(commitment).await?;
+        self.cache.insert(commitment.to_vec(), blob.clone());
+        Ok(blob)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{encode_commitment, test_cert, TestEigenDAProvider};
+    use alloc::{sync::Arc, vec};
+
+    #[tokio::test]
+    async fn test_cached_blob_get() {
+        let first = encode_commitment(&test_cert(1));
+        let second = encode_commitment(&test_cert(2));
+        let mut inner = TestEigenDAProvider::default();
+        inner.insert_blob(first.clone(), vec![1]);
+        inner.insert_blob(second.clone(), vec![2]);
+        let provider = CachingEigenDAProvider::new(inner, NonZeroUsize::new(1).unwrap());
+
+        assert_eq!(provider.blob_get(&first).await.unwrap(), vec![1]);
+        assert_eq!(provider.blob_get(&first).await.unwrap(), vec![1]);
+        assert_eq!((provider.hits(), provider.misses()), (1, 1));
+
+        // the second blob evicts the first one
+        assert_eq!(provider.blob_get(&second).await.unwrap(), vec![2]);
+        assert_eq!(provider.blob_get(&first).await.unwrap(), vec![1]);
+        assert_eq!((provider.hits(), provider.misses()), (1, 3));
+    }
+
+    #[tokio::test]
+    async fn test_errors_not_cached() {
+        let commitment = encode_commitment(&test_cert(1));
+        let mut provider = CachingEigenDAProvider::new(
+            TestEigenDAProvider::default(),
+            NonZeroUsize::new(4).unwrap(),
+        );
+        assert!(provider.blob_get(&commitment).await.is_err());
+
+        provider.inner.insert_blob(commitment.clone(), vec![1]);
+        assert_eq!(provider.blob_get(&commitment).await.unwrap(), vec![1]);
+        assert_eq!((provider.hits(), provider.misses()), (0, 2));
+    }
+
+    #[tokio::test]
+    async fn test_shared_cache() {
+        let commitment = encode_commitment(&test_cert(1));
+        let cache = SharedBlobCache::new(NonZeroUsize::new(4).unwrap());
+        cache.insert(commitment.clone(), vec![1]);
+
+        // blobs cached by others are served without retrieving them
+        let provider = CachingEigenDAProvider::with_cache(TestEigenDAProvider::default(), cache);
+        assert_eq!(provider.blob_get(&commitment).await.unwrap(), vec![1]);
+        assert_eq!((provider.hits(), provider.misses()), (1, 0));
+        assert_eq!(provider.cache().len(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_shared_across_tasks() {
+        let commitments: Vec<_> = (1..=8).map(|i| encode_commitment(&test_cert(i))).collect();
+        let mut inner = TestEigenDAProvider::default();
+        for (i, commitment) in commitments.iter().enumerate() {
+            inner.insert_blob(commitment.clone(), vec![i as u8]);
+        }
+        // no lock is needed to share the provider, its cache being interior mutable
+        let provider = Arc::new(CachingEigenDAProvider::new(
+            inner,
+            NonZeroUsize::new(8).unwrap(),
+        ));
+
+        let tasks: Vec<_> = commitments
+            .iter()
+            .chain(&commitments)
+            .cloned()
+            .enumerate()
+            .map(|(i, commitment)| {
+                let provider = provider.clone();
+                tokio::spawn(async move {
+                    let blob = provider.blob_get(&commitment).await.unwrap();
+                    assert_eq!(blob, vec![(i % 8) as u8]);
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(provider.hits() + provider.misses(), 16);
+        assert!(provider.misses() >= 8);
+    }
+}
@@ -0,0 +1,164 @@
+//! Contains the [BlobCompression] of EigenDA blobs.
+
+use crate::{common::MAX_BLOB_SIZE, errors::EigenDAProviderError};
+use alloc::{borrow::Cow, string::ToString};
+use miniz_oxide::inflate::{decompress_to_vec_with_limit, TINFLStatus};
+
+/// The gzip magic bytes.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// The deflate compression method of gzip.
+const GZIP_DEFLATE: u8 = 8;
+/// Gzip header flags, see RFC 1952.
+const FHCRC: u8 = 0x02;
+const FEXTRA: u8 = 0x04;
+const FNAME: u8 = 0x08;
+const FCOMMENT: u8 = 0x10;
+
+/// The compression applied by the batcher to the frames of an EigenDA blob, on top of the
+/// compression of the channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlobCompression {
+    /// The blob carries the RLP encoded frames as is.
+    #[default]
+    None,
+    /// The RLP encoded frames are gzip compressed.
+    Gzip,
+}
+
+impl BlobCompression {
+    /// Decompresses the blob data, which may not expand beyond [MAX_BLOB_SIZE] bytes.
+    pub fn decompress<'a>(&self, data: &'a [u8]) -> Result<Cow<'a, [u8]>, EigenDAProviderError> {
+        self.decompress_with_limit(data, MAX_BLOB_SIZE)
+    }
+
+    /// Decompresses the blob data, aborting once it expands beyond `max_size` bytes rather
+    /// than inflating a decompression bomb. Uncompressed data is returned as is.
+    pub fn decompress_with_limit<'a>(
+        &self,
+        data: &'a [u8],
+        max_size: usize,
+    ) -> Result<Cow<'a, [u8]>, EigenDAProviderError> {
+        match self {
+            Self::None => Ok(Cow::Borrowed(data)),
+            Self::Gzip => gunzip(data, max_size).map(Cow::Owned),
+        }
+    }
+}
+
+/// Decompresses a single gzip member of at most `max_size` bytes, checking its CRC32 and size
+/// trailer.
+fn gunzip(data: &[u8], max_size: usize) -> Result<alloc::vec::Vec<u8>, EigenDAProviderError> {
+    let err = |msg: &str| EigenDAProviderError::DecompressionError(msg.to_string());
+
+    if data.len() < 18 || data[..2] != GZIP_MAGIC || data[2] != GZIP_DEFLATE {
+        return Err(err("not a gzip stream"));
+    }
+    let flags = data[3];
+    let mut pos = 10;
+    if flags & FEXTRA != 0 {
+        let len = data
+            .get(pos..pos + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]) as usize)
+            .ok_or_else(|| err("truncated gzip header"))?;
+        pos += 2 + len;
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            let end = data
+                .get(pos..)
+                .and_then(|rest| rest.iter().position(|&b| b == 0))
+                .ok_or_else(|| err("truncated gzip header"))?;
+            pos += end + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        pos += 2;
+    }
+    if pos + 8 > data.len() {
+        return Err(err("truncated gzip stream"));
+    }
+
+    let (deflated, trailer) = data[pos..].split_at(data.len() - pos - 8);
+    let out = decompress_to_vec_with_limit(deflated, max_size).map_err(|e| match e.status {
+        TINFLStatus::HasMoreOutput => EigenDAProviderError::DecompressedSizeExceeded(max_size),
+        _ => EigenDAProviderError::DecompressionError(alloc::format!("{e:?}")),
+    })?;
+
+    let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+    let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+    if crc32(&out) != crc || out.len() as u32 != size {
+        return Err(err("gzip checksum mismatch"));
+    }
+    Ok(out)
+}
+
+/// Computes the CRC32 (IEEE) checksum of gzip.
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::gzip;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn test_gzip_round_trip() {
+        let data = alloc::vec![7u8; 4096];
+        let compressed = gzip(&data);
+        assert!(compressed.len() < data.len());
+        assert_eq!(
+            BlobCompression::Gzip.decompress(&compressed).unwrap(),
+            &data[..]
+        );
+        assert_eq!(BlobCompression::None.decompress(&data).unwrap(), &data[..]);
+    }
+
+    #[test]
+    fn test_gzip_corrupted() {
+        let mut compressed = gzip(b"some rollup frames");
+        let last = compressed.len() - 5;
+        compressed[last] ^= 0xff;
+        assert!(BlobCompression::Gzip.decompress(&compressed).is_err());
+        assert!(BlobCompression::Gzip
+            .decompress(b"plain data, not gzip")
+            .is_err());
+    }
+
+    #[test]
+    fn test_gzip_size_limit() {
+        let data = alloc::vec![0u8; 64 * 1024];
+        let compressed = gzip(&data);
+        assert!(compressed.len() < 1024);
+
+        let err = BlobCompression::Gzip
+            .decompress_with_limit(&compressed, 1024)
+            .unwrap_err();
+        assert_eq!(err, EigenDAProviderError::DecompressedSizeExceeded(1024));
+        let decompressed = BlobCompression::Gzip
+            .decompress_with_limit(&compressed, data.len())
+            .unwrap();
+        assert_eq!(decompressed, &data[..]);
+        // uncompressed blobs are not bounded
+        assert!(BlobCompression::None
+            .decompress_with_limit(&data, 1024)
+            .is_ok());
+    }
+}
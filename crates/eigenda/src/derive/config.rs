@@ -0,0 +1,371 @@
+//! Contains the [EigenDASourceConfig].
+
+use crate::{
+    common::{BlobInfo, MAX_BLOB_SIZE},
+    derive::{BlobCompression, ValidatedFrameRef},
+    errors::EigenDAProviderError,
+    proto::FrameRef,
+};
+use alloc::vec::Vec;
+use alloy_primitives::Address;
+
+/// A minimum confirmation threshold required for a quorum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuorumThreshold {
+    /// The quorum number.
+    pub quorum_number: u32,
+    /// The minimum confirmation threshold percentage of the quorum.
+    pub confirmation_threshold_percentage: u32,
+}
+
+/// A batch inbox address, used from its activation L1 block on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchInbox {
+    /// The first L1 block whose batcher transactions are sent to the inbox.
+    pub activation_block: u64,
+    /// The address of the batch inbox.
+    pub address: Address,
+}
+
+/// How calldata frames carrying no value, neither a frame nor a frame ref, are handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmptyFramePolicy {
+    /// The batcher tx is skipped with a warning.
+    #[default]
+    Skip,
+    /// Loading the block fails, to surface malformed batcher output.
+    Reject,
+}
+
+/// Rollup specific options of the [EigenDASource](crate::EigenDASource).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EigenDASourceConfig {
+    /// The minimum confirmation thresholds a cert must carry. Frame refs whose cert is below
+    /// any of them are dropped. Certs are not decoded when empty.
+    pub quorum_thresholds: Vec<QuorumThreshold>,
+    /// Legacy mode, also decoding calldata frames which are not prefixed with the EigenDA
+    /// derivation version byte. Only needed to derive the history of early batches.
+    pub allow_unprefixed_frames: bool,
+    /// The number of blocks following the current one whose data is loaded ahead, in the
+    /// background, and buffered, to speed up backfills. No block is loaded ahead when zero.
+    pub prefetch_depth: u64,
+    /// The maximum age in seconds of a cert, measured from the timestamp of its reference
+    /// block to the timestamp of the L1 block carrying the frame ref. Older frame refs are
+    /// dropped. The age is not checked when [None].
+    pub max_cert_age: Option<u64>,
+    /// The compression applied by the batcher to the frames of EigenDA blobs. Only for
+    /// rollups whose batcher compresses its blobs.
+    pub blob_compression: BlobCompression,
+    /// The maximum size in bytes a compressed EigenDA blob may expand to, guarding against
+    /// decompression bombs. Blobs expanding beyond it are rejected. Bounded by
+    /// [MAX_BLOB_SIZE] when [None].
+    pub max_decompressed_size: Option<usize>,
+    /// Verifies the Merkle inclusion proof of certs against the batch root of their batch
+    /// header, dropping frame refs whose proof is invalid.
+    pub verify_inclusion: bool,
+    /// The batch inboxes activated at hardforks. The inbox of a block is the one with the
+    /// latest activation at or before it, falling back to the batch inbox address of the
+    /// source before the first activation.
+    pub batch_inboxes: Vec<BatchInbox>,
+    /// The quorum whose blob params the blob length of frame refs is validated against.
+    /// Frame refs whose cert carries no params for it, or whose blob length exceeds the blob
+    /// on it, are dropped. The blob length is not validated when [None].
+    pub length_quorum: Option<u32>,
+    /// The quorum IDs of frame refs carrying none, for rollups implying the quorums of their
+    /// blobs rather than encoding them. Frame refs without quorum IDs are rejected when empty.
+    pub default_quorum_ids: Vec<u32>,
+    /// The quorums the cert of a frame ref must carry blob params for, for its blob to be
+    /// dispersed to all quorums mandated by the rollup. Frame refs missing any of them, or
+    /// declaring quorums absent from their cert, are dropped, while extra quorums are accepted.
+    pub required_quorum_ids: Vec<u32>,
+    /// The maximum number of EigenDA blobs of a block retrieved concurrently, the frames
+    /// being returned in transaction order regardless. Blobs are retrieved one at a time when
+    /// zero or one.
+    pub blob_fetch_concurrency: usize,
+    /// Retrieves the EigenDA blob of a frame ref once more, bypassing the blob cache, when its
+    /// frames fail to RLP decode, e.g. because the proxy served a corrupt blob. Each blob is
+    /// retrieved at most twice.
+    pub refetch_corrupt_blobs: bool,
+    /// How calldata frames carrying no value are handled.
+    pub empty_frame_policy: EmptyFramePolicy,
+}
+
+impl EigenDASourceConfig {
+    /// Returns the batch inbox of the L1 block `block_number`, `default` if no configured
+    /// inbox is active yet.
+    pub fn batch_inbox(&self, block_number: u64, default: Address) -> Address {
+        self.batch_inboxes
+            .iter()
+            .filter(|inbox| inbox.activation_block <= block_number)
+            .max_by_key(|inbox| inbox.activation_block)
+            .map_or(default, |inbox| inbox.address)
+    }
+
+    /// Returns the maximum size in bytes a compressed EigenDA blob may expand to.
+    pub fn max_decompressed_size(&self) -> usize {
+        self.max_decompressed_size.unwrap_or(MAX_BLOB_SIZE)
+    }
+
+    /// Sets the configured default quorum IDs on a frame ref carrying none.
+    pub fn apply_default_quorum_ids(&self, frame_ref: &mut FrameRef) {
+        if frame_ref.quorum_ids.is_empty() {
+            frame_ref.quorum_ids.clone_from(&self.default_quorum_ids);
+        }
+    }
+
+    /// Checks that the cert was dispersed to all quorums of the configured thresholds, with a
+    /// confirmation threshold at least as high as the required one.
+    pub fn check_quorum_thresholds(&self, cert: &BlobInfo) -> Result<(), EigenDAProviderError> {
+        for required in &self.quorum_thresholds {
+            let actual = cert
+                .quorum_param(required.quorum_number)
+                .map_or(0, |param| param.confirmation_threshold_percentage);
+            if actual < required.confirmation_threshold_percentage {
+                return Err(EigenDAProviderError::ConfirmationThreshold {
+                    quorum: required.quorum_number,
+                    actual,
+                    required: required.confirmation_threshold_percentage,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the cert of the frame ref was dispersed to all of the required quorums, and
+    /// to all of the quorums the frame ref declares, the declared ones being unverified.
+    pub fn check_required_quorums(
+        &self,
+        frame_ref: &ValidatedFrameRef,
+    ) -> Result<(), EigenDAProviderError> {
+        let dispersed = |quorum: &&u32| frame_ref.cert.quorum_param(**quorum).is_some();
+        if let Some(&quorum) = frame_ref.quorum_ids.iter().find(|q| !dispersed(q)) {
+            return Err(EigenDAProviderError::QuorumNotInCert(quorum));
+        }
+        match self.required_quorum_ids.iter().find(|q| !dispersed(q)) {
+            Some(&quorum) => Err(EigenDAProviderError::MissingRequiredQuorum(quorum)),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that the blob length of the frame ref fits the blob its cert commits to on the
+    /// configured length quorum.
+    pub fn check_blob_length(
+        &self,
+        frame_ref: &ValidatedFrameRef,
+    ) -> Result<(), EigenDAProviderError> {
+        let Some(quorum) = self.length_quorum else {
+            return Ok(());
+        };
+        let blob_size = frame_ref.cert.quorum_blob_size(quorum)?;
+        if frame_ref.blob_length > blob_size {
+            return Err(EigenDAProviderError::BlobLengthExceedsCert {
+                quorum,
+                blob_length: frame_ref.blob_length,
+                blob_size,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{common::BlobQuorumParam, test_utils::test_cert};
+
+    fn cert_with_thresholds(thresholds: &[(u32, u32)]) -> BlobInfo {
+        let mut cert = BlobInfo::default();
+        cert.blob_header.blob_quorum_params = thresholds
+            .iter()
+            .map(
+                |&(quorum_number, confirmation_threshold_percentage)| BlobQuorumParam {
+                    quorum_number,
+                    confirmation_threshold_percentage,
+                    ..Default::default()
+                },
+            )
+            .collect();
+        cert
+    }
+
+    fn config(thresholds: &[(u32, u32)]) -> EigenDASourceConfig {
+        EigenDASourceConfig {
+            quorum_thresholds: thresholds
+                .iter()
+                .map(
+                    |&(quorum_number, confirmation_threshold_percentage)| QuorumThreshold {
+                        quorum_number,
+                        confirmation_threshold_percentage,
+                    },
+                )
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check_quorum_thresholds() {
+        let config = config(&[(0, 55), (1, 55)]);
+
+        assert!(config
+            .check_quorum_thresholds(&cert_with_thresholds(&[(0, 55), (1, 60)]))
+            .is_ok());
+        assert_eq!(
+            config.check_quorum_thresholds(&cert_with_thresholds(&[(0, 55), (1, 50)])),
+            Err(EigenDAProviderError::ConfirmationThreshold {
+                quorum: 1,
+                actual: 50,
+                required: 55
+            })
+        );
+        assert_eq!(
+            config.check_quorum_thresholds(&cert_with_thresholds(&[(0, 55)])),
+            Err(EigenDAProviderError::ConfirmationThreshold {
+                quorum: 1,
+                actual: 0,
+                required: 55
+            })
+        );
+        assert!(EigenDASourceConfig::default()
+            .check_quorum_thresholds(&cert_with_thresholds(&[]))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_check_blob_length_of_chosen_quorum() {
+        // a cert dispersed to quorums 0 and 2, of 4 field elements
+        let mut cert = test_cert(4);
+        cert.blob_header.blob_quorum_params[1].quorum_number = 2;
+        cert.blob_header.blob_quorum_params[1].chunk_length = 8;
+        let frame_ref = |blob_length| ValidatedFrameRef {
+            commitment: Default::default(),
+            cert: cert.clone(),
+            blob_length,
+            quorum_ids: alloc::vec![0, 2],
+        };
+        let config = |length_quorum| EigenDASourceConfig {
+            length_quorum,
+            ..Default::default()
+        };
+
+        assert!(config(Some(2)).check_blob_length(&frame_ref(128)).is_ok());
+        assert_eq!(
+            config(Some(2)).check_blob_length(&frame_ref(129)),
+            Err(EigenDAProviderError::BlobLengthExceedsCert {
+                quorum: 2,
+                blob_length: 129,
+                blob_size: 128
+            })
+        );
+        // the cert carries no params for quorum 1
+        assert_eq!(
+            config(Some(1)).check_blob_length(&frame_ref(1)),
+            Err(EigenDAProviderError::MissingQuorumParams(1))
+        );
+        assert!(config(None).check_blob_length(&frame_ref(129)).is_ok());
+    }
+
+    #[test]
+    fn test_check_required_quorums() {
+        let frame_ref = |cert_quorums: &[u32], quorum_ids| {
+            let mut cert = test_cert(1);
+            cert.blob_header.blob_quorum_params = cert_quorums
+                .iter()
+                .map(|&quorum_number| BlobQuorumParam {
+                    quorum_number,
+                    ..Default::default()
+                })
+                .collect();
+            ValidatedFrameRef {
+                commitment: Default::default(),
+                cert,
+                blob_length: 1,
+                quorum_ids,
+            }
+        };
+        let config = EigenDASourceConfig {
+            required_quorum_ids: alloc::vec![0, 1],
+            ..Default::default()
+        };
+
+        assert!(config
+            .check_required_quorums(&frame_ref(&[0, 1], alloc::vec![1, 0]))
+            .is_ok());
+        // extra quorums are accepted
+        assert!(config
+            .check_required_quorums(&frame_ref(&[0, 1, 2], alloc::vec![0, 1, 2]))
+            .is_ok());
+        assert_eq!(
+            config.check_required_quorums(&frame_ref(&[0, 2], alloc::vec![0, 2])),
+            Err(EigenDAProviderError::MissingRequiredQuorum(1))
+        );
+        // the quorums are those of the cert, not the declared ones
+        assert_eq!(
+            config.check_required_quorums(&frame_ref(&[0, 1], alloc::vec![0])),
+            Ok(())
+        );
+        assert_eq!(
+            config.check_required_quorums(&frame_ref(&[0], alloc::vec![0, 1])),
+            Err(EigenDAProviderError::QuorumNotInCert(1))
+        );
+        assert_eq!(
+            EigenDASourceConfig::default()
+                .check_required_quorums(&frame_ref(&[0, 1], alloc::vec![2])),
+            Err(EigenDAProviderError::QuorumNotInCert(2))
+        );
+        assert!(EigenDASourceConfig::default()
+            .check_required_quorums(&frame_ref(&[0], alloc::vec![]))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_apply_default_quorum_ids() {
+        let config = EigenDASourceConfig {
+            default_quorum_ids: alloc::vec![0, 1],
+            ..Default::default()
+        };
+        let mut frame_ref = FrameRef::default();
+        config.apply_default_quorum_ids(&mut frame_ref);
+        assert_eq!(frame_ref.quorum_ids, alloc::vec![0, 1]);
+
+        // encoded quorum IDs are kept
+        let mut frame_ref = FrameRef {
+            quorum_ids: alloc::vec![2],
+            ..Default::default()
+        };
+        config.apply_default_quorum_ids(&mut frame_ref);
+        assert_eq!(frame_ref.quorum_ids, alloc::vec![2]);
+
+        let mut frame_ref = FrameRef::default();
+        EigenDASourceConfig::default().apply_default_quorum_ids(&mut frame_ref);
+        assert!(frame_ref.quorum_ids.is_empty());
+    }
+
+    #[test]
+    fn test_batch_inbox() {
+        let genesis = Address::repeat_byte(1);
+        let (a, b) = (Address::repeat_byte(2), Address::repeat_byte(3));
+        let config = EigenDASourceConfig {
+            // the activations need not be sorted
+            batch_inboxes: alloc::vec![
+                BatchInbox {
+                    activation_block: 200,
+                    address: b
+                },
+                BatchInbox {
+                    activation_block: 100,
+                    address: a
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(config.batch_inbox(99, genesis), genesis);
+        assert_eq!(config.batch_inbox(100, genesis), a);
+        assert_eq!(config.batch_inbox(199, genesis), a);
+        assert_eq!(config.batch_inbox(200, genesis), b);
+        assert_eq!(
+            EigenDASourceConfig::default().batch_inbox(200, genesis),
+            genesis
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Contains the [BatchDataDump] of a block.
+
+use crate::{derive::DaSourceInfo, errors::EigenDAProviderError};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use alloy_primitives::{hex, Bytes};
+
+/// The decoded batch data of a block, in the order derivation consumes it, as handed to a
+/// [BatchDataSink](crate::derive::BatchDataSink).
+///
+/// It is stored as text, one item per line: the 0x-prefixed hex data of the item, followed by
+/// the commitment of the EigenDA blob carrying it, if any. Empty lines and lines starting with
+/// `#` are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchDataDump {
+    /// The items of the block, in order.
+    pub data: Vec<Bytes>,
+    /// The origin of each item in `data`, [None] for inline frames and 4844 blobs.
+    pub sources: Vec<Option<DaSourceInfo>>,
+}
+
+impl BatchDataDump {
+    /// Serializes the dump to its text format.
+    pub fn to_text(&self) -> String {
+        self.data
+            .iter()
+            .zip(&self.sources)
+            .map(|(item, source)| match source {
+                Some(source) => format!(
+                    "{} {}\n",
+                    hex::encode_prefixed(item),
+                    hex::encode_prefixed(&source.commitment)
+                ),
+                None => format!("{}\n", hex::encode_prefixed(item)),
+            })
+            .collect()
+    }
+
+    /// Parses a dump from its text format.
+    pub fn from_text(text: &str) -> Result<Self, EigenDAProviderError> {
+        let mut dump = Self::default();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |reason: &str| {
+                EigenDAProviderError::InvalidBatchDataDump(format!("line {}: {reason}", index + 1))
+            };
+            let mut fields = line.split_whitespace();
+            let (Some(item), commitment, None) = (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(invalid("expected an item and an optional commitment"));
+            };
+            let item = hex::decode(item).map_err(|e| invalid(&e.to_string()))?;
+            let source = commitment
+                .map(|commitment| {
+                    hex::decode(commitment).map(|commitment| DaSourceInfo {
+                        commitment: commitment.into(),
+                    })
+                })
+                .transpose()
+                .map_err(|e| invalid(&e.to_string()))?;
+            dump.data.push(item.into());
+            dump.sources.push(source);
+        }
+        Ok(dump)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_format() {
+        let dump = BatchDataDump {
+            data: alloc::vec![Bytes::from_static(&[1, 2]), Bytes::from_static(&[3])],
+            sources: alloc::vec![
+                None,
+                Some(DaSourceInfo {
+                    commitment: Bytes::from_static(&[0xaa]),
+                })
+            ],
+        };
+        assert_eq!(dump.to_text(), "0x0102\n0x03 0xaa\n");
+        assert_eq!(BatchDataDump::from_text(&dump.to_text()), Ok(dump));
+
+        let err = BatchDataDump::from_text("# block 10\n0x01 0x02 0x03\n").unwrap_err();
+        assert_eq!(
+            err,
+            EigenDAProviderError::InvalidBatchDataDump(
+                "line 2: expected an item and an optional commitment".to_string()
+            )
+        );
+        assert!(BatchDataDump::from_text("0xzz").is_err());
+    }
+}
@@ -1,26 +1,53 @@
 use crate::{
-    derive::{blob_data::BlobData, traits::EigenDAProvider},
-    errors::EigenDAProviderError,
+    common::{BlobInfo, MAX_BLOB_SIZE},
+    derive::{
+        blob_data::BlobData,
+        cache::SharedBlobCache,
+        compression::BlobCompression,
+        config::{EigenDASourceConfig, EmptyFramePolicy},
+        dump::BatchDataDump,
+        frame_ref::{decode_frame_ref, ValidatedFrameRef},
+        signer::AlloySignerRecovery,
+        traits::{BatchDataSink, EigenDAProvider, SignerRecovery},
+    },
+    errors::{EigenDAProviderError, FramePath},
+    metrics,
     proto::{calldata_frame, CalldataFrame},
 };
-use alloc::{boxed::Box, string::ToString, vec::Vec};
-use alloy_consensus::{Transaction, TxEip4844Variant, TxEnvelope, TxType};
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use alloy_consensus::{Transaction, TxEip4844Variant, TxEnvelope};
 use alloy_eips::eip4844::IndexedBlobHash;
-use alloy_primitives::{Address, Bytes};
+use alloy_primitives::{Address, Bytes, B256};
 use async_trait::async_trait;
+use core::{
+    fmt,
+    future::poll_fn,
+    task::{Context, Poll},
+};
+use futures::{
+    future::BoxFuture,
+    stream::{self, FuturesUnordered},
+    FutureExt, Stream, StreamExt,
+};
 use kona_derive::{
-    errors::{BlobProviderError, PipelineError},
+    errors::{BlobProviderError, PipelineError, PipelineErrorKind},
     traits::{BlobProvider, ChainProvider, DataAvailabilityProvider},
     types::PipelineResult,
 };
 use kona_protocol::BlockInfo;
 use prost::Message;
-use rlp::{decode, Decodable, DecoderError};
-use tracing::{debug, warn};
+use rlp::{decode, Decodable, DecoderError, Rlp};
+use tracing::{debug, info, warn};
 
 /// Useful to dinstiguish between plain calldata and alt-da blob refs
 /// Support seamless migration of existing rollups using ETH DA
-const DERIVATION_VERSION_EIGEN_DA: u8 = 0xed;
+pub(crate) const DERIVATION_VERSION_EIGEN_DA: u8 = 0xed;
 
 /// A simple wrapper around Vec<Vec<u8>> to implement Decodable trait for RLP decoding
 struct VecOfBytes(Vec<Vec<u8>>);
@@ -32,12 +59,178 @@ impl Decodable for VecOfBytes {
     }
 }
 
+/// Indexes the blob hashes of a transaction within its block, advancing `next_index` past them.
+///
+/// Invariant: the blobs of a block are indexed across all of its transactions, so every
+/// transaction carrying blobs must advance `next_index`, whether it is a batcher transaction or
+/// not and whether its blobs are used or not. Skipping one would shift the indices of the blobs
+/// of the following batcher transactions, which are then fetched from the wrong slots.
+fn index_blob_hashes(blob_hashes: Vec<B256>, next_index: &mut u64) -> Vec<IndexedBlobHash> {
+    blob_hashes
+        .into_iter()
+        .map(|hash| {
+            let indexed = IndexedBlobHash {
+                hash,
+                index: *next_index,
+            };
+            *next_index += 1;
+            indexed
+        })
+        .collect()
+}
+
+/// Upper bound on the number of frames decoded from a single RLP list, a frame being at
+/// least 23 bytes (16 bytes channel id, 2 bytes frame number, 4 bytes length, 1 byte is_last).
+const MAX_FRAMES: usize = MAX_BLOB_SIZE / 23;
+
+/// Decodes an RLP list of frames, rejecting data larger than [MAX_BLOB_SIZE] or holding more
+/// than [MAX_FRAMES] items before anything is allocated for them.
+///
+/// Decoding failures are tagged with the derivation `path` of the data, to tell a malformed
+/// EigenDA blob from malformed 4844 blobs.
+fn decode_frames(data: &[u8], path: FramePath) -> Result<Vec<Bytes>, EigenDAProviderError> {
+    if data.len() > MAX_BLOB_SIZE {
+        return Err(EigenDAProviderError::FrameDataTooLarge(alloc::format!(
+            "{} bytes exceed the maximum of {MAX_BLOB_SIZE}",
+            data.len()
+        )));
+    }
+    let rlp_error = |e: DecoderError| {
+        warn!(target: "eigen-da-source", path = path.as_str(), "Failed to decode frames: {e}");
+        EigenDAProviderError::RLPDecodeError {
+            path,
+            error: e.to_string(),
+        }
+    };
+    let count = Rlp::new(data).item_count().map_err(rlp_error)?;
+    if count > MAX_FRAMES {
+        return Err(EigenDAProviderError::FrameDataTooLarge(alloc::format!(
+            "{count} frames exceed the maximum of {MAX_FRAMES}"
+        )));
+    }
+
+    let frames: VecOfBytes = decode(data).map_err(rlp_error)?;
+    Ok(frames.0.into_iter().map(Bytes::from).collect())
+}
+
+/// The data of a block along with the origin of each item.
+type BlockData = Vec<(Bytes, Option<DaSourceInfo>)>;
+
+/// An item of a block whose frame ref, if any, was validated but not loaded yet.
+enum PendingItem {
+    /// An inline calldata frame.
+    Frame(Bytes),
+    /// A frame ref whose blob is still to be retrieved.
+    FrameRef(ValidatedFrameRef),
+}
+
+/// A block of the prefetch window along with its data, once loaded in the background.
+type PrefetchedBlock = (
+    BlockInfo,
+    Result<(BlockData, BlockLoadSummary), EigenDAProviderError>,
+);
+
+/// The prefetch window of an [EigenDASource], whose blocks are loaded in the background: the
+/// window is polled along the calls to the source rather than awaited, so that the data of a
+/// block is returned without waiting for the blocks following it.
+///
+/// The blocks are looked up by number one after the other by a copy of the source, the
+/// fetcher, so that a [BatchingChainProvider](crate::derive::BatchingChainProvider) keeps
+/// looking up the window in batches. Their data is then loaded concurrently, each block by its
+/// own copy of the fetcher. The window is created with the first block loaded with a non-zero
+/// prefetch depth.
+struct PrefetchWindow<P>(spin::Mutex<Option<WindowState<P>>>);
+
+/// The lookups and loads in flight of a [PrefetchWindow].
+struct WindowState<P> {
+    /// Looks up the blocks of the window, [None] while a lookup is in flight.
+    fetcher: Option<Box<P>>,
+    /// The number of the block being looked up and the lookup, handing the fetcher back.
+    lookup: Option<(u64, BoxFuture<'static, (Box<P>, Result<BlockInfo, String>)>)>,
+    /// The loads of the looked up blocks.
+    loads: FuturesUnordered<BoxFuture<'static, PrefetchedBlock>>,
+    /// The hashes of the blocks being loaded, keyed by number.
+    loading: BTreeMap<u64, B256>,
+    /// The number of the last block loaded by the source.
+    current: u64,
+    /// The number of the last block of the window.
+    last: u64,
+    /// Whether a block of the window failed to load, stopping the window until the source
+    /// loads the next block.
+    stalled: bool,
+}
+
+impl<P> PrefetchWindow<P> {
+    /// Creates an empty window.
+    const fn new() -> Self {
+        Self(spin::Mutex::new(None))
+    }
+}
+
+/// The work in flight is not cloned, a cloned source starting with an empty window.
+impl<P> Clone for PrefetchWindow<P> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl<P> fmt::Debug for PrefetchWindow<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let window = self.0.lock();
+        f.debug_struct("PrefetchWindow")
+            .field("loading", &window.as_ref().map(|window| &window.loading))
+            .finish_non_exhaustive()
+    }
+}
+
+/// A summary of the data loaded for a block, logged once the block is loaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockLoadSummary {
+    /// The number of transactions of the block scanned for batcher transactions.
+    pub txs: usize,
+    /// The number of inline calldata frames.
+    pub inline_frames: usize,
+    /// The number of frame refs whose EigenDA blob was retrieved and decoded.
+    pub frame_refs: usize,
+    /// The number of 4844 blobs processed.
+    pub blobs: usize,
+    /// The total size in bytes of the items decoded from the block.
+    pub decoded_bytes: usize,
+}
+
+/// Describes where an item returned by [EigenDASource::next_with_source] came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaSourceInfo {
+    /// The EigenDA commitment of the blob carrying the item, as posted by the batcher.
+    pub commitment: Bytes,
+}
+
+impl DaSourceInfo {
+    /// Decodes the v1 cert embedded in the commitment.
+    ///
+    /// The first three bytes of the commitment are metadata (version, OP generic commitment).
+    pub fn cert(&self) -> Result<BlobInfo, alloy_rlp::Error> {
+        BlobInfo::from_commitment(&self.commitment)
+    }
+}
+
+/// An item carried by the batcher transactions of a block, as listed by
+/// [EigenDASource::list_commitments].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListedItem {
+    /// An inline calldata frame.
+    Frame,
+    /// A frame ref to an EigenDA blob.
+    FrameRef(DaSourceInfo),
+}
+
 #[derive(Debug, Clone)]
-pub struct EigenDASource<F, B, E>
+pub struct EigenDASource<F, B, E, S = AlloySignerRecovery>
 where
     F: ChainProvider + Send,
     B: BlobProvider + Send,
-    E: EigenDAProvider + Send,
+    E: EigenDAProvider + Send + Sync,
+    S: SignerRecovery + Send,
 {
     /// Chain provider.
     pub chain_provider: F,
@@ -45,52 +238,332 @@ where
     pub blob_fetcher: B,
     /// Fetches eigen da blobs.
     pub eigen_da_provider: E,
-    /// The address of the batcher contract.
-    pub batcher_address: Address,
+    /// The address of the batch inbox the batcher transactions are sent to, see
+    /// [EigenDASourceConfig::batch_inboxes] for rollups which changed it. The sender of the
+    /// transactions is checked against the batcher address passed to
+    /// [DataAvailabilityProvider::next], the one of the system config at the block.
+    pub batch_inbox_address: Address,
+    /// Rollup specific options.
+    pub config: EigenDASourceConfig,
+    /// Recovers the sender of batcher transactions.
+    pub signer_recovery: S,
     /// Data.
     pub data: Vec<Bytes>,
-    /// Whether the source is open.
-    pub open: bool,
+    /// The origin of each item in `data`, [None] for inline frames and 4844 blobs.
+    pub sources: Vec<Option<DaSourceInfo>>,
+    /// The hash of the block whose data is loaded, [None] once the source is cleared.
+    ///
+    /// Data is loaded again when another block is requested, so that a caller moving to the
+    /// next block without clearing the source is not served the exhausted data of the previous
+    /// one. Frames of a channel spanning several blocks are reassembled by the channel bank.
+    pub open_block: Option<B256>,
+    /// Data of the blocks loaded ahead of the current one, keyed by block number.
+    pub prefetched: BTreeMap<u64, (BlockInfo, BlockData, BlockLoadSummary)>,
+    /// The blocks of the prefetch window being loaded in the background.
+    prefetch_window: PrefetchWindow<Self>,
+    /// The summary of the last loaded block, [None] until a block is loaded.
+    pub last_load_summary: Option<BlockLoadSummary>,
+    /// The EigenDA blobs retrieved by this source and the others sharing the cache, see
+    /// [EigenDASource::new_with_cache].
+    pub blob_cache: Option<SharedBlobCache>,
+    /// Receives the batch data of each loaded block, see [EigenDASource::with_batch_data_sink].
+    pub batch_data_sink: Option<Arc<dyn BatchDataSink>>,
 }
 
 impl<F, B, E> EigenDASource<F, B, E>
 where
     F: ChainProvider + Send,
     B: BlobProvider + Send,
-    E: EigenDAProvider + Send,
+    E: EigenDAProvider + Send + Sync,
 {
-    /// Creates a new [EigenDASource].
+    /// Creates a new [EigenDASource] recovering transaction signers with
+    /// [AlloySignerRecovery].
     pub const fn new(
         chain_provider: F,
         blob_fetcher: B,
         eigen_da_provider: E,
-        batcher_address: Address,
+        batch_inbox_address: Address,
     ) -> Self {
         Self {
             chain_provider,
             blob_fetcher,
             eigen_da_provider,
-            batcher_address,
+            batch_inbox_address,
+            config: EigenDASourceConfig {
+                quorum_thresholds: Vec::new(),
+                allow_unprefixed_frames: false,
+                prefetch_depth: 0,
+                max_cert_age: None,
+                blob_compression: BlobCompression::None,
+                max_decompressed_size: None,
+                verify_inclusion: false,
+                batch_inboxes: Vec::new(),
+                length_quorum: None,
+                default_quorum_ids: Vec::new(),
+                required_quorum_ids: Vec::new(),
+                blob_fetch_concurrency: 0,
+                refetch_corrupt_blobs: false,
+                empty_frame_policy: EmptyFramePolicy::Skip,
+            },
+            signer_recovery: AlloySignerRecovery,
             data: Vec::new(),
-            open: false,
+            sources: Vec::new(),
+            open_block: None,
+            prefetched: BTreeMap::new(),
+            prefetch_window: PrefetchWindow::new(),
+            last_load_summary: None,
+            blob_cache: None,
+            batch_data_sink: None,
+        }
+    }
+
+    /// Creates a new [EigenDASource] like [EigenDASource::new], looking up EigenDA blobs in
+    /// the shared cache before retrieving them.
+    ///
+    /// The cache outlives the source, so that a source recreated on a pipeline reset reuses
+    /// the blobs retrieved by the previous one. Only blobs that decode are cached. The same
+    /// cache can back a [CachingEigenDAProvider](crate::derive::CachingEigenDAProvider).
+    pub fn new_with_cache(
+        chain_provider: F,
+        blob_fetcher: B,
+        eigen_da_provider: E,
+        batch_inbox_address: Address,
+        blob_cache: SharedBlobCache,
+    ) -> Self {
+        Self {
+            blob_cache: Some(blob_cache),
+            ..Self::new(
+                chain_provider,
+                blob_fetcher,
+                eigen_da_provider,
+                batch_inbox_address,
+            )
+        }
+    }
+}
+
+impl<F, B, E, S> EigenDASource<F, B, E, S>
+where
+    F: ChainProvider + Clone + Send + 'static,
+    B: BlobProvider + Clone + Send + 'static,
+    E: EigenDAProvider + Clone + Send + Sync + 'static,
+    S: SignerRecovery + Clone + Send + 'static,
+{
+    /// Sets the rollup specific options of the source.
+    pub fn with_config(mut self, config: EigenDASourceConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Replaces the strategy recovering the sender of batcher transactions.
+    pub fn with_signer_recovery<R>(self, signer_recovery: R) -> EigenDASource<F, B, E, R>
+    where
+        R: SignerRecovery + Send,
+    {
+        EigenDASource {
+            chain_provider: self.chain_provider,
+            blob_fetcher: self.blob_fetcher,
+            eigen_da_provider: self.eigen_da_provider,
+            batch_inbox_address: self.batch_inbox_address,
+            config: self.config,
+            signer_recovery,
+            data: self.data,
+            sources: self.sources,
+            open_block: self.open_block,
+            prefetched: self.prefetched,
+            prefetch_window: PrefetchWindow::new(),
+            last_load_summary: self.last_load_summary,
+            blob_cache: self.blob_cache,
+            batch_data_sink: self.batch_data_sink,
         }
     }
 
+    /// Hands the decoded batch data of each block the source loads to `sink`, for inspection.
+    ///
+    /// Off by default. Each block is recorded once, when loaded, with every item derivation
+    /// will consume from it.
+    pub fn with_batch_data_sink(mut self, sink: Arc<dyn BatchDataSink>) -> Self {
+        self.batch_data_sink = Some(sink);
+        self
+    }
+
+    /// Turns the source into a [Stream] over the data of a block, for stream oriented
+    /// pipelines.
+    ///
+    /// The stream yields the items [DataAvailabilityProvider::next] returns for the block and
+    /// ends at [PipelineError::Eof]. Any other error is yielded and ends the stream.
+    pub fn into_stream(
+        self,
+        block_ref: BlockInfo,
+        batcher_address: Address,
+    ) -> impl Stream<Item = PipelineResult<Bytes>> {
+        stream::unfold(Some(self), move |source| async move {
+            let mut source = source?;
+            match source.next(&block_ref, batcher_address).await {
+                Ok(data) => Some((Ok(data), Some(source))),
+                Err(PipelineErrorKind::Temporary(PipelineError::Eof)) => None,
+                Err(e) => Some((Err(e), None)),
+            }
+        })
+    }
+
+    /// Lists the items carried by the batcher transactions of a block without retrieving any
+    /// blob, e.g. to index the EigenDA commitments of the block.
+    ///
+    /// Frame refs are validated with [decode_frame_ref], but not filtered by the rollup
+    /// specific options. 4844 blobs are not listed.
+    pub async fn list_commitments(
+        &mut self,
+        block_ref: &BlockInfo,
+        batcher_address: Address,
+    ) -> Result<Vec<ListedItem>, EigenDAProviderError> {
+        let (_, txs) = self
+            .chain_provider
+            .block_info_and_transactions_by_hash(block_ref.hash)
+            .await
+            .map_err(|e| EigenDAProviderError::Backend(e.to_string()))?;
+
+        let (values, _) = self.calldata_frames(block_ref, txs, batcher_address)?;
+        values
+            .into_iter()
+            .map(|value| match value {
+                calldata_frame::Value::Frame(_) => Ok(ListedItem::Frame),
+                calldata_frame::Value::FrameRef(mut frame_ref) => {
+                    self.config.apply_default_quorum_ids(&mut frame_ref);
+                    decode_frame_ref(frame_ref).map(|frame_ref| {
+                        ListedItem::FrameRef(DaSourceInfo {
+                            commitment: frame_ref.commitment,
+                        })
+                    })
+                }
+            })
+            .collect()
+    }
+
     /// Extracts the data from the eigen da.
     async fn data_from_eigen_da(
         &mut self,
+        block_ref: &BlockInfo,
         txs: Vec<TxEnvelope>,
         batcher_address: Address,
-    ) -> Result<(Vec<Bytes>, Vec<IndexedBlobHash>), EigenDAProviderError> {
-        let mut data: Vec<Bytes> = Vec::new();
+        summary: &mut BlockLoadSummary,
+    ) -> Result<(BlockData, Vec<IndexedBlobHash>), EigenDAProviderError> {
+        let (values, hashes) = self.calldata_frames(block_ref, txs, batcher_address)?;
+
+        // the frame refs are validated first, their blobs then being retrieved concurrently
+        let mut items = Vec::with_capacity(values.len());
+        for value in values {
+            match value {
+                calldata_frame::Value::Frame(frame) => {
+                    items.push(PendingItem::Frame(Bytes::from(frame)))
+                }
+                calldata_frame::Value::FrameRef(mut frame_ref) => {
+                    self.config.apply_default_quorum_ids(&mut frame_ref);
+                    let frame_ref = match decode_frame_ref(frame_ref) {
+                        Ok(frame_ref) => frame_ref,
+                        Err(e) => {
+                            warn!(target: "eigen-da-source", "Skipping frame ref: {e}");
+                            metrics::frame_skipped("invalid_frame_ref");
+                            continue;
+                        }
+                    };
+                    if let Err(e) = self.config.check_required_quorums(&frame_ref) {
+                        warn!(target: "eigen-da-source", "Skipping frame ref: {e}");
+                        metrics::frame_skipped("required_quorum");
+                        continue;
+                    }
+                    if let Err(e) = self.config.check_quorum_thresholds(&frame_ref.cert) {
+                        warn!(target: "eigen-da-source", "Skipping frame ref: {e}");
+                        metrics::frame_skipped("quorum_threshold");
+                        continue;
+                    }
+                    if let Err(e) = self.config.check_blob_length(&frame_ref) {
+                        warn!(target: "eigen-da-source", "Skipping frame ref: {e}");
+                        metrics::frame_skipped("blob_length");
+                        continue;
+                    }
+                    if let Err(e) = frame_ref.cert.check_recency(block_ref.number) {
+                        warn!(target: "eigen-da-source", "Skipping frame ref: {e}");
+                        metrics::frame_skipped("stale_cert");
+                        continue;
+                    }
+                    if self.config.verify_inclusion {
+                        if let Err(e) = frame_ref.cert.verify_inclusion() {
+                            warn!(target: "eigen-da-source", "Skipping frame ref: {e}");
+                            metrics::frame_skipped("inclusion_proof");
+                            continue;
+                        }
+                    }
+                    if let Some(max_cert_age) = self.config.max_cert_age {
+                        let age = self.cert_age(&frame_ref.cert, block_ref).await?;
+                        if age > max_cert_age {
+                            warn!(
+                                target: "eigen-da-source",
+                                "Skipping frame ref: cert is {age}s old, exceeding {max_cert_age}s"
+                            );
+                            metrics::frame_skipped("cert_age");
+                            continue;
+                        }
+                    }
+                    items.push(PendingItem::FrameRef(frame_ref));
+                }
+            }
+        }
+
+        for item in &items {
+            match item {
+                PendingItem::Frame(_) => summary.inline_frames += 1,
+                PendingItem::FrameRef(_) => summary.frame_refs += 1,
+            }
+        }
+
+        let eigen_da_provider = &self.eigen_da_provider;
+        let blob_cache = self.blob_cache.as_ref();
+        let config = &self.config;
+        let mut loaded = stream::iter(items)
+            .map(|item| async move {
+                match item {
+                    PendingItem::Frame(frame) => Ok(alloc::vec![(frame, None)]),
+                    PendingItem::FrameRef(frame_ref) => {
+                        Self::load_frame_ref(eigen_da_provider, blob_cache, config, &frame_ref)
+                            .await
+                    }
+                }
+            })
+            // buffered rather than unordered, keeping the items in transaction order
+            .buffered(self.config.blob_fetch_concurrency.max(1));
+
+        let mut data: BlockData = Vec::new();
+        while let Some(item) = loaded.next().await {
+            data.extend(item?);
+        }
+        Ok((data, hashes))
+    }
+
+    /// Decodes the calldata frames of the batcher transactions, in transaction order, and
+    /// collects the hashes of the 4844 blobs they carry.
+    ///
+    /// Batcher transactions are the ones sent to the batch inbox of the block by
+    /// `batcher_address`, the batcher of the system config.
+    fn calldata_frames(
+        &self,
+        block_ref: &BlockInfo,
+        txs: Vec<TxEnvelope>,
+        batcher_address: Address,
+    ) -> Result<(Vec<calldata_frame::Value>, Vec<IndexedBlobHash>), EigenDAProviderError> {
+        let mut values = Vec::new();
         let mut hashes = Vec::new();
         let mut index: u64 = 0;
+        let batch_inbox = self
+            .config
+            .batch_inbox(block_ref.number, self.batch_inbox_address);
 
         for tx in txs {
             let (tx_kind, calldata, blob_hashes) = match &tx {
                 TxEnvelope::Legacy(tx) => (tx.tx().to(), tx.tx().input.clone(), None),
                 TxEnvelope::Eip2930(tx) => (tx.tx().to(), tx.tx().input.clone(), None),
                 TxEnvelope::Eip1559(tx) => (tx.tx().to(), tx.tx().input.clone(), None),
+                TxEnvelope::Eip7702(tx) => (tx.tx().to(), tx.tx().input.clone(), None),
                 TxEnvelope::Eip4844(blob_tx_wrapper) => match blob_tx_wrapper.tx() {
                     TxEip4844Variant::TxEip4844(tx) => (
                         tx.to(),
@@ -108,79 +581,186 @@ where
                 },
                 _ => continue,
             };
+            // indexed before any filtering, see `index_blob_hashes`
+            let blob_hashes = index_blob_hashes(blob_hashes.unwrap_or_default(), &mut index);
             let Some(to) = tx_kind else {
-                index += blob_hashes.map_or(0, |h| h.len() as u64);
                 continue;
             };
 
-            if to != self.batcher_address {
-                index += blob_hashes.map_or(0, |h| h.len() as u64);
+            if to != batch_inbox {
                 continue;
             }
 
-            if tx.recover_signer().unwrap_or_default() != batcher_address {
-                index += blob_hashes.map_or(0, |h| h.len() as u64);
+            // a failed recovery must not be compared as the zero address, which a misconfigured
+            // batcher address could match
+            let Some(signer) = self.signer_recovery.recover_signer(&tx) else {
+                warn!(
+                    target: "eigen-da-source",
+                    "Skipping batch inbox tx {} whose signer cannot be recovered",
+                    tx.tx_hash()
+                );
+                metrics::frame_skipped("unrecoverable_signer");
+                continue;
+            };
+            if signer != batcher_address {
+                metrics::frame_skipped("wrong_batcher");
                 continue;
             }
 
+            // only the blobs of batcher txs without calldata carry batch data
             if calldata.is_empty() {
-                if tx.tx_type() == TxType::Eip4844 {
-                    let blob_hashes = if let Some(b) = blob_hashes {
-                        b
-                    } else {
+                hashes.extend(blob_hashes);
+                continue;
+            }
+
+            let calldata_frame = if calldata[0] == DERIVATION_VERSION_EIGEN_DA {
+                CalldataFrame::decode(calldata.slice(1..))
+                    .map_err(|e| EigenDAProviderError::ProtoDecodeError(e.to_string()))?
+            } else if self.config.allow_unprefixed_frames {
+                // legacy batches may carry the calldata frame without the version byte, any
+                // other calldata is not a frame and is ignored
+                match CalldataFrame::decode(calldata.clone()) {
+                    Ok(calldata_frame) => calldata_frame,
+                    Err(e) => {
+                        debug!(target: "eigen-da-source", "Ignoring unprefixed calldata: {e}");
+                        metrics::frame_skipped("unprefixed_calldata");
                         continue;
-                    };
-                    for blob in blob_hashes {
-                        let indexed = IndexedBlobHash { hash: blob, index };
-                        hashes.push(indexed);
-                        index += 1;
                     }
                 }
+            } else {
+                // likely a misconfigured batcher, dropped but reported for the frames not to
+                // vanish silently
+                warn!(
+                    target: "eigen-da-source",
+                    "Skipping batcher tx {} with unknown derivation version {:#04x}",
+                    tx.tx_hash(),
+                    calldata[0]
+                );
+                metrics::frame_skipped("unknown_version");
                 continue;
+            };
+
+            match calldata_frame.value {
+                Some(value) => values.push(value),
+                None if self.config.empty_frame_policy == EmptyFramePolicy::Reject => {
+                    return Err(EigenDAProviderError::EmptyCalldataFrame(*tx.tx_hash()));
+                }
+                None => {
+                    warn!(
+                        target: "eigen-da-source",
+                        "Skipping batcher tx {} with a calldata frame without value",
+                        tx.tx_hash()
+                    );
+                    metrics::frame_skipped("empty_frame");
+                }
             }
+        }
+        Ok((values, hashes))
+    }
 
-            if calldata[0] == DERIVATION_VERSION_EIGEN_DA {
-                let blob_data = calldata.slice(1..);
-                let calldata_frame: CalldataFrame = CalldataFrame::decode(blob_data)
-                    .map_err(|e| EigenDAProviderError::ProtoDecodeError(e.to_string()))?;
-                if let Some(value) = calldata_frame.value {
-                    match value {
-                        calldata_frame::Value::Frame(frame) => data.push(Bytes::from(frame)),
-                        calldata_frame::Value::FrameRef(frame_ref) => {
-                            if frame_ref.quorum_ids.is_empty() {
-                                return Err(EigenDAProviderError::RetrieveFramesFromDaIndexer(
-                                    "decoded frame ref contains no quorum IDs".to_string(),
-                                ));
-                            }
-                            let blob_data = self
-                                .eigen_da_provider
-                                .blob_get(&frame_ref.commitment)
-                                .await
-                                .map_err(|e| EigenDAProviderError::Status(e.to_string()))?;
-                            
-                            let blob_length = frame_ref.blob_length as usize;
-                            if blob_length > blob_data.len() {
-                                return Err(EigenDAProviderError::RetrieveFramesFromDaIndexer(
-                                    alloc::format!(
-                                        "frame_ref.blob_length ({}) exceeds actual blob data length ({})",
-                                        blob_length,
-                                        blob_data.len()
-                                    ),
-                                ));
-                            }
-                            
-                            let blobs = &blob_data[..blob_length];
-                            let blob_data: VecOfBytes = decode(blobs)
-                                .map_err(|e| EigenDAProviderError::RLPDecodeError(e.to_string()))?;
-                            for blob in blob_data.0 {
-                                data.push(Bytes::from(blob));
-                            }
-                        }
-                    }
+    /// Returns the age in seconds of the cert at `block_ref`, from the timestamp of the L1
+    /// block the cert references.
+    async fn cert_age(
+        &mut self,
+        cert: &BlobInfo,
+        block_ref: &BlockInfo,
+    ) -> Result<u64, EigenDAProviderError> {
+        let reference_block = self
+            .chain_provider
+            .block_info_by_number(cert.reference_block_number())
+            .await
+            .map_err(|e| EigenDAProviderError::Backend(e.to_string()))?;
+        Ok(block_ref
+            .timestamp
+            .saturating_sub(reference_block.timestamp))
+    }
+
+    /// Retrieves the blob of a frame ref and decodes the frames it carries, along with their
+    /// origin.
+    ///
+    /// With [EigenDASourceConfig::refetch_corrupt_blobs], a blob whose frames fail to RLP
+    /// decode is retrieved once more before giving up. Blobs are only cached once decoded.
+    async fn load_frame_ref(
+        eigen_da_provider: &E,
+        blob_cache: Option<&SharedBlobCache>,
+        config: &EigenDASourceConfig,
+        frame_ref: &ValidatedFrameRef,
+    ) -> Result<BlockData, EigenDAProviderError> {
+        let cached = blob_cache.and_then(|cache| cache.get(&frame_ref.commitment));
+        let (blob_data, frames) = match cached {
+            Some(blob_data) => {
+                let frames = Self::decode_blob(&blob_data, frame_ref, config);
+                (blob_data, frames)
+            }
+            None => {
+                let blob_data = Self::retrieve_blob(eigen_da_provider, frame_ref).await?;
+                let frames = Self::decode_blob(&blob_data, frame_ref, config);
+                if let (Some(cache), Ok(_)) = (blob_cache, &frames) {
+                    cache.insert(frame_ref.commitment.to_vec(), blob_data.clone());
+                }
+                (blob_data, frames)
+            }
+        };
+        let frames = match frames {
+            Err(e @ EigenDAProviderError::RLPDecodeError { .. })
+                if config.refetch_corrupt_blobs =>
+            {
+                warn!(
+                    target: "eigen-da-source",
+                    blob_size = blob_data.len(),
+                    "Retrieving the EigenDA blob again: {e}"
+                );
+                let blob_data = Self::retrieve_blob(eigen_da_provider, frame_ref).await?;
+                let frames = Self::decode_blob(&blob_data, frame_ref, config)?;
+                if let Some(cache) = blob_cache {
+                    cache.insert(frame_ref.commitment.to_vec(), blob_data);
                 }
+                frames
             }
+            frames => frames?,
+        };
+
+        let source = DaSourceInfo {
+            commitment: frame_ref.commitment.clone(),
+        };
+        Ok(frames
+            .into_iter()
+            .map(|frame| (frame, Some(source.clone())))
+            .collect())
+    }
+
+    /// Retrieves the blob of a frame ref from EigenDA.
+    async fn retrieve_blob(
+        eigen_da_provider: &E,
+        frame_ref: &ValidatedFrameRef,
+    ) -> Result<Vec<u8>, EigenDAProviderError> {
+        eigen_da_provider
+            .blob_get(&frame_ref.commitment)
+            .await
+            .map_err(|e| EigenDAProviderError::Status(e.to_string()))
+    }
+
+    /// Decodes the frames carried by the blob of a frame ref.
+    fn decode_blob(
+        blob_data: &[u8],
+        frame_ref: &ValidatedFrameRef,
+        config: &EigenDASourceConfig,
+    ) -> Result<Vec<Bytes>, EigenDAProviderError> {
+        let blob_length = frame_ref.blob_length;
+        if blob_length > blob_data.len() {
+            return Err(EigenDAProviderError::RetrieveFramesFromDaIndexer(
+                alloc::format!(
+                    "frame_ref.blob_length ({}) exceeds actual blob data length ({})",
+                    blob_length,
+                    blob_data.len()
+                ),
+            ));
         }
-        Ok((data, hashes))
+
+        let blob = config
+            .blob_compression
+            .decompress_with_limit(&blob_data[..blob_length], config.max_decompressed_size())?;
+        decode_frames(&blob, FramePath::FrameRef)
     }
 
     /// Loads the blobs from the eigen da.
@@ -189,17 +769,217 @@ where
         block_ref: &BlockInfo,
         batcher_address: Address,
     ) -> Result<(), EigenDAProviderError> {
-        if self.open {
+        self.await_prefetch(block_ref, batcher_address).await;
+        if self.open_block == Some(block_ref.hash) {
             return Ok(());
         }
+
+        let (blob_data, summary) = match self.prefetched.remove(&block_ref.number) {
+            Some((block, data, summary)) if block.hash == block_ref.hash => (data, summary),
+            _ => self.fetch_block(block_ref, batcher_address).await?,
+        };
+        self.open_block = Some(block_ref.hash);
+        info!(
+            target: "eigen-da-source",
+            block_hash = %block_ref.hash,
+            txs = summary.txs,
+            inline_frames = summary.inline_frames,
+            frame_refs = summary.frame_refs,
+            blobs = summary.blobs,
+            decoded_bytes = summary.decoded_bytes,
+            "Loaded block data"
+        );
+        self.last_load_summary = Some(summary);
+        (self.data, self.sources) = blob_data.into_iter().unzip();
+        if let Some(sink) = &self.batch_data_sink {
+            let dump = BatchDataDump {
+                data: self.data.clone(),
+                sources: self.sources.clone(),
+            };
+            sink.record(block_ref, &dump);
+        }
+
+        self.prefetch(block_ref);
+        self.await_prefetch(block_ref, batcher_address).await;
+        Ok(())
+    }
+
+    /// Moves the prefetch window to the blocks following `block_ref`, up to the configured
+    /// prefetch depth, and evicts the data of the blocks up to `block_ref`.
+    ///
+    /// The blocks of the window are loaded in the background, see [PrefetchWindow].
+    /// Prefetching is best effort: it stops at the first block which cannot be loaded, e.g.
+    /// because it does not exist yet, until the next block is loaded. Buffered data is only
+    /// used if the hash of the block matches, so data prefetched before a reorg is discarded.
+    fn prefetch(&mut self, block_ref: &BlockInfo) {
+        self.prefetched = self.prefetched.split_off(&(block_ref.number + 1));
+        if self.config.prefetch_depth == 0 {
+            return;
+        }
+
+        if self.prefetch_window.0.get_mut().is_none() {
+            let window = WindowState {
+                fetcher: Some(Box::new(self.fetcher())),
+                lookup: None,
+                loads: FuturesUnordered::new(),
+                loading: BTreeMap::new(),
+                current: 0,
+                last: 0,
+                stalled: false,
+            };
+            *self.prefetch_window.0.get_mut() = Some(window);
+        }
+        if let Some(window) = self.prefetch_window.0.get_mut() {
+            window.current = block_ref.number;
+            window.last = block_ref.number.saturating_add(self.config.prefetch_depth);
+            window.stalled = false;
+        }
+    }
+
+    /// Polls the prefetch window, waiting only if `block_ref` is being loaded in it.
+    async fn await_prefetch(&mut self, block_ref: &BlockInfo, batcher_address: Address) {
+        poll_fn(|cx| {
+            self.poll_prefetch(cx, batcher_address);
+            let loading = self
+                .prefetch_window
+                .0
+                .get_mut()
+                .as_ref()
+                .and_then(|window| window.loading.get(&block_ref.number).copied());
+            if loading == Some(block_ref.hash) {
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        })
+        .await
+    }
+
+    /// Polls the lookups and loads of the prefetch window without waiting: the loaded blocks
+    /// are moved to `prefetched`, the looked up ones start loading and the next block of the
+    /// window is looked up.
+    fn poll_prefetch(&mut self, cx: &mut Context<'_>, batcher_address: Address) {
+        let Some(window) = self.prefetch_window.0.get_mut() else {
+            return;
+        };
+        loop {
+            let mut progress = false;
+            let looked_up = match &mut window.lookup {
+                Some((number, lookup)) => match lookup.poll_unpin(cx) {
+                    Poll::Ready(result) => Some((*number, result)),
+                    Poll::Pending => None,
+                },
+                None => None,
+            };
+            if let Some((number, (fetcher, result))) = looked_up {
+                window.lookup = None;
+                match result {
+                    Ok(block) if number > window.current => {
+                        let mut loader = fetcher.fetcher();
+                        window.loading.insert(number, block.hash);
+                        let load = async move {
+                            let result = loader.fetch_block(&block, batcher_address).await;
+                            (block, result)
+                        };
+                        window.loads.push(load.boxed());
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        debug!(
+                            target: "eigen-da-source",
+                            "Stopping prefetch at block {number}: {e}"
+                        );
+                        window.stalled = true;
+                    }
+                }
+                window.fetcher = Some(fetcher);
+                progress = true;
+            }
+
+            let next = (window.current + 1..=window.last).find(|number| {
+                !self.prefetched.contains_key(number) && !window.loading.contains_key(number)
+            });
+            if let (Some(number), false) = (next, window.stalled) {
+                if let Some(mut fetcher) = window.fetcher.take() {
+                    let lookup = async move {
+                        let result = fetcher
+                            .chain_provider
+                            .block_info_by_number(number)
+                            .await
+                            .map_err(|e| e.to_string());
+                        (fetcher, result)
+                    };
+                    window.lookup = Some((number, lookup.boxed()));
+                    progress = true;
+                }
+            }
+
+            while let Poll::Ready(Some((block, result))) = window.loads.poll_next_unpin(cx) {
+                window.loading.remove(&block.number);
+                match result {
+                    Ok((data, summary)) if block.number > window.current => {
+                        self.prefetched.insert(block.number, (block, data, summary));
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        debug!(
+                            target: "eigen-da-source",
+                            "Stopping prefetch at block {}: {e}",
+                            block.number
+                        );
+                        window.stalled = true;
+                    }
+                }
+            }
+
+            if !progress {
+                return;
+            }
+        }
+    }
+
+    /// Returns a copy of the source without any block state, loading the blocks of the
+    /// prefetch window.
+    fn fetcher(&self) -> Self {
+        Self {
+            chain_provider: self.chain_provider.clone(),
+            blob_fetcher: self.blob_fetcher.clone(),
+            eigen_da_provider: self.eigen_da_provider.clone(),
+            batch_inbox_address: self.batch_inbox_address,
+            config: self.config.clone(),
+            signer_recovery: self.signer_recovery.clone(),
+            data: Vec::new(),
+            sources: Vec::new(),
+            open_block: None,
+            prefetched: BTreeMap::new(),
+            prefetch_window: PrefetchWindow::new(),
+            last_load_summary: None,
+            blob_cache: self.blob_cache.clone(),
+            batch_data_sink: None,
+        }
+    }
+
+    /// Retrieves the data of a block from its calldata, EigenDA and 4844 blobs, along with the
+    /// summary of the loaded data.
+    async fn fetch_block(
+        &mut self,
+        block_ref: &BlockInfo,
+        batcher_address: Address,
+    ) -> Result<(BlockData, BlockLoadSummary), EigenDAProviderError> {
         let info = self
             .chain_provider
             .block_info_and_transactions_by_hash(block_ref.hash)
             .await
             .map_err(|e| EigenDAProviderError::Backend(e.to_string()))?;
 
-        let (mut blob_data, blob_hashes) = self.data_from_eigen_da(info.1, batcher_address).await?;
-        debug!(target: "eigen-da-source", "loading eigen blobs blob hashes len {}, blob data len {}", blob_hashes.len(), blob_data.len());
+        let mut summary = BlockLoadSummary {
+            txs: info.1.len(),
+            ..Default::default()
+        };
+        let (mut blob_data, blob_hashes) = self
+            .data_from_eigen_da(block_ref, info.1, batcher_address, &mut summary)
+            .await?;
+        summary.blobs = blob_hashes.len();
 
         if !blob_hashes.is_empty() {
             let blobs = self
@@ -235,43 +1015,31 @@ where
                 }
             }
 
-            let rlp_blob: VecOfBytes = decode(&whole_blob_data)
-                .map_err(|e| EigenDAProviderError::RLPDecodeError(e.to_string()))?;
-
-            for blob in rlp_blob.0 {
-                blob_data.push(Bytes::from(blob));
+            for frame in decode_frames(&whole_blob_data, FramePath::Blob4844)? {
+                blob_data.push((frame, None));
             }
         }
-        self.open = true;
-        debug!(target: "eigen-da-source", "loaded eigen blobs blob data len {}", blob_data.len());
-        self.data = blob_data;
-        Ok(())
+        summary.decoded_bytes = blob_data.iter().map(|(item, _)| item.len()).sum();
+        Ok((blob_data, summary))
     }
 
     /// Extracts the next data from the source.
-    fn next_data(&mut self) -> Result<Bytes, PipelineResult<Bytes>> {
+    fn next_data(&mut self) -> PipelineResult<(Bytes, Option<DaSourceInfo>)> {
         if self.data.is_empty() {
-            return Err(Err(PipelineError::Eof.temp()));
+            return Err(PipelineError::Eof.temp());
         }
 
-        Ok(self.data.remove(0))
+        Ok((self.data.remove(0), self.sources.remove(0)))
     }
-}
 
-#[async_trait]
-impl<F, B, E> DataAvailabilityProvider for EigenDASource<F, B, E>
-where
-    F: ChainProvider + Send,
-    B: BlobProvider + Send,
-    E: EigenDAProvider + Send,
-{
-    type Item = Bytes;
-
-    async fn next(
+    /// Returns the next item like [DataAvailabilityProvider::next], along with the EigenDA
+    /// commitment it was retrieved with. Inline calldata frames and 4844 blob data have no
+    /// EigenDA origin and are returned with [None].
+    pub async fn next_with_source(
         &mut self,
         block_ref: &BlockInfo,
         batcher_address: Address,
-    ) -> PipelineResult<Self::Item> {
+    ) -> PipelineResult<(Bytes, Option<DaSourceInfo>)> {
         let result = self.load_blobs(block_ref, batcher_address).await;
         match result {
             Ok(_) => (),
@@ -286,17 +1054,1314 @@ where
             }
         }
 
-        let next_data = match self.next_data() {
-            Ok(d) => d,
-            Err(e) => return e,
-        };
         //TODO EigenDA decode
+        self.next_data()
+    }
+}
 
-        Ok(next_data)
+#[async_trait]
+impl<F, B, E, S> DataAvailabilityProvider for EigenDASource<F, B, E, S>
+where
+    F: ChainProvider + Clone + Send + 'static,
+    B: BlobProvider + Clone + Send + 'static,
+    E: EigenDAProvider + Clone + Send + Sync + 'static,
+    S: SignerRecovery + Clone + Send + 'static,
+{
+    type Item = Bytes;
+
+    async fn next(
+        &mut self,
+        block_ref: &BlockInfo,
+        batcher_address: Address,
+    ) -> PipelineResult<Self::Item> {
+        self.next_with_source(block_ref, batcher_address)
+            .await
+            .map(|(data, _)| data)
     }
 
     fn clear(&mut self) {
         self.data.clear();
-        self.open = false;
+        self.sources.clear();
+        self.open_block = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        common::BlobQuorumParam,
+        derive::{BatchInbox, QuorumThreshold},
+        test_utils::{
+            batcher_address, batcher_signer, batcher_tx, encode_commitment, encode_frames,
+            frame_calldata, frame_ref_calldata, gzip, signed_tx, test_cert, TestEigenDAProvider,
+            TEST_BATCH_INBOX,
+        },
+    };
+    use alloy_consensus::{SignableTransaction, TxEip1559, TxEip4844, TxEip7702};
+    use alloy_primitives::{Signature, TxKind};
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
+    use kona_derive::test_utils::{TestBlobProvider, TestChainProvider};
+    use kona_protocol::{Channel, Frame};
+
+    type TestSource<S = AlloySignerRecovery> =
+        EigenDASource<TestChainProvider, TestBlobProvider, TestEigenDAProvider, S>;
+
+    fn test_block() -> BlockInfo {
+        BlockInfo {
+            hash: B256::repeat_byte(0xbb),
+            number: 10,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the frames, the blob encoding them, the commitment of `test_cert(cert_id)` and a
+    /// provider serving the blob under the commitment.
+    fn frames_fixture(
+        frames: Vec<Vec<u8>>,
+        cert_id: u32,
+    ) -> (Vec<Vec<u8>>, Vec<u8>, Vec<u8>, TestEigenDAProvider) {
+        let blob = encode_frames(&frames);
+        let commitment = encode_commitment(&test_cert(cert_id));
+        let mut eigen_da = TestEigenDAProvider::default();
+        eigen_da.insert_blob(commitment.clone(), blob.clone());
+        (frames, blob, commitment, eigen_da)
+    }
+
+    /// Returns the [frames_fixture] of a single frame of 8 bytes.
+    fn single_frame_fixture(cert_id: u32) -> (Vec<Vec<u8>>, Vec<u8>, Vec<u8>, TestEigenDAProvider) {
+        frames_fixture(alloc::vec![alloc::vec![1u8; 8]], cert_id)
+    }
+
+    fn test_source(txs: Vec<TxEnvelope>, eigen_da: TestEigenDAProvider) -> TestSource {
+        let mut chain = TestChainProvider::default();
+        let block = test_block();
+        chain.insert_block_with_transactions(block.number, block, txs);
+        EigenDASource::new(
+            chain,
+            TestBlobProvider::default(),
+            eigen_da,
+            TEST_BATCH_INBOX,
+        )
+    }
+
+    async fn drain<S: SignerRecovery + Send>(
+        source: &mut TestSource<S>,
+        block: &BlockInfo,
+    ) -> Vec<Bytes> {
+        let mut out = Vec::new();
+        while let Ok(data) = source.next(block, batcher_address()).await {
+            out.push(data);
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn test_next_frames_and_frame_refs_in_order() {
+        let (frames, blob, commitment, eigen_da) =
+            frames_fixture(alloc::vec![alloc::vec![1u8; 8], alloc::vec![2u8; 8]], 1);
+
+        let txs = alloc::vec![
+            batcher_tx(frame_calldata(&[0u8; 4]), 0),
+            batcher_tx(
+                frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0]),
+                1
+            ),
+        ];
+        let mut source = test_source(txs, eigen_da);
+
+        let data = drain(&mut source, &test_block()).await;
+        assert_eq!(
+            data,
+            alloc::vec![
+                Bytes::from(alloc::vec![0u8; 4]),
+                Bytes::from(frames[0].clone()),
+                Bytes::from(frames[1].clone())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_channel_spanning_blocks() {
+        // frames carry the derivation version the frame queue expects
+        let frame = |number, data: [u8; 4], is_last| {
+            let frame = Frame {
+                id: [0xcc; 16],
+                number,
+                data: data.to_vec(),
+                is_last,
+            };
+            [alloc::vec![0u8], frame.encode()].concat()
+        };
+        let (_, blob, commitment, eigen_da) =
+            frames_fixture(alloc::vec![frame(0, [1u8; 4], false)], 2);
+
+        // the first frame is referenced in block 10, the last one is inline in block 11
+        let blocks = [10, 11].map(|number| BlockInfo {
+            hash: B256::repeat_byte(number as u8),
+            number,
+            ..Default::default()
+        });
+        let mut chain = TestChainProvider::default();
+        let tx = batcher_tx(
+            frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0]),
+            0,
+        );
+        chain.insert_block_with_transactions(10, blocks[0], alloc::vec![tx]);
+        let tx = batcher_tx(frame_calldata(&frame(1, [2u8; 4], true)), 1);
+        chain.insert_block_with_transactions(11, blocks[1], alloc::vec![tx]);
+
+        for (prefetch_depth, clear) in [(0, true), (1, true), (0, false), (1, false)] {
+            let config = EigenDASourceConfig {
+                prefetch_depth,
+                ..Default::default()
+            };
+            let mut source = EigenDASource::new(
+                chain.clone(),
+                TestBlobProvider::default(),
+                eigen_da.clone(),
+                TEST_BATCH_INBOX,
+            )
+            .with_config(config);
+
+            // read each block until EOF as the L1 retrieval stage does, which clears the
+            // source before moving to the next block
+            let mut channel = Channel::new([0xcc; 16], blocks[0]);
+            for block in &blocks {
+                while let Ok(data) = source.next(block, batcher_address()).await {
+                    for frame in Frame::parse_frames(&data).unwrap() {
+                        channel.add_frame(frame, *block).unwrap();
+                    }
+                }
+                if clear {
+                    source.clear();
+                }
+            }
+            assert!(
+                channel.is_ready(),
+                "prefetch depth {prefetch_depth}, clear {clear}"
+            );
+            assert_eq!(
+                channel.frame_data().unwrap(),
+                Bytes::from([1, 1, 1, 1, 2, 2, 2, 2])
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_into_stream() {
+        let (_, blob, commitment, eigen_da) =
+            frames_fixture(alloc::vec![alloc::vec![1u8; 8], alloc::vec![2u8; 8]], 1);
+        let txs = alloc::vec![
+            batcher_tx(frame_calldata(&[0u8; 4]), 0),
+            batcher_tx(
+                frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0]),
+                1
+            ),
+        ];
+
+        let mut source = test_source(txs.clone(), eigen_da.clone());
+        let expected = drain(&mut source, &test_block()).await;
+        assert_eq!(expected.len(), 3);
+
+        let streamed: Vec<Bytes> = test_source(txs, eigen_da)
+            .into_stream(test_block(), batcher_address())
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(streamed, expected);
+
+        // a failing block ends the stream after the error
+        let source = test_source(Vec::new(), TestEigenDAProvider::default())
+            .into_stream(BlockInfo::default(), batcher_address());
+        let mut stream = core::pin::pin!(source);
+        assert!(stream.next().await.unwrap().is_err());
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_next_with_source() {
+        let (frames, blob, commitment, eigen_da) = single_frame_fixture(1);
+
+        let txs = alloc::vec![
+            batcher_tx(frame_calldata(&[0u8; 4]), 0),
+            batcher_tx(
+                frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0]),
+                1
+            ),
+        ];
+        let mut source = test_source(txs, eigen_da);
+        let block = test_block();
+
+        let (inline, info) = source
+            .next_with_source(&block, batcher_address())
+            .await
+            .unwrap();
+        assert_eq!(inline, Bytes::from(alloc::vec![0u8; 4]));
+        assert_eq!(info, None);
+
+        let (frame, info) = source
+            .next_with_source(&block, batcher_address())
+            .await
+            .unwrap();
+        assert_eq!(frame, Bytes::from(frames[0].clone()));
+        assert_eq!(
+            info,
+            Some(DaSourceInfo {
+                commitment: Bytes::from(commitment)
+            })
+        );
+        assert_eq!(info.unwrap().cert().unwrap(), test_cert(1));
+
+        assert!(source
+            .next_with_source(&block, batcher_address())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_frame_ref_below_confirmation_threshold_dropped() {
+        let (frames, blob, commitment, eigen_da) = single_frame_fixture(1);
+        let txs = alloc::vec![batcher_tx(
+            frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0, 1]),
+            0
+        )];
+        let block = test_block();
+
+        // the test cert is confirmed at 55% on quorums 0 and 1
+        let config = |confirmation_threshold_percentage| EigenDASourceConfig {
+            quorum_thresholds: alloc::vec![QuorumThreshold {
+                quorum_number: 1,
+                confirmation_threshold_percentage,
+            }],
+            ..Default::default()
+        };
+
+        let mut source = test_source(txs.clone(), eigen_da.clone()).with_config(config(55));
+        assert_eq!(
+            drain(&mut source, &block).await,
+            alloc::vec![Bytes::from(frames[0].clone())]
+        );
+
+        let mut source = test_source(txs, eigen_da).with_config(config(60));
+        assert!(drain(&mut source, &block).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_frame_ref_dropped() {
+        let (frames, blob, commitment, eigen_da) = single_frame_fixture(1);
+        let txs = alloc::vec![
+            batcher_tx(frame_ref_calldata(&commitment, 0, alloc::vec![0]), 0),
+            batcher_tx(frame_ref_calldata(&commitment[..3], 8, alloc::vec![0]), 1),
+            batcher_tx(
+                frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0]),
+                2
+            ),
+        ];
+
+        // the invalid frame refs are skipped without failing the block
+        let mut source = test_source(txs, eigen_da);
+        assert_eq!(
+            drain(&mut source, &test_block()).await,
+            alloc::vec![Bytes::from(frames[0].clone())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_frame_ref_default_quorum_ids() {
+        let (frames, blob, commitment, eigen_da) = single_frame_fixture(1);
+        // the frame ref carries no quorum IDs
+        let txs = alloc::vec![batcher_tx(
+            frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![]),
+            0
+        )];
+        let block = test_block();
+
+        // the frame ref is skipped without default quorum IDs
+        let mut source = test_source(txs.clone(), eigen_da.clone());
+        assert!(drain(&mut source, &block).await.is_empty());
+
+        let config = EigenDASourceConfig {
+            default_quorum_ids: alloc::vec![0, 1],
+            ..Default::default()
+        };
+        let mut source = test_source(txs, eigen_da).with_config(config);
+        assert_eq!(
+            drain(&mut source, &block).await,
+            alloc::vec![Bytes::from(frames[0].clone())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_frame_ref_missing_required_quorum_dropped() {
+        let frames = alloc::vec![alloc::vec![1u8; 8]];
+        let blob = encode_frames(&frames);
+        let cert_with_quorums = |quorums: &[u32]| {
+            let mut cert = test_cert(1);
+            cert.blob_header.blob_quorum_params = quorums
+                .iter()
+                .map(|&quorum_number| BlobQuorumParam {
+                    quorum_number,
+                    ..Default::default()
+                })
+                .collect();
+            encode_commitment(&cert)
+        };
+        let single_quorum = cert_with_quorums(&[0]);
+        let extra_quorum = cert_with_quorums(&[0, 1, 2]);
+        let mut eigen_da = TestEigenDAProvider::default();
+        eigen_da.insert_blob(single_quorum.clone(), blob.clone());
+        eigen_da.insert_blob(extra_quorum.clone(), blob.clone());
+        let txs = |commitment: &[u8], quorum_ids| {
+            alloc::vec![batcher_tx(
+                frame_ref_calldata(commitment, blob.len() as u32, quorum_ids),
+                0
+            )]
+        };
+        let block = test_block();
+        let config = EigenDASourceConfig {
+            required_quorum_ids: alloc::vec![0, 1],
+            ..Default::default()
+        };
+
+        let mut source = test_source(txs(&single_quorum, alloc::vec![0]), eigen_da.clone())
+            .with_config(config.clone());
+        assert!(drain(&mut source, &block).await.is_empty());
+
+        // the declared quorums are not trusted over the cert
+        let mut source = test_source(txs(&single_quorum, alloc::vec![0, 1]), eigen_da.clone())
+            .with_config(config.clone());
+        assert!(drain(&mut source, &block).await.is_empty());
+
+        // extra quorums are accepted
+        let mut source =
+            test_source(txs(&extra_quorum, alloc::vec![0, 1, 2]), eigen_da).with_config(config);
+        assert_eq!(
+            drain(&mut source, &block).await,
+            alloc::vec![Bytes::from(frames[0].clone())]
+        );
+    }
+
+    /// A provider serving the blobs of a [TestEigenDAProvider] after yielding to the executor
+    /// a number of times per commitment, tracking the retrievals in flight across clones.
+    #[derive(Debug, Clone, Default)]
+    struct SlowEigenDAProvider {
+        inner: TestEigenDAProvider,
+        yields: BTreeMap<Vec<u8>, usize>,
+        in_flight: Arc<core::sync::atomic::AtomicUsize>,
+        max_in_flight: Arc<core::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl EigenDAProvider for SlowEigenDAProvider {
+        type Error = EigenDAProviderError;
+
+        async fn blob_get(&self, commitment: &[u8]) -> Result<Vec<u8>, Self::Error> {
+            use core::sync::atomic::Ordering;
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            for _ in 0..self.yields.get(commitment).copied().unwrap_or_default() {
+                tokio::task::yield_now().await;
+            }
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            self.inner.blob_get(commitment).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_frame_refs_in_order() {
+        let mut eigen_da = SlowEigenDAProvider::default();
+        let mut txs = alloc::vec![batcher_tx(frame_calldata(&[0u8; 4]), 0)];
+        let mut expected = alloc::vec![Bytes::from(alloc::vec![0u8; 4])];
+        for i in 1..=3u8 {
+            let frames = alloc::vec![alloc::vec![i; 8]];
+            let blob = encode_frames(&frames);
+            let commitment = encode_commitment(&test_cert(i as u32));
+            // the first blob is retrieved last
+            eigen_da
+                .yields
+                .insert(commitment.clone(), 3 * (4 - i as usize));
+            eigen_da.inner.insert_blob(commitment.clone(), blob.clone());
+            let calldata = frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0]);
+            txs.push(batcher_tx(calldata, i as u64));
+            expected.push(Bytes::from(frames[0].clone()));
+        }
+
+        for (blob_fetch_concurrency, max_in_flight) in [(0, 1), (1, 1), (2, 2), (3, 3), (8, 3)] {
+            let eigen_da = SlowEigenDAProvider {
+                inner: eigen_da.inner.clone(),
+                yields: eigen_da.yields.clone(),
+                ..Default::default()
+            };
+            let mut chain = TestChainProvider::default();
+            chain.insert_block_with_transactions(10, test_block(), txs.clone());
+            let config = EigenDASourceConfig {
+                blob_fetch_concurrency,
+                ..Default::default()
+            };
+            let mut source = EigenDASource::new(
+                chain,
+                TestBlobProvider::default(),
+                eigen_da,
+                TEST_BATCH_INBOX,
+            )
+            .with_config(config);
+
+            let mut data = Vec::new();
+            while let Ok(item) = source.next(&test_block(), batcher_address()).await {
+                data.push(item);
+            }
+            assert_eq!(data, expected);
+            assert_eq!(
+                source
+                    .eigen_da_provider
+                    .max_in_flight
+                    .load(core::sync::atomic::Ordering::SeqCst),
+                max_in_flight,
+                "concurrency {blob_fetch_concurrency}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shared_blob_cache() {
+        let (frames, blob, commitment, eigen_da) = single_frame_fixture(1);
+        let txs = alloc::vec![batcher_tx(
+            frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0]),
+            0
+        )];
+        let cache = SharedBlobCache::new(core::num::NonZeroUsize::new(4).unwrap());
+        let source_with_cache = |eigen_da| {
+            let mut chain = TestChainProvider::default();
+            chain.insert_block_with_transactions(10, test_block(), txs.clone());
+            EigenDASource::new_with_cache(
+                chain,
+                TestBlobProvider::default(),
+                eigen_da,
+                TEST_BATCH_INBOX,
+                cache.clone(),
+            )
+        };
+        let expected = alloc::vec![Bytes::from(frames[0].clone())];
+
+        let mut source = source_with_cache(eigen_da);
+        assert_eq!(drain(&mut source, &test_block()).await, expected);
+        assert_eq!(cache.get(&commitment), Some(blob));
+
+        // a recreated source is served by the cache, its provider not having the blob
+        let mut source = source_with_cache(TestEigenDAProvider::default());
+        assert_eq!(drain(&mut source, &test_block()).await, expected);
+    }
+
+    /// A provider corrupting the RLP of the blobs of a [TestEigenDAProvider] for the first
+    /// `corrupt` retrievals.
+    #[derive(Debug, Clone, Default)]
+    struct CorruptingEigenDAProvider {
+        inner: TestEigenDAProvider,
+        corrupt: usize,
+        retrievals: Arc<core::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl EigenDAProvider for CorruptingEigenDAProvider {
+        type Error = EigenDAProviderError;
+
+        async fn blob_get(&self, commitment: &[u8]) -> Result<Vec<u8>, Self::Error> {
+            let retrieval = self
+                .retrievals
+                .fetch_add(1, core::sync::atomic::Ordering::SeqCst);
+            let mut blob = self.inner.blob_get(commitment).await?;
+            if retrieval < self.corrupt {
+                // a long list prefix whose length exceeds the blob
+                blob[0] = 0xff;
+            }
+            Ok(blob)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refetch_corrupt_blob() {
+        let (frames, blob, commitment, inner) = single_frame_fixture(1);
+        let txs = alloc::vec![batcher_tx(
+            frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0]),
+            0
+        )];
+        // derives the block, returning the data and the number of retrievals
+        let derive = |corrupt, refetch_corrupt_blobs| {
+            let mut chain = TestChainProvider::default();
+            chain.insert_block_with_transactions(10, test_block(), txs.clone());
+            let eigen_da = CorruptingEigenDAProvider {
+                inner: inner.clone(),
+                corrupt,
+                ..Default::default()
+            };
+            let config = EigenDASourceConfig {
+                refetch_corrupt_blobs,
+                ..Default::default()
+            };
+            let mut source = EigenDASource::new(
+                chain,
+                TestBlobProvider::default(),
+                eigen_da,
+                TEST_BATCH_INBOX,
+            )
+            .with_config(config);
+            async move {
+                let mut data = Vec::new();
+                while let Ok(item) = source.next(&test_block(), batcher_address()).await {
+                    data.push(item);
+                }
+                let retrievals = &source.eigen_da_provider.retrievals;
+                (data, retrievals.load(core::sync::atomic::Ordering::SeqCst))
+            }
+        };
+
+        // the corrupt blob fails the block without the re-fetch
+        assert_eq!(derive(1, false).await, (Vec::new(), 1));
+        // the re-fetched blob is valid
+        assert_eq!(
+            derive(1, true).await,
+            (alloc::vec![Bytes::from(frames[0].clone())], 2)
+        );
+        // a blob corrupt on every retrieval is only re-fetched once
+        assert_eq!(derive(usize::MAX, true).await, (Vec::new(), 2));
+    }
+
+    #[tokio::test]
+    async fn test_batch_inbox_by_activation_block() {
+        let new_inbox = Address::repeat_byte(0x42);
+        let txs = alloc::vec![
+            batcher_tx(frame_calldata(&[1u8; 4]), 0),
+            signed_tx(&batcher_signer(), new_inbox, frame_calldata(&[2u8; 4]), 1),
+        ];
+        let block = test_block();
+        let config = |activation_block| EigenDASourceConfig {
+            batch_inboxes: alloc::vec![BatchInbox {
+                activation_block,
+                address: new_inbox,
+            }],
+            ..Default::default()
+        };
+
+        // the block is before the activation
+        let mut source = test_source(txs.clone(), Default::default()).with_config(config(11));
+        assert_eq!(
+            drain(&mut source, &block).await,
+            alloc::vec![Bytes::from(alloc::vec![1u8; 4])]
+        );
+
+        // the block is the activation block
+        let mut source = test_source(txs, Default::default()).with_config(config(10));
+        assert_eq!(
+            drain(&mut source, &block).await,
+            alloc::vec![Bytes::from(alloc::vec![2u8; 4])]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_frame_ref_with_invalid_inclusion_proof_dropped() {
+        // the test cert carries no inclusion proof
+        let (_, blob, commitment, eigen_da) = single_frame_fixture(1);
+        let txs = alloc::vec![
+            batcher_tx(frame_calldata(&[2u8; 8]), 0),
+            batcher_tx(
+                frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0]),
+                1
+            ),
+        ];
+        let block = test_block();
+
+        let mut source = test_source(txs.clone(), eigen_da.clone());
+        assert_eq!(drain(&mut source, &block).await.len(), 2);
+
+        let config = EigenDASourceConfig {
+            verify_inclusion: true,
+            ..Default::default()
+        };
+        let mut source = test_source(txs, eigen_da).with_config(config);
+        assert_eq!(
+            drain(&mut source, &block).await,
+            alloc::vec![Bytes::from(alloc::vec![2u8; 8])]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gzip_compressed_blob() {
+        let frames = alloc::vec![alloc::vec![1u8; 64], alloc::vec![2u8; 64]];
+        let blob = gzip(&encode_frames(&frames));
+        let commitment = encode_commitment(&test_cert(1));
+        let mut eigen_da = TestEigenDAProvider::default();
+        eigen_da.insert_blob(commitment.clone(), blob.clone());
+        let txs = alloc::vec![batcher_tx(
+            frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0, 1]),
+            0
+        )];
+        let block = test_block();
+
+        let config = EigenDASourceConfig {
+            blob_compression: BlobCompression::Gzip,
+            ..Default::default()
+        };
+        let mut source = test_source(txs, eigen_da).with_config(config);
+        assert_eq!(
+            drain(&mut source, &block).await,
+            frames.into_iter().map(Bytes::from).collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_decompressed_size_limit() {
+        let frames = alloc::vec![alloc::vec![0u8; 4096]];
+        let blob = gzip(&encode_frames(&frames));
+        let commitment = encode_commitment(&test_cert(1));
+        let mut eigen_da = TestEigenDAProvider::default();
+        eigen_da.insert_blob(commitment.clone(), blob.clone());
+        let txs = alloc::vec![batcher_tx(
+            frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0, 1]),
+            0
+        )];
+        let block = test_block();
+
+        let config = EigenDASourceConfig {
+            blob_compression: BlobCompression::Gzip,
+            max_decompressed_size: Some(1024),
+            ..Default::default()
+        };
+        let mut source = test_source(txs, eigen_da).with_config(config);
+        let err = source.next(&block, batcher_address()).await.unwrap_err();
+        assert!(
+            err.to_string().contains("maximum size of 1024 bytes"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_commitments() {
+        let first = encode_commitment(&test_cert(1));
+        let second = encode_commitment(&test_cert(2));
+        let txs = alloc::vec![
+            batcher_tx(frame_ref_calldata(&first, 16, alloc::vec![0, 1]), 0),
+            batcher_tx(frame_calldata(&[1u8; 8]), 1),
+            batcher_tx(frame_ref_calldata(&second, 16, alloc::vec![0, 1]), 2),
+        ];
+        let block = test_block();
+
+        // no blob is available, listing must not retrieve any
+        let mut source = test_source(txs, TestEigenDAProvider::default());
+        let listed = source
+            .list_commitments(&block, batcher_address())
+            .await
+            .unwrap();
+        assert_eq!(
+            listed,
+            alloc::vec![
+                ListedItem::FrameRef(DaSourceInfo {
+                    commitment: first.into()
+                }),
+                ListedItem::Frame,
+                ListedItem::FrameRef(DaSourceInfo {
+                    commitment: second.into()
+                }),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expired_cert_dropped() {
+        let frames = alloc::vec![alloc::vec![1u8; 8]];
+        let blob = encode_frames(&frames);
+        let mut cert = test_cert(1);
+        cert.blob_verification_proof
+            .batch_medatada
+            .batch_header
+            .reference_block_number = 5;
+        let commitment = encode_commitment(&cert);
+        let mut eigen_da = TestEigenDAProvider::default();
+        eigen_da.insert_blob(commitment.clone(), blob.clone());
+        let txs = alloc::vec![batcher_tx(
+            frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0, 1]),
+            0
+        )];
+
+        // the reference block is an hour older than the block carrying the frame ref
+        let block = BlockInfo {
+            timestamp: 10_000,
+            ..test_block()
+        };
+        let reference_block = BlockInfo {
+            hash: B256::repeat_byte(0x05),
+            number: 5,
+            timestamp: 10_000 - 3600,
+            ..Default::default()
+        };
+        let source = |max_cert_age| {
+            let mut chain = TestChainProvider::default();
+            chain.insert_block(reference_block.number, reference_block);
+            chain.insert_block_with_transactions(block.number, block, txs.clone());
+            let config = EigenDASourceConfig {
+                max_cert_age: Some(max_cert_age),
+                ..Default::default()
+            };
+            EigenDASource::new(
+                chain,
+                TestBlobProvider::default(),
+                eigen_da.clone(),
+                TEST_BATCH_INBOX,
+            )
+            .with_config(config)
+        };
+
+        let mut fresh = source(3600);
+        assert_eq!(
+            drain(&mut fresh, &block).await,
+            alloc::vec![Bytes::from(frames[0].clone())]
+        );
+
+        let mut expired = source(3599);
+        assert!(drain(&mut expired, &block).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stale_cert_dropped() {
+        let frames = alloc::vec![alloc::vec![1u8; 8]];
+        let blob = encode_frames(&frames);
+        let block = BlockInfo {
+            number: 1000,
+            ..test_block()
+        };
+        let source = |reference_block_number| {
+            let mut cert = test_cert(1);
+            cert.blob_verification_proof
+                .batch_medatada
+                .batch_header
+                .reference_block_number = reference_block_number;
+            let commitment = encode_commitment(&cert);
+            let mut eigen_da = TestEigenDAProvider::default();
+            eigen_da.insert_blob(commitment.clone(), blob.clone());
+            let calldata = frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0]);
+            let tx = batcher_tx(calldata, 0);
+            let mut chain = TestChainProvider::default();
+            chain.insert_block_with_transactions(block.number, block, alloc::vec![tx]);
+            EigenDASource::new(
+                chain,
+                TestBlobProvider::default(),
+                eigen_da,
+                TEST_BATCH_INBOX,
+            )
+        };
+
+        // the block carrying the frame ref is exactly STALE_GAP blocks after the reference block
+        let mut fresh = source(900);
+        assert_eq!(
+            drain(&mut fresh, &block).await,
+            alloc::vec![Bytes::from(frames[0].clone())]
+        );
+
+        let mut stale = source(899);
+        assert!(drain(&mut stale, &block).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unprefixed_frame_requires_legacy_mode() {
+        // the calldata frame without the leading derivation version byte
+        let calldata = frame_calldata(&[3u8; 4]).slice(1..);
+        let txs = alloc::vec![batcher_tx(calldata, 0)];
+        let block = test_block();
+
+        let mut source = test_source(txs.clone(), TestEigenDAProvider::default());
+        assert!(drain(&mut source, &block).await.is_empty());
+
+        let config = EigenDASourceConfig {
+            allow_unprefixed_frames: true,
+            ..Default::default()
+        };
+        let mut source = test_source(txs, TestEigenDAProvider::default()).with_config(config);
+        assert_eq!(
+            drain(&mut source, &block).await,
+            alloc::vec![Bytes::from(alloc::vec![3u8; 4])]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_empty_frame_policy() {
+        // a calldata frame without value encodes to nothing after the version byte
+        let empty = Bytes::from(alloc::vec![DERIVATION_VERSION_EIGEN_DA]);
+        let txs = alloc::vec![
+            batcher_tx(empty, 0),
+            batcher_tx(frame_calldata(&[3u8; 4]), 1)
+        ];
+        let block = test_block();
+
+        let mut source = test_source(txs.clone(), TestEigenDAProvider::default());
+        assert_eq!(
+            drain(&mut source, &block).await,
+            alloc::vec![Bytes::from(alloc::vec![3u8; 4])]
+        );
+
+        let config = EigenDASourceConfig {
+            empty_frame_policy: EmptyFramePolicy::Reject,
+            ..Default::default()
+        };
+        let mut source = test_source(txs, TestEigenDAProvider::default()).with_config(config);
+        let err = source.next(&block, batcher_address()).await.unwrap_err();
+        assert!(
+            err.to_string().contains("calldata frame without value"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_decode_frames_bounded() {
+        let frames = alloc::vec![alloc::vec![1u8; 8], alloc::vec![2u8; 8]];
+        let decoded = decode_frames(&encode_frames(&frames), FramePath::FrameRef).unwrap();
+        assert_eq!(
+            decoded,
+            frames.into_iter().map(Bytes::from).collect::<Vec<_>>()
+        );
+
+        // a blob set decoding to a huge number of empty frames
+        let empty = alloc::vec![Vec::new(); MAX_FRAMES + 1];
+        assert!(matches!(
+            decode_frames(&encode_frames(&empty), FramePath::FrameRef),
+            Err(EigenDAProviderError::FrameDataTooLarge(_))
+        ));
+
+        // more data than any EigenDA blob can carry
+        let oversized = alloc::vec![0u8; MAX_BLOB_SIZE + 1];
+        assert!(matches!(
+            decode_frames(&oversized, FramePath::FrameRef),
+            Err(EigenDAProviderError::FrameDataTooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_frames_error_tagged_with_path() {
+        // a list header claiming more data than present
+        let malformed = [0xc5, 0x01];
+        for path in [FramePath::FrameRef, FramePath::Blob4844] {
+            let err = decode_frames(&malformed, path).unwrap_err();
+            assert!(matches!(
+                err,
+                EigenDAProviderError::RLPDecodeError { path: tagged, .. } if tagged == path
+            ));
+            assert!(err.to_string().contains(path.as_str()), "{err}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_malformed_frame_ref_blob_tagged() {
+        let blob = alloc::vec![0xc5, 0x01];
+        let commitment = encode_commitment(&test_cert(1));
+        let mut eigen_da = TestEigenDAProvider::default();
+        eigen_da.insert_blob(commitment.clone(), blob.clone());
+        let txs = alloc::vec![batcher_tx(
+            frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0]),
+            0
+        )];
+        let mut source = test_source(txs, eigen_da);
+
+        let err = source
+            .next(&test_block(), batcher_address())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("RLP of frame_ref frames"), "{err}");
+    }
+
+    /// Reports the same signer for every transaction.
+    #[derive(Clone)]
+    struct FixedSigner(Option<Address>);
+
+    impl SignerRecovery for FixedSigner {
+        fn recover_signer(&self, _tx: &TxEnvelope) -> Option<Address> {
+            self.0
+        }
+    }
+
+    /// An inline frame transaction to the batch inbox with a placeholder signature.
+    fn unsigned_frame_tx(frame: &[u8]) -> TxEnvelope {
+        let tx = TxEip1559 {
+            chain_id: 1,
+            to: TxKind::Call(TEST_BATCH_INBOX),
+            input: frame_calldata(frame),
+            ..Default::default()
+        };
+        TxEnvelope::Eip1559(tx.into_signed(Signature::test_signature()))
+    }
+
+    #[tokio::test]
+    async fn test_eip7702_batcher_tx() {
+        let tx = TxEip7702 {
+            chain_id: 1,
+            gas_limit: 1_000_000,
+            to: TEST_BATCH_INBOX,
+            input: frame_calldata(&[7u8; 8]),
+            ..Default::default()
+        };
+        let signature = batcher_signer()
+            .sign_hash_sync(&tx.signature_hash())
+            .unwrap();
+        let txs = alloc::vec![TxEnvelope::Eip7702(tx.into_signed(signature))];
+        let block = test_block();
+
+        let mut source = test_source(txs.clone(), TestEigenDAProvider::default());
+        assert_eq!(
+            drain(&mut source, &block).await,
+            alloc::vec![Bytes::from([7u8; 8])]
+        );
+
+        // the signer is checked like for the other transaction types
+        let mut source = test_source(txs, TestEigenDAProvider::default());
+        assert!(source
+            .next(&block, Address::repeat_byte(0x42))
+            .await
+            .is_err());
+    }
+
+    /// A 4844 transaction to `to` carrying `calldata` and `blobs` blob hashes tagged with the
+    /// nonce, signed by `signer`.
+    fn blob_tx(
+        signer: &PrivateKeySigner,
+        to: Address,
+        calldata: Bytes,
+        blobs: u8,
+        nonce: u8,
+    ) -> TxEnvelope {
+        let tx = TxEip4844 {
+            chain_id: 1,
+            nonce: nonce.into(),
+            gas_limit: 1_000_000,
+            to,
+            input: calldata,
+            blob_versioned_hashes: (0..blobs)
+                .map(|i| B256::repeat_byte(nonce * 16 + i))
+                .collect(),
+            ..Default::default()
+        };
+        let signature = signer.sign_hash_sync(&tx.signature_hash()).unwrap();
+        tx.into_signed(signature).into()
+    }
+
+    #[test]
+    fn test_blob_indices_across_txs() {
+        let other = PrivateKeySigner::from_bytes(&B256::repeat_byte(2)).unwrap();
+        let batcher = batcher_signer();
+        let txs = alloc::vec![
+            // not from the batcher, its blobs 0 and 1 are skipped
+            blob_tx(&other, TEST_BATCH_INBOX, Bytes::new(), 2, 1),
+            batcher_tx(frame_calldata(&[1u8; 4]), 2),
+            // the calldata carries the frame, blob 2 is skipped
+            blob_tx(&batcher, TEST_BATCH_INBOX, frame_calldata(&[3u8; 4]), 1, 3),
+            // not to the inbox, blob 3 is skipped
+            blob_tx(&batcher, Address::repeat_byte(0x42), Bytes::new(), 1, 4),
+            blob_tx(&batcher, TEST_BATCH_INBOX, Bytes::new(), 2, 5),
+        ];
+        let source = test_source(Vec::new(), TestEigenDAProvider::default());
+
+        let (values, hashes) = source
+            .calldata_frames(&test_block(), txs, batcher_address())
+            .unwrap();
+        assert_eq!(values.len(), 2);
+        let hashes: Vec<_> = hashes.iter().map(|h| (h.hash, h.index)).collect();
+        assert_eq!(
+            hashes,
+            alloc::vec![(B256::repeat_byte(0x50), 4), (B256::repeat_byte(0x51), 5)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_signer_recovery_filters_batcher() {
+        let block = test_block();
+        let txs = alloc::vec![unsigned_frame_tx(&[1u8; 8])];
+
+        let mut source = test_source(txs.clone(), TestEigenDAProvider::default())
+            .with_signer_recovery(FixedSigner(Some(batcher_address())));
+        assert_eq!(
+            drain(&mut source, &block).await,
+            alloc::vec![Bytes::from([1u8; 8])]
+        );
+
+        let mut source = test_source(txs.clone(), TestEigenDAProvider::default())
+            .with_signer_recovery(FixedSigner(Some(Address::repeat_byte(0x42))));
+        assert!(drain(&mut source, &block).await.is_empty());
+
+        let mut source = test_source(txs.clone(), TestEigenDAProvider::default())
+            .with_signer_recovery(FixedSigner(None));
+        assert!(drain(&mut source, &block).await.is_empty());
+
+        // a failed recovery does not match a zero batcher address
+        let mut source = test_source(txs, TestEigenDAProvider::default())
+            .with_signer_recovery(FixedSigner(None));
+        assert!(source.next(&block, Address::ZERO).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_window() {
+        let blocks: Vec<BlockInfo> = (10..14)
+            .map(|number| BlockInfo {
+                hash: B256::repeat_byte(number as u8),
+                number,
+                ..Default::default()
+            })
+            .collect();
+        let mut chain = TestChainProvider::default();
+        for block in &blocks {
+            let tx = batcher_tx(frame_calldata(&[block.number as u8; 4]), 0);
+            chain.insert_block_with_transactions(block.number, *block, alloc::vec![tx]);
+        }
+        let config = EigenDASourceConfig {
+            prefetch_depth: 2,
+            ..Default::default()
+        };
+        let mut source = EigenDASource::new(
+            chain,
+            TestBlobProvider::default(),
+            TestEigenDAProvider::default(),
+            TEST_BATCH_INBOX,
+        )
+        .with_config(config);
+
+        // loading block 10 fetches blocks 11 and 12 ahead
+        assert_eq!(
+            drain(&mut source, &blocks[0]).await,
+            alloc::vec![Bytes::from([10u8; 4])]
+        );
+        source.clear();
+        assert_eq!(
+            source.prefetched.keys().copied().collect::<Vec<_>>(),
+            alloc::vec![11, 12]
+        );
+
+        // block 11 is served from the window, evicted, and block 13 joins the window
+        source
+            .chain_provider
+            .blocks
+            .retain(|(number, _)| *number != 11);
+        assert_eq!(
+            drain(&mut source, &blocks[1]).await,
+            alloc::vec![Bytes::from([11u8; 4])]
+        );
+        source.clear();
+        assert_eq!(
+            source.prefetched.keys().copied().collect::<Vec<_>>(),
+            alloc::vec![12, 13]
+        );
+
+        // the buffered data of a reorged block is not used
+        let reorged = BlockInfo {
+            hash: B256::repeat_byte(0xee),
+            ..blocks[2]
+        };
+        let tx = batcher_tx(frame_calldata(&[0xee; 4]), 0);
+        source
+            .chain_provider
+            .insert_block_with_transactions(12, reorged, alloc::vec![tx]);
+        assert_eq!(
+            drain(&mut source, &reorged).await,
+            alloc::vec![Bytes::from([0xee; 4])]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_in_background() {
+        let blocks: Vec<BlockInfo> = (10..13)
+            .map(|number| BlockInfo {
+                hash: B256::repeat_byte(number as u8),
+                number,
+                ..Default::default()
+            })
+            .collect();
+        let mut eigen_da = SlowEigenDAProvider::default();
+        let mut chain = TestChainProvider::default();
+        let mut expected = Vec::new();
+        for block in &blocks {
+            let frame = alloc::vec![block.number as u8; 8];
+            let blob = encode_frames(&[frame.clone()]);
+            let commitment = encode_commitment(&test_cert(block.number as u32));
+            // the blobs of the window are retrieved after yielding to the executor
+            if block.number > 10 {
+                eigen_da.yields.insert(commitment.clone(), 3);
+            }
+            eigen_da.inner.insert_blob(commitment.clone(), blob.clone());
+            let calldata = frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0]);
+            let txs = alloc::vec![batcher_tx(calldata, 0)];
+            chain.insert_block_with_transactions(block.number, *block, txs);
+            expected.push(alloc::vec![Bytes::from(frame)]);
+        }
+        let config = EigenDASourceConfig {
+            prefetch_depth: 2,
+            ..Default::default()
+        };
+        let mut source = EigenDASource::new(
+            chain,
+            TestBlobProvider::default(),
+            eigen_da,
+            TEST_BATCH_INBOX,
+        )
+        .with_config(config);
+
+        // block 10 is returned without waiting for the blobs of blocks 11 and 12, retrieved
+        // concurrently
+        let item = source.next(&blocks[0], batcher_address()).now_or_never();
+        assert_eq!(item.map(Result::unwrap), Some(expected[0][0].clone()));
+        let max_in_flight = &source.eigen_da_provider.max_in_flight;
+        assert_eq!(max_in_flight.load(core::sync::atomic::Ordering::SeqCst), 2);
+        assert!(source.prefetched.is_empty());
+
+        // the following blocks are served once loaded in the background
+        for (block, expected) in blocks.iter().zip(expected).skip(1) {
+            source.clear();
+            assert_eq!(drain(&mut source, block).await, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_load_summary() {
+        let (_, blob, commitment, eigen_da) =
+            frames_fixture(alloc::vec![alloc::vec![1u8; 8], alloc::vec![2u8; 8]], 1);
+
+        let txs = alloc::vec![
+            batcher_tx(frame_calldata(&[0u8; 4]), 0),
+            batcher_tx(
+                frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0]),
+                1
+            ),
+            // not sent to the batch inbox
+            signed_tx(
+                &batcher_signer(),
+                Address::repeat_byte(0x42),
+                frame_calldata(&[3u8; 4]),
+                2
+            ),
+        ];
+        let mut source = test_source(txs, eigen_da);
+        assert_eq!(source.last_load_summary, None);
+        assert_eq!(drain(&mut source, &test_block()).await.len(), 3);
+        assert_eq!(
+            source.last_load_summary,
+            Some(BlockLoadSummary {
+                txs: 3,
+                inline_frames: 1,
+                frame_refs: 1,
+                blobs: 0,
+                decoded_bytes: 20,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_clear_resets_block_state() {
+        let blocks: Vec<BlockInfo> = (10..12)
+            .map(|number| BlockInfo {
+                hash: B256::repeat_byte(number as u8),
+                number,
+                ..Default::default()
+            })
+            .collect();
+        let (frames, blob, commitment, eigen_da) =
+            frames_fixture(alloc::vec![alloc::vec![2u8; 8]], 1);
+
+        let txs_a = alloc::vec![
+            batcher_tx(frame_calldata(&[1u8; 4]), 0),
+            batcher_tx(
+                frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0]),
+                1
+            ),
+        ];
+        let txs_b = alloc::vec![batcher_tx(frame_calldata(&[5u8; 4]), 0)];
+        let chain = || {
+            let mut chain = TestChainProvider::default();
+            chain.insert_block_with_transactions(10, blocks[0], txs_a.clone());
+            chain.insert_block_with_transactions(11, blocks[1], txs_b.clone());
+            chain
+        };
+        let cache = SharedBlobCache::new(core::num::NonZeroUsize::new(4).unwrap());
+        let config = EigenDASourceConfig {
+            prefetch_depth: 1,
+            ..Default::default()
+        };
+        let mut source = EigenDASource::new_with_cache(
+            chain(),
+            TestBlobProvider::default(),
+            eigen_da,
+            TEST_BATCH_INBOX,
+            cache.clone(),
+        )
+        .with_config(config.clone());
+
+        // block A is left partially consumed
+        let first = source.next(&blocks[0], batcher_address()).await.unwrap();
+        assert_eq!(first, Bytes::from([1u8; 4]));
+        assert_eq!(source.data.len(), 1);
+        source.clear();
+        assert!(source.data.is_empty());
+        assert!(source.sources.is_empty());
+        assert_eq!(source.open_block, None);
+        // the shared cache outlives the block
+        assert_eq!(cache.get(&commitment), Some(blob.clone()));
+
+        // block B is served as by a source which never loaded block A
+        let mut fresh = EigenDASource::new(
+            chain(),
+            TestBlobProvider::default(),
+            TestEigenDAProvider::default(),
+            TEST_BATCH_INBOX,
+        )
+        .with_config(config);
+        let expected = (Bytes::from([5u8; 4]), None);
+        let item = source.next_with_source(&blocks[1], batcher_address()).await;
+        assert_eq!(item.unwrap(), expected);
+        let item = fresh.next_with_source(&blocks[1], batcher_address()).await;
+        assert_eq!(item.unwrap(), expected);
+        assert!(source.next(&blocks[1], batcher_address()).await.is_err());
+        assert_eq!(source.last_load_summary, fresh.last_load_summary);
+        assert!(source.prefetched.range(..=11).next().is_none());
+
+        // block A is loaded again from the cache, without the provider
+        source.clear();
+        source.eigen_da_provider.blobs.clear();
+        let expected = alloc::vec![Bytes::from([1u8; 4]), Bytes::from(frames[0].clone())];
+        assert_eq!(drain(&mut source, &blocks[0]).await, expected);
+    }
+
+    /// A [BatchDataSink] keeping the dumps it receives, as text.
+    #[derive(Debug, Default)]
+    struct TextSink(spin::Mutex<Vec<(u64, alloc::string::String)>>);
+
+    impl BatchDataSink for TextSink {
+        fn record(&self, block_ref: &BlockInfo, dump: &BatchDataDump) {
+            self.0.lock().push((block_ref.number, dump.to_text()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_data_dump() {
+        let (_, blob, commitment, eigen_da) =
+            frames_fixture(alloc::vec![alloc::vec![1u8; 8], alloc::vec![2u8; 8]], 1);
+
+        let txs = alloc::vec![
+            batcher_tx(frame_calldata(&[0u8; 4]), 0),
+            batcher_tx(
+                frame_ref_calldata(&commitment, blob.len() as u32, alloc::vec![0]),
+                1
+            ),
+        ];
+        let sink = Arc::new(TextSink::default());
+        let mut source = test_source(txs, eigen_da).with_batch_data_sink(sink.clone());
+        let block = test_block();
+        let mut loaded = Vec::new();
+        while let Ok(item) = source.next_with_source(&block, batcher_address()).await {
+            loaded.push(item);
+        }
+
+        // the block is dumped once, with the data derivation consumed from it
+        let dumps = sink.0.lock().clone();
+        assert_eq!(dumps.len(), 1);
+        assert_eq!(dumps[0].0, block.number);
+        let (data, sources) = loaded.into_iter().unzip();
+        assert_eq!(
+            BatchDataDump::from_text(&dumps[0].1),
+            Ok(BatchDataDump { data, sources })
+        );
     }
 }
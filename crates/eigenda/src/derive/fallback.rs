@@ -0,0 +1,78 @@
+//! Contains the [FallbackEigenDAProvider].
+
+use crate::derive::EigenDAProvider;
+use alloc::{boxed::Box, vec::Vec};
+use async_trait::async_trait;
+use tracing::debug;
+
+/// An [EigenDAProvider] retrieving blobs from a primary provider, falling back to a secondary
+/// one when the primary fails.
+///
+/// E.g. an oracle over a partially populated preimage store backed by online retrieval, so that
+/// the store is transparently topped up. The error of the secondary provider is returned when
+/// both fail.
+#[derive(Debug, Clone)]
+pub struct FallbackEigenDAProvider<A, B> {
+    /// The provider tried first.
+    pub primary: A,
+    /// The provider tried when the primary fails.
+    pub secondary: B,
+}
+
+impl<A, B> FallbackEigenDAProvider<A, B> {
+    /// Creates a new [FallbackEigenDAProvider] trying `primary` then `secondary`.
+    pub const fn new(primary: A, secondary: B) -> Self {
+        Self { primary, secondary }
+    }
+}
+
+#[async_trait]
+impl<A, B> EigenDAProvider for FallbackEigenDAProvider<A, B>
+where
+    A: EigenDAProvider + Send + Sync,
+    B: EigenDAProvider + Send + Sync,
+{
+    type Error = B::Error;
+
+    async fn blob_get(&self, commitment: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        match self.primary.blob_get(commitment).await {
+            Ok(blob) => Ok(blob),
+            Err(e) => {
+                debug!(
+                    target: "eigen-da-fallback",
+                    "Primary provider failed, falling back: {e}"
+                );
+                self.secondary.blob_get(commitment).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{encode_commitment, test_cert, TestEigenDAProvider};
+    use alloc::vec;
+
+    #[tokio::test]
+    async fn test_fallback_on_primary_miss() {
+        let stored = encode_commitment(&test_cert(1));
+        let online = encode_commitment(&test_cert(2));
+
+        let mut store = TestEigenDAProvider::default();
+        store.insert_blob(stored.clone(), vec![1]);
+        let mut remote = TestEigenDAProvider::default();
+        remote.insert_blob(stored.clone(), vec![0xff]);
+        remote.insert_blob(online.clone(), vec![2]);
+
+        let provider = FallbackEigenDAProvider::new(store, remote);
+        // the store misses and the online provider serves
+        assert_eq!(provider.blob_get(&online).await.unwrap(), vec![2]);
+        // the store serves without reaching the online provider
+        assert_eq!(provider.blob_get(&stored).await.unwrap(), vec![1]);
+
+        // both miss
+        let missing = encode_commitment(&test_cert(3));
+        assert!(provider.blob_get(&missing).await.is_err());
+    }
+}
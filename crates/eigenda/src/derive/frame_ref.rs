@@ -0,0 +1,136 @@
+//! Contains the validation of [FrameRef]s.
+
+use crate::{
+    common::{checked_usize, BlobInfo, MAX_BLOB_SIZE},
+    errors::EigenDAProviderError,
+    proto::FrameRef,
+};
+use alloc::{string::ToString, vec::Vec};
+use alloy_primitives::Bytes;
+
+/// Minimal commitment length, 3 bytes of metadata followed by a cert of at least 32 bytes.
+const MIN_COMMITMENT_LENGTH: usize = 3 + 32;
+
+/// A [FrameRef] which passed [decode_frame_ref].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidatedFrameRef {
+    /// The EigenDA commitment of the referenced blob.
+    pub commitment: Bytes,
+    /// The cert decoded from the commitment.
+    pub cert: BlobInfo,
+    /// The length in bytes of the RLP encoded frames at the start of the blob.
+    pub blob_length: usize,
+    /// The quorums the blob was dispersed to.
+    pub quorum_ids: Vec<u32>,
+}
+
+/// Validates a [FrameRef] decoded from batcher calldata.
+///
+/// The frame ref must reference at least one quorum, have a non-zero `blob_length` of at most
+/// [MAX_BLOB_SIZE] bytes and carry a commitment whose cert can be decoded.
+pub fn decode_frame_ref(frame_ref: FrameRef) -> Result<ValidatedFrameRef, EigenDAProviderError> {
+    if frame_ref.quorum_ids.is_empty() {
+        return Err(EigenDAProviderError::InvalidFrameRef(
+            "decoded frame ref contains no quorum IDs".to_string(),
+        ));
+    }
+
+    let blob_length = checked_usize(frame_ref.blob_length.into())?;
+    if blob_length == 0 || blob_length > MAX_BLOB_SIZE {
+        return Err(EigenDAProviderError::InvalidFrameRef(alloc::format!(
+            "frame ref blob length {blob_length} is out of range"
+        )));
+    }
+
+    if frame_ref.commitment.len() < MIN_COMMITMENT_LENGTH {
+        return Err(EigenDAProviderError::InvalidFrameRef(alloc::format!(
+            "frame ref commitment of {} bytes is too short",
+            frame_ref.commitment.len()
+        )));
+    }
+    let cert = BlobInfo::from_commitment(&frame_ref.commitment)
+        .map_err(|e| EigenDAProviderError::CertDecodeError(e.to_string()))?;
+
+    Ok(ValidatedFrameRef {
+        commitment: Bytes::from(frame_ref.commitment),
+        cert,
+        blob_length,
+        quorum_ids: frame_ref.quorum_ids,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{encode_commitment, test_cert};
+    use alloc::vec;
+
+    fn valid_frame_ref() -> FrameRef {
+        FrameRef {
+            quorum_ids: vec![0, 1],
+            blob_length: 100,
+            commitment: encode_commitment(&test_cert(4)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_decode_valid_frame_ref() {
+        let frame_ref = valid_frame_ref();
+        let validated = decode_frame_ref(frame_ref.clone()).unwrap();
+        assert_eq!(validated.commitment, Bytes::from(frame_ref.commitment));
+        assert_eq!(validated.cert, test_cert(4));
+        assert_eq!(validated.blob_length, 100);
+        assert_eq!(validated.quorum_ids, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_decode_frame_ref_no_quorum_ids() {
+        let frame_ref = FrameRef {
+            quorum_ids: vec![],
+            ..valid_frame_ref()
+        };
+        assert!(matches!(
+            decode_frame_ref(frame_ref),
+            Err(EigenDAProviderError::InvalidFrameRef(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_frame_ref_invalid_blob_length() {
+        for blob_length in [0, MAX_BLOB_SIZE as u32 + 1] {
+            let frame_ref = FrameRef {
+                blob_length,
+                ..valid_frame_ref()
+            };
+            assert!(matches!(
+                decode_frame_ref(frame_ref),
+                Err(EigenDAProviderError::InvalidFrameRef(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_decode_frame_ref_short_commitment() {
+        let frame_ref = FrameRef {
+            commitment: vec![0x01, 0x00, 0x00, 0xaa],
+            ..valid_frame_ref()
+        };
+        assert!(matches!(
+            decode_frame_ref(frame_ref),
+            Err(EigenDAProviderError::InvalidFrameRef(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_frame_ref_malformed_commitment() {
+        let frame_ref = FrameRef {
+            commitment: vec![0xff; 64],
+            ..valid_frame_ref()
+        };
+        assert!(matches!(
+            decode_frame_ref(frame_ref),
+            Err(EigenDAProviderError::CertDecodeError(_))
+        ));
+    }
+}
@@ -1,8 +1,39 @@
+pub(crate) mod compression;
+pub use compression::BlobCompression;
+
+mod config;
+pub use config::{BatchInbox, EigenDASourceConfig, EmptyFramePolicy, QuorumThreshold};
+
+mod frame_ref;
+pub use frame_ref::{decode_frame_ref, ValidatedFrameRef};
+
 mod traits;
-pub use traits::EigenDAProvider;
+pub use traits::{BatchChainProvider, BatchDataSink, EigenDAProvider, SignerRecovery};
+
+mod batch;
+pub use batch::BatchingChainProvider;
+
+mod cache;
+pub use cache::SharedBlobCache;
+
+mod caching;
+pub use caching::CachingEigenDAProvider;
+
+mod fallback;
+pub use fallback::FallbackEigenDAProvider;
+
+mod dump;
+pub use dump::BatchDataDump;
+
+mod recording;
+pub use recording::{EigenDARecording, RecordingEigenDAProvider};
+
+mod signer;
+pub use signer::AlloySignerRecovery;
 
 mod eigenda;
-pub use eigenda::EigenDASource;
+pub(crate) use eigenda::DERIVATION_VERSION_EIGEN_DA;
+pub use eigenda::{BlockLoadSummary, DaSourceInfo, EigenDASource, ListedItem};
 
 mod blob_data;
 pub use blob_data::BlobData;
@@ -0,0 +1,267 @@
+//! Contains the [RecordingEigenDAProvider] and the [EigenDARecording] it captures.
+
+use crate::{derive::EigenDAProvider, errors::EigenDAProviderError};
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use alloy_primitives::hex;
+use async_trait::async_trait;
+use spin::Mutex;
+
+/// The EigenDA blobs retrieved during a derivation, keyed by commitment.
+///
+/// A recording is an [EigenDAProvider] itself, replaying the EigenDA retrievals of the
+/// derivation deterministically and without network access, e.g. for golden file regression
+/// tests. Commitments which were not recorded fail the retrieval.
+///
+/// Only EigenDA blobs are recorded. The L1 headers, receipts, transactions and EIP-4844 blobs
+/// of the replayed blocks are still served by the chain and blob providers of the replay.
+///
+/// It is stored as text, one `<commitment> <blob>` pair of 0x-prefixed hex strings per line,
+/// ordered by commitment. Empty lines and lines starting with `#` are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EigenDARecording {
+    /// The recorded blobs, keyed by commitment.
+    pub blobs: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl EigenDARecording {
+    /// Serializes the recording to its text format.
+    pub fn to_text(&self) -> String {
+        self.blobs
+            .iter()
+            .map(|(commitment, blob)| {
+                format!(
+                    "{} {}\n",
+                    hex::encode_prefixed(commitment),
+                    hex::encode_prefixed(blob)
+                )
+            })
+            .collect()
+    }
+
+    /// Parses a recording from its text format.
+    pub fn from_text(text: &str) -> Result<Self, EigenDAProviderError> {
+        let mut blobs = BTreeMap::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |reason: &str| {
+                EigenDAProviderError::InvalidRecording(format!("line {}: {reason}", index + 1))
+            };
+            let mut fields = line.split_whitespace();
+            let (Some(commitment), Some(blob), None) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(invalid("expected a commitment and a blob"));
+            };
+            let commitment = hex::decode(commitment).map_err(|e| invalid(&e.to_string()))?;
+            let blob = hex::decode(blob).map_err(|e| invalid(&e.to_string()))?;
+            blobs.insert(commitment, blob);
+        }
+        Ok(Self { blobs })
+    }
+}
+
+#[async_trait]
+impl EigenDAProvider for EigenDARecording {
+    type Error = EigenDAProviderError;
+
+    async fn blob_get(&self, commitment: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        self.blobs.get(commitment).cloned().ok_or_else(|| {
+            EigenDAProviderError::RetrieveFramesFromDaIndexer(format!(
+                "commitment {} was not recorded",
+                hex::encode_prefixed(commitment)
+            ))
+        })
+    }
+}
+
+/// An [EigenDAProvider] recording the blobs retrieved from the wrapped provider into an
+/// [EigenDARecording], to replay the EigenDA retrievals of the derivation later.
+#[derive(Debug)]
+pub struct RecordingEigenDAProvider<P> {
+    /// The provider the blobs are retrieved from.
+    pub inner: P,
+    /// The blobs retrieved so far.
+    recording: Mutex<EigenDARecording>,
+}
+
+impl<P> RecordingEigenDAProvider<P> {
+    /// Creates a new [RecordingEigenDAProvider] recording the blobs retrieved from `inner`.
+    pub const fn new(inner: P) -> Self {
+        Self {
+            inner,
+            recording: Mutex::new(EigenDARecording {
+                blobs: BTreeMap::new(),
+            }),
+        }
+    }
+
+    /// Returns a copy of the blobs retrieved so far.
+    pub fn recording(&self) -> EigenDARecording {
+        self.recording.lock().clone()
+    }
+
+    /// Consumes the provider, returning the blobs retrieved.
+    pub fn into_recording(self) -> EigenDARecording {
+        self.recording.into_inner()
+    }
+}
+
+#[async_trait]
+impl<P> EigenDAProvider for RecordingEigenDAProvider<P>
+where
+    P: EigenDAProvider + Send + Sync,
+{
+    type Error = P::Error;
+
+    async fn blob_get(&self, commitment: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        let blob = self.inner.blob_get(commitment).await?;
+        self.recording
+            .lock()
+            .blobs
+            .insert(commitment.to_vec(), blob.clone());
+        Ok(blob)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{
+            batcher_address, batcher_tx, encode_commitment, encode_frames, frame_calldata,
+            frame_ref_calldata, test_cert, TestEigenDAProvider, TEST_BATCH_INBOX,
+        },
+        EigenDASource,
+    };
+    use alloc::sync::Arc;
+    use alloy_primitives::{Bytes, B256};
+    use kona_derive::{
+        test_utils::{TestBlobProvider, TestChainProvider},
+        traits::DataAvailabilityProvider,
+    };
+    use kona_protocol::BlockInfo;
+
+    /// The recording of the EigenDA blobs retrieved by [derive].
+    const FIXTURE: &str = include_str!("../../testdata/frame_refs.recording");
+
+    /// Derives a block carrying an inline frame and two frame refs, returning the derived data
+    /// and the EigenDA provider.
+    async fn derive<E>(eigen_da: E) -> (Vec<Bytes>, E)
+    where
+        E: EigenDAProvider + Clone + Send + Sync + 'static,
+    {
+        let block = BlockInfo {
+            hash: B256::repeat_byte(0xbb),
+            number: 10,
+            ..Default::default()
+        };
+        let frame_ref = |data_length, blob_length| {
+            frame_ref_calldata(
+                &encode_commitment(&test_cert(data_length)),
+                blob_length,
+                alloc::vec![0],
+            )
+        };
+        let txs = alloc::vec![
+            batcher_tx(frame_calldata(&[0u8; 4]), 0),
+            batcher_tx(frame_ref(1, 19), 1),
+            batcher_tx(frame_ref(2, 10), 2),
+        ];
+        let mut chain = TestChainProvider::default();
+        chain.insert_block_with_transactions(block.number, block, txs);
+        let mut source = EigenDASource::new(
+            chain,
+            TestBlobProvider::default(),
+            eigen_da,
+            TEST_BATCH_INBOX,
+        );
+
+        let mut data = Vec::new();
+        while let Ok(item) = source.next(&block, batcher_address()).await {
+            data.push(item);
+        }
+        (data, source.eigen_da_provider)
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let mut eigen_da = TestEigenDAProvider::default();
+        let frames = [
+            alloc::vec![1u8; 8],
+            alloc::vec![2u8; 8],
+            alloc::vec![3u8; 8],
+        ];
+        eigen_da.insert_blob(
+            encode_commitment(&test_cert(1)),
+            encode_frames(&frames[..2]),
+        );
+        eigen_da.insert_blob(
+            encode_commitment(&test_cert(2)),
+            encode_frames(&frames[2..]),
+        );
+
+        let recording = Arc::new(RecordingEigenDAProvider::new(eigen_da));
+        let (recorded, provider) = derive(recording).await;
+        assert_eq!(
+            recorded,
+            alloc::vec![
+                Bytes::from_static(&[0u8; 4]),
+                Bytes::from(frames[0].clone()),
+                Bytes::from(frames[1].clone()),
+                Bytes::from(frames[2].clone())
+            ]
+        );
+        // the recording matches the golden file
+        let fixture = EigenDARecording::from_text(FIXTURE).unwrap();
+        assert_eq!(provider.recording(), fixture);
+
+        // replaying the golden file derives the same data
+        let (replayed, _) = derive(fixture).await;
+        assert_eq!(replayed, recorded);
+    }
+
+    #[tokio::test]
+    async fn test_replay_unrecorded_commitment() {
+        let (replayed, _) = derive(EigenDARecording::default()).await;
+        assert!(replayed.is_empty());
+
+        let err = EigenDARecording::default()
+            .blob_get(&[1, 2])
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err,
+            EigenDAProviderError::RetrieveFramesFromDaIndexer(
+                "commitment 0x0102 was not recorded".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_text_format() {
+        let recording = EigenDARecording::from_text(FIXTURE).unwrap();
+        assert_eq!(recording.blobs.len(), 2);
+        assert_eq!(
+            EigenDARecording::from_text(&recording.to_text()),
+            Ok(recording)
+        );
+
+        let err = EigenDARecording::from_text("# comment\n0x01\n").unwrap_err();
+        assert_eq!(
+            err,
+            EigenDAProviderError::InvalidRecording(
+                "line 2: expected a commitment and a blob".to_string()
+            )
+        );
+        assert!(EigenDARecording::from_text("0x01 0xzz").is_err());
+    }
+}
@@ -0,0 +1,15 @@
+//! Contains the default [SignerRecovery].
+
+use crate::derive::SignerRecovery;
+use alloy_consensus::TxEnvelope;
+use alloy_primitives::Address;
+
+/// Recovers the signer from the transaction signature with alloy.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AlloySignerRecovery;
+
+impl SignerRecovery for AlloySignerRecovery {
+    fn recover_signer(&self, tx: &TxEnvelope) -> Option<Address> {
+        tx.recover_signer().ok()
+    }
+}
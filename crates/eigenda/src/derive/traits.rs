@@ -1,13 +1,154 @@
-use alloc::{boxed::Box, string::ToString, vec::Vec};
+use crate::{common::BlobInfo, derive::BatchDataDump, errors::EigenDAProviderError};
+use alloc::{boxed::Box, string::ToString, sync::Arc, vec::Vec};
+use alloy_consensus::TxEnvelope;
+use alloy_primitives::{Address, B256};
 use async_trait::async_trait;
-use core::fmt::Display;
-use kona_derive::errors::PipelineErrorKind;
+use core::fmt::{Debug, Display};
+use kona_derive::{errors::PipelineErrorKind, traits::ChainProvider};
+use kona_protocol::BlockInfo;
 
 /// Describes the functionality of the Eigen DA client needed to fetch a blob
+///
+/// Blobs are retrieved through a shared reference, so that the blobs of a block can be
+/// retrieved concurrently. Providers keeping state across retrievals use interior mutability.
 #[async_trait]
 pub trait EigenDAProvider {
     type Error: Display + ToString + Into<PipelineErrorKind>;
 
     /// Retrieves a blob with the given commitment.
-    async fn blob_get(&mut self, commitment: &[u8]) -> Result<Vec<u8>, Self::Error>;
+    async fn blob_get(&self, commitment: &[u8]) -> Result<Vec<u8>, Self::Error>;
+
+    /// Retrieves a blob with the given commitment along with the cert decoded from the
+    /// commitment.
+    async fn blob_get_with_cert(
+        &self,
+        commitment: &[u8],
+    ) -> Result<(Vec<u8>, BlobInfo), Self::Error>
+    where
+        Self::Error: From<alloy_rlp::Error>,
+    {
+        let cert = BlobInfo::from_commitment(commitment)?;
+        let blob = self.blob_get(commitment).await?;
+        Ok((blob, cert))
+    }
+
+    /// Retrieves a blob by its cert hash, see [BlobInfo::cert_hash], for tooling keying blobs
+    /// by it rather than by commitment.
+    ///
+    /// Fails with [EigenDAProviderError::CertHashUnsupported] unless the provider can look
+    /// blobs up by cert hash. The EigenDA Proxy API addresses blobs by commitment only, so
+    /// only providers indexing blobs locally support it, e.g. recorded fixtures.
+    async fn blob_get_by_cert_hash(&self, cert_hash: B256) -> Result<Vec<u8>, Self::Error>
+    where
+        Self::Error: From<EigenDAProviderError>,
+    {
+        let _ = cert_hash;
+        Err(EigenDAProviderError::CertHashUnsupported.into())
+    }
+}
+
+/// A provider shared behind an [Arc], e.g. by the [EigenDASource](crate::EigenDASource)s
+/// recreated on pipeline resets, or by concurrent retrievals.
+#[async_trait]
+impl<P> EigenDAProvider for Arc<P>
+where
+    P: EigenDAProvider + Send + Sync + ?Sized,
+{
+    type Error = P::Error;
+
+    async fn blob_get(&self, commitment: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        (**self).blob_get(commitment).await
+    }
+
+    async fn blob_get_with_cert(
+        &self,
+        commitment: &[u8],
+    ) -> Result<(Vec<u8>, BlobInfo), Self::Error>
+    where
+        Self::Error: From<alloy_rlp::Error>,
+    {
+        (**self).blob_get_with_cert(commitment).await
+    }
+
+    async fn blob_get_by_cert_hash(&self, cert_hash: B256) -> Result<Vec<u8>, Self::Error>
+    where
+        Self::Error: From<EigenDAProviderError>,
+    {
+        (**self).blob_get_by_cert_hash(cert_hash).await
+    }
+}
+
+/// A [ChainProvider] able to look up several consecutive blocks in one request, e.g. with a
+/// JSON-RPC batch, see [BatchingChainProvider](crate::derive::BatchingChainProvider).
+#[async_trait]
+pub trait BatchChainProvider: ChainProvider {
+    /// Returns the info and transactions of the blocks `first..=last`, in order. Fewer blocks
+    /// are returned if the range extends past the chain head.
+    async fn blocks_with_transactions(
+        &mut self,
+        first: u64,
+        last: u64,
+    ) -> Result<Vec<(BlockInfo, Vec<TxEnvelope>)>, Self::Error>;
+}
+
+/// Recovers the sender of a batcher transaction, used to filter out transactions which were
+/// not sent by the batcher.
+pub trait SignerRecovery {
+    /// Returns the signer of `tx`, or [None] if it cannot be recovered.
+    fn recover_signer(&self, tx: &TxEnvelope) -> Option<Address>;
+}
+
+/// Receives the decoded batch data of each block loaded by an
+/// [EigenDASource](crate::EigenDASource), e.g. to write it to a file and inspect exactly what
+/// derivation consumes.
+///
+/// Dumping is a diagnostic: it cannot fail the derivation, sinks logging their own failures.
+pub trait BatchDataSink: Debug + Send + Sync {
+    /// Records the batch data of `block_ref`, in the order derivation consumes it.
+    fn record(&self, block_ref: &BlockInfo, dump: &BatchDataDump);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{encode_commitment, test_cert, TestEigenDAProvider};
+
+    #[tokio::test]
+    async fn test_blob_get_with_cert() {
+        let cert = test_cert(4);
+        let commitment = encode_commitment(&cert);
+        let mut provider = TestEigenDAProvider::default();
+        provider.insert_blob(commitment.clone(), alloc::vec![1, 2, 3]);
+
+        let (blob, decoded) = provider.blob_get_with_cert(&commitment).await.unwrap();
+        assert_eq!(blob, alloc::vec![1, 2, 3]);
+        assert_eq!(decoded, cert);
+
+        let err = provider.blob_get_with_cert(&[1, 0, 0]).await.unwrap_err();
+        assert!(matches!(err, EigenDAProviderError::CertDecodeError(_)));
+
+        // blobs are not looked up by cert hash by default
+        let err = provider
+            .blob_get_by_cert_hash(cert.cert_hash())
+            .await
+            .unwrap_err();
+        assert_eq!(err, EigenDAProviderError::CertHashUnsupported);
+    }
+
+    #[tokio::test]
+    async fn test_shared_provider() {
+        let commitment = encode_commitment(&test_cert(4));
+        let mut provider = TestEigenDAProvider::default();
+        provider.insert_blob(commitment.clone(), alloc::vec![1, 2, 3]);
+
+        // clones share the provider, retrieving concurrently
+        let provider = Arc::new(provider);
+        let shared = provider.clone();
+        let (first, second) =
+            futures::future::join(provider.blob_get(&commitment), shared.blob_get(&commitment))
+                .await;
+        assert_eq!(first.unwrap(), alloc::vec![1, 2, 3]);
+        assert_eq!(second.unwrap(), alloc::vec![1, 2, 3]);
+        assert!(shared.blob_get_with_cert(&commitment).await.is_ok());
+    }
 }
@@ -1,4 +1,7 @@
-use alloc::string::String;
+use alloc::string::{String, ToString};
+use alloy_primitives::B256;
+use core::time::Duration;
+use kona_derive::errors::{BlobDecodingError, PipelineError, PipelineErrorKind};
 use thiserror::Error;
 
 /// An error returned by the [EigenDAProxyError]
@@ -13,15 +16,99 @@ pub enum EigenDAProxyError {
     /// Get blob status error.
     #[error("Failed to get blob status, error: {0}")]
     GetBlobStatus(String),
+    /// Disperse blob error.
+    #[error("Failed to disperse blob, error: {0}")]
+    DisperseBlob(String),
+    /// The proxy accepted the blob for dispersal without returning its commitment yet.
+    #[error("EigenDA proxy accepted the blob without returning its commitment")]
+    DispersalPending,
+    /// The blob read back with the commitment of a dispersal differs from the dispersed one.
+    #[error("EigenDA proxy served another blob than the dispersed one")]
+    DispersalMismatch,
     /// No fund blob from EigenDA.
     #[error("Blob not fund from EigenDA")]
     NotFound,
     /// Network error.
     #[error("Network error: {0}")]
     NetworkError(String),
-    /// Request timeout.
-    #[error("Request blob timeout, error: {0}")]
-    TimeOut(String),
+    /// A single request to the proxy exceeded its share of the retrieve timeout, retried as
+    /// long as the retrieval deadline allows.
+    #[error("EigenDA proxy request attempt timed out after {0:?}")]
+    AttemptTimeout(Duration),
+    /// The retrieval, all attempts and backoff included, exceeded the retrieve timeout.
+    #[error("EigenDA proxy retrieval deadline exceeded: {0}")]
+    DeadlineExceeded(String),
+    /// The proxy answered with an unexpected HTTP status.
+    #[error("EigenDA proxy responded with status {0}")]
+    HttpStatus(u16),
+    /// The proxy answered `200 OK` with an empty body.
+    #[error("EigenDA proxy returned an empty response")]
+    EmptyResponse,
+    /// The proxy response body exceeds the configured size limit.
+    #[error("EigenDA proxy response exceeds the limit of {0} bytes")]
+    ResponseTooLarge(usize),
+    /// The proxy answered with something other than the blob payload, e.g. a cert envelope.
+    #[error("EigenDA proxy returned unexpected content type: {0}")]
+    UnexpectedContentType(String),
+    /// The blob returned by the proxy does not match the cert of the requested commitment.
+    #[error("EigenDA proxy returned a blob not matching the requested commitment: {0}")]
+    CommitmentMismatch(String),
+    /// The proxy, or an auth gateway in front of it, rejected the credentials of the request.
+    #[error("EigenDA proxy rejected the request as unauthorized with status {0}")]
+    Unauthorized(u16),
+    /// The proxy returned a version which cannot be parsed.
+    #[error("EigenDA proxy returned an invalid version: {0}")]
+    InvalidVersion(String),
+    /// The proxy runs a version outside of the known compatible range.
+    #[error("EigenDA proxy version {0} is not compatible")]
+    IncompatibleVersion(String),
+    /// The proxy returned capabilities which cannot be parsed.
+    #[error("EigenDA proxy returned invalid capabilities: {0}")]
+    InvalidCapabilities(String),
+}
+
+/// The derivation path an RLP list of frames is decoded on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FramePath {
+    /// The blob of an EigenDA frame ref.
+    FrameRef,
+    /// The 4844 blobs of a block.
+    Blob4844,
+}
+
+impl FramePath {
+    /// The label of the path in logs.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::FrameRef => "frame_ref",
+            Self::Blob4844 => "blob_4844",
+        }
+    }
+}
+
+impl core::fmt::Display for FramePath {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An error returned when decoding an [EigenDABlobData](crate::common::EigenDABlobData).
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum EigenDADataError {
+    /// The blob holds less data than the content size of its header.
+    #[error("Insufficient blob data, header claims {expected} bytes but the blob holds {actual}")]
+    InsufficientData {
+        /// The content size claimed by the header.
+        expected: usize,
+        /// The size of the data held by the blob.
+        actual: usize,
+    },
+    /// The version byte of the blob header is not a known blob encoding version.
+    #[error("Unknown blob encoding version {0}")]
+    UnknownEncodingVersion(u8),
+    /// The blob is malformed.
+    #[error(transparent)]
+    Blob(#[from] BlobDecodingError),
 }
 
 /// An error returned by the [EigenDAProviderError]
@@ -39,10 +126,107 @@ pub enum EigenDAProviderError {
     /// Error pertaining to the backend transport.
     #[error("{0}")]
     Backend(String),
-    /// Failed to decode RLP.
-    #[error("Failed to decode RLP, error: {0}")]
-    RLPDecodeError(String),
+    /// Failed to decode the RLP list of frames.
+    #[error("Failed to decode RLP of {path} frames, error: {error}")]
+    RLPDecodeError {
+        /// The derivation path the frames were decoded on.
+        path: FramePath,
+        /// The decoding error.
+        error: String,
+    },
+    /// The RLP encoded frames exceed the size or frame count bound.
+    #[error("Frame data out of bounds: {0}")]
+    FrameDataTooLarge(String),
+    /// Failed to decompress a blob.
+    #[error("Failed to decompress blob, error: {0}")]
+    DecompressionError(String),
+    /// A blob decompressed beyond the maximum size.
+    #[error("Decompressed blob exceeds the maximum size of {0} bytes")]
+    DecompressedSizeExceeded(usize),
     /// Failed to decode proto buf.
     #[error("Failed to decode proto buf, error: {0}")]
     ProtoDecodeError(String),
+    /// The frame ref decoded from calldata is invalid.
+    #[error("Invalid frame ref: {0}")]
+    InvalidFrameRef(String),
+    /// Failed to decode the cert of a commitment.
+    #[error("Failed to decode cert, error: {0}")]
+    CertDecodeError(String),
+    /// The inclusion proof of the cert does not prove the blob header against the batch root.
+    #[error("Invalid cert inclusion proof: {0}")]
+    InvalidInclusionProof(String),
+    /// A recording of EigenDA blobs could not be parsed.
+    #[error("Invalid EigenDA recording: {0}")]
+    InvalidRecording(String),
+    /// A dump of the batch data of a block could not be parsed.
+    #[error("Invalid batch data dump: {0}")]
+    InvalidBatchDataDump(String),
+    /// The commitment exceeds the configured maximum length.
+    #[error("Commitment of {length} bytes exceeds the maximum of {max} bytes")]
+    CommitmentTooLarge {
+        /// The length of the commitment.
+        length: usize,
+        /// The maximum commitment length.
+        max: usize,
+    },
+    /// The provider cannot retrieve blobs by cert hash.
+    #[error("Retrieving blobs by cert hash is not supported")]
+    CertHashUnsupported,
+    /// The cert does not meet the required confirmation threshold of a quorum.
+    #[error("Cert confirmation threshold {actual}% of quorum {quorum} is below the required {required}%")]
+    ConfirmationThreshold {
+        /// The quorum number.
+        quorum: u32,
+        /// The confirmation threshold of the cert.
+        actual: u32,
+        /// The required confirmation threshold.
+        required: u32,
+    },
+    /// The blob length of a frame ref exceeds the blob its cert commits to on a quorum.
+    #[error("Frame ref blob length {blob_length} exceeds the {blob_size} bytes of the blob on quorum {quorum}")]
+    BlobLengthExceedsCert {
+        /// The quorum the length is validated against.
+        quorum: u32,
+        /// The blob length of the frame ref.
+        blob_length: usize,
+        /// The size of the blob on the quorum.
+        blob_size: usize,
+    },
+    /// The cert carries no blob params for the quorum the blob length is validated against.
+    #[error("Cert carries no blob params for quorum {0}")]
+    MissingQuorumParams(u32),
+    /// The cert of the frame ref carries no blob params for a quorum required by the rollup.
+    #[error("Cert is missing required quorum {0}")]
+    MissingRequiredQuorum(u32),
+    /// The frame ref declares a quorum its cert carries no blob params for.
+    #[error("Frame ref declares quorum {0} absent from its cert")]
+    QuorumNotInCert(u32),
+    /// The cert references an L1 block more than [STALE_GAP](crate::common::STALE_GAP)
+    /// blocks before the block carrying its frame ref.
+    #[error("Cert referencing block {reference_block_number} is stale at block {block_number}")]
+    StaleCert {
+        /// The number of the L1 block referenced by the cert.
+        reference_block_number: u64,
+        /// The number of the L1 block carrying the frame ref.
+        block_number: u64,
+    },
+    /// A batcher tx carries a calldata frame without value, rejected with
+    /// [EmptyFramePolicy::Reject](crate::derive::EmptyFramePolicy::Reject).
+    #[error("Batcher tx {0} carries a calldata frame without value")]
+    EmptyCalldataFrame(B256),
+    /// A length of a cert or frame ref does not fit the `usize` of the target.
+    #[error("Length {0} exceeds the address space of the target")]
+    LengthOverflow(u64),
+}
+
+impl From<alloy_rlp::Error> for EigenDAProviderError {
+    fn from(e: alloy_rlp::Error) -> Self {
+        Self::CertDecodeError(e.to_string())
+    }
+}
+
+impl From<EigenDAProviderError> for PipelineErrorKind {
+    fn from(e: EigenDAProviderError) -> Self {
+        PipelineError::Provider(e.to_string()).temp()
+    }
 }
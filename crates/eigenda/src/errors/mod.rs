@@ -1,2 +1,2 @@
 mod da;
-pub use da::{EigenDAProviderError, EigenDAProxyError};
+pub use da::{EigenDADataError, EigenDAProviderError, EigenDAProxyError, FramePath};
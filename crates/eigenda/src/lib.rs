@@ -8,6 +8,11 @@ extern crate alloc;
 pub mod common;
 pub mod derive;
 pub mod errors;
+pub mod metrics;
 pub mod proto;
+pub mod retry;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 
 pub use derive::EigenDASource;
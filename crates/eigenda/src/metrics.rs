@@ -0,0 +1,41 @@
+//! Metrics of the EigenDA derivation, recorded with the `metrics` feature through the
+//! recorder installed by the application, e.g. a Prometheus exporter. Without the feature,
+//! nothing is recorded.
+
+/// Counter of the frame refs and batcher transactions skipped by the
+/// [EigenDASource](crate::EigenDASource), labeled with the `reason` they were skipped for.
+pub const FRAMES_SKIPPED: &str = "hydro_eigenda_frames_skipped_total";
+
+/// Counts an item skipped by the source for the given reason.
+pub(crate) fn frame_skipped(reason: &'static str) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(FRAMES_SKIPPED, "reason" => reason).increment(1);
+    #[cfg(not(feature = "metrics"))]
+    let _ = reason;
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+    #[test]
+    fn test_frame_skipped_recorded() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            frame_skipped("wrong_batcher");
+            frame_skipped("wrong_batcher");
+        });
+
+        let snapshot = snapshotter.snapshot().into_vec();
+        let [(key, _, _, value)] = snapshot.as_slice() else {
+            panic!("expected a single metric: {snapshot:?}");
+        };
+        assert_eq!(key.key().name(), FRAMES_SKIPPED);
+        let labels: Vec<_> = key.key().labels().map(|l| (l.key(), l.value())).collect();
+        assert_eq!(labels, [("reason", "wrong_batcher")]);
+        assert!(matches!(value, DebugValue::Counter(2)), "{value:?}");
+    }
+}
@@ -0,0 +1,236 @@
+//! Retries of fallible async operations, shared by the EigenDA providers.
+
+use crate::errors::{EigenDAProviderError, EigenDAProxyError};
+use core::{future::Future, time::Duration};
+
+/// Classifies errors into transient ones, worth retrying, and permanent ones.
+pub trait Retryable {
+    /// Whether retrying the operation may succeed.
+    fn is_retryable(&self) -> bool;
+}
+
+impl Retryable for EigenDAProxyError {
+    fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::RetrieveBlob(_)
+                | Self::RetrieveBlobWithCommitment(_)
+                | Self::GetBlobStatus(_)
+                | Self::NetworkError(_)
+                | Self::AttemptTimeout(_)
+                | Self::HttpStatus(500 | 502 | 503 | 504)
+        )
+    }
+}
+
+impl Retryable for EigenDAProviderError {
+    fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::RetrieveFramesFromDaIndexer(_) | Self::TimeOut(_) | Self::Backend(_)
+        )
+    }
+}
+
+/// How often and how fast a failing operation is retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// The delay before the first retry, doubled on every further retry.
+    pub initial_backoff: Duration,
+    /// The maximum delay between two attempts.
+    pub max_backoff: Duration,
+    /// The maximum random delay added to every backoff, see [RetryPolicy::jittered].
+    pub jitter: Duration,
+}
+
+impl RetryPolicy {
+    /// A policy making up to `max_attempts` attempts, backing off from 100ms up to 5s.
+    pub const fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            jitter: Duration::ZERO,
+        }
+    }
+
+    /// A policy making a single attempt.
+    pub const fn none() -> Self {
+        Self::new(1)
+    }
+
+    /// Returns the delay before the given retry, the first one being `1`.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(retry.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    /// Adds a share of the jitter picked by `random` to the delay, so that clients failing
+    /// together don't retry in lockstep.
+    pub fn jittered(&self, delay: Duration, random: u64) -> Duration {
+        let jitter_nanos = u64::try_from(self.jitter.as_nanos()).unwrap_or(u64::MAX);
+        delay.saturating_add(Duration::from_nanos(
+            random % jitter_nanos.saturating_add(1),
+        ))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+/// Runs `op` until it succeeds, fails with an error which is not [Retryable], or the attempts
+/// of the `policy` are exhausted, returning the last error.
+///
+/// The crate is `no_std`, so the delay between attempts is awaited through `sleep`, e.g.
+/// `tokio::time::sleep`, which is also where the jitter of the policy is applied with
+/// [RetryPolicy::jittered].
+pub async fn retry<T, E, Op, Fut, S, SleepFut>(
+    policy: &RetryPolicy,
+    mut sleep: S,
+    mut op: Op,
+) -> Result<T, E>
+where
+    E: Retryable,
+    Op: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    S: FnMut(Duration) -> SleepFut,
+    SleepFut: Future<Output = ()>,
+{
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Err(e) if e.is_retryable() && attempt < policy.max_attempts => {
+                sleep(policy.backoff(attempt)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec::Vec};
+    use core::cell::{Cell, RefCell};
+
+    /// Runs `op` with the policy, recording the delays instead of sleeping.
+    async fn run<T, Op, Fut>(
+        policy: RetryPolicy,
+        op: Op,
+    ) -> (Result<T, EigenDAProxyError>, Vec<Duration>)
+    where
+        Op: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, EigenDAProxyError>>,
+    {
+        let delays = RefCell::new(Vec::new());
+        let result = retry(
+            &policy,
+            |delay| {
+                delays.borrow_mut().push(delay);
+                async {}
+            },
+            op,
+        )
+        .await;
+        (result, delays.into_inner())
+    }
+
+    #[tokio::test]
+    async fn test_retry_succeeds_after_failures() {
+        let attempts = Cell::new(0);
+        let (result, delays) = run(RetryPolicy::new(5), || {
+            attempts.set(attempts.get() + 1);
+            let attempt = attempts.get();
+            async move {
+                if attempt < 3 {
+                    Err(EigenDAProxyError::NetworkError(
+                        "connection reset".to_string(),
+                    ))
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result, Ok(3));
+        assert_eq!(
+            delays,
+            [Duration::from_millis(100), Duration::from_millis(200)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up() {
+        let attempts = Cell::new(0);
+        let (result, delays) = run(RetryPolicy::new(4), || {
+            attempts.set(attempts.get() + 1);
+            async { Err::<(), _>(EigenDAProxyError::AttemptTimeout(Duration::from_secs(1))) }
+        })
+        .await;
+
+        assert!(matches!(result, Err(EigenDAProxyError::AttemptTimeout(_))));
+        assert_eq!(attempts.get(), 4);
+        assert_eq!(delays.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_permanent_error() {
+        let attempts = Cell::new(0);
+        let (result, delays) = run(RetryPolicy::new(4), || {
+            attempts.set(attempts.get() + 1);
+            async { Err::<(), _>(EigenDAProxyError::NotFound) }
+        })
+        .await;
+
+        assert_eq!(result, Err(EigenDAProxyError::NotFound));
+        assert_eq!(attempts.get(), 1);
+        assert!(delays.is_empty());
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::new(10);
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(7), Duration::from_secs(5));
+        assert_eq!(policy.backoff(64), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_jitter() {
+        let policy = RetryPolicy::new(3);
+        let delay = Duration::from_millis(100);
+        assert_eq!(policy.jittered(delay, u64::MAX), delay);
+
+        let policy = RetryPolicy {
+            jitter: Duration::from_millis(50),
+            ..policy
+        };
+        assert_eq!(policy.jittered(delay, 0), delay);
+        assert_eq!(
+            policy.jittered(delay, 20_000_000),
+            Duration::from_millis(120)
+        );
+        assert!(policy.jittered(delay, u64::MAX) <= Duration::from_millis(150));
+    }
+
+    #[test]
+    fn test_retryable_status() {
+        assert!(EigenDAProxyError::HttpStatus(503).is_retryable());
+        assert!(EigenDAProxyError::HttpStatus(504).is_retryable());
+        assert!(!EigenDAProxyError::HttpStatus(400).is_retryable());
+        assert!(!EigenDAProxyError::HttpStatus(501).is_retryable());
+        assert!(!EigenDAProxyError::NotFound.is_retryable());
+    }
+}
@@ -0,0 +1,242 @@
+//! Test utilities for the EigenDA derivation pipeline.
+
+use crate::{
+    common::{BlobHeader, BlobInfo, BlobQuorumParam, G1Commitment},
+    derive::EigenDAProvider,
+    errors::EigenDAProviderError,
+    proto::{calldata_frame, CalldataFrame, FrameRef},
+};
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    string::ToString,
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
+use alloy_consensus::{SignableTransaction, TxEip1559, TxEnvelope};
+use alloy_primitives::{address, b256, hex, Address, Bytes, TxKind};
+use alloy_rlp::Encodable;
+use alloy_signer::SignerSync;
+use alloy_signer_local::PrivateKeySigner;
+use async_trait::async_trait;
+use core::fmt;
+use prost::Message;
+
+/// The batch inbox address used by the test fixtures.
+pub const TEST_BATCH_INBOX: Address = address!("ff00000000000000000000000000000000000010");
+
+/// Returns the signer of the test batcher.
+pub fn batcher_signer() -> PrivateKeySigner {
+    PrivateKeySigner::from_bytes(&b256!(
+        "0101010101010101010101010101010101010101010101010101010101010101"
+    ))
+    .expect("valid private key")
+}
+
+/// Returns the address of the test batcher.
+pub fn batcher_address() -> Address {
+    batcher_signer().address()
+}
+
+/// Builds an eip-1559 transaction to `to` carrying `calldata`, signed by `signer`.
+pub fn signed_tx(
+    signer: &PrivateKeySigner,
+    to: Address,
+    calldata: Bytes,
+    nonce: u64,
+) -> TxEnvelope {
+    let tx = TxEip1559 {
+        chain_id: 1,
+        nonce,
+        gas_limit: 1_000_000,
+        to: TxKind::Call(to),
+        input: calldata,
+        ..Default::default()
+    };
+    let signature = signer
+        .sign_hash_sync(&tx.signature_hash())
+        .expect("signing failed");
+    TxEnvelope::Eip1559(tx.into_signed(signature))
+}
+
+/// Builds a transaction from the test batcher to the test batch inbox.
+pub fn batcher_tx(calldata: Bytes, nonce: u64) -> TxEnvelope {
+    signed_tx(&batcher_signer(), TEST_BATCH_INBOX, calldata, nonce)
+}
+
+/// Encodes a [CalldataFrame] as batcher calldata, prefixed with the EigenDA derivation version.
+pub fn calldata_frame_calldata(value: calldata_frame::Value) -> Bytes {
+    let frame = CalldataFrame { value: Some(value) };
+    let mut calldata = vec![crate::derive::DERIVATION_VERSION_EIGEN_DA];
+    calldata.extend(frame.encode_to_vec());
+    calldata.into()
+}
+
+/// Batcher calldata carrying an inline frame.
+pub fn frame_calldata(frame: &[u8]) -> Bytes {
+    calldata_frame_calldata(calldata_frame::Value::Frame(frame.to_vec()))
+}
+
+/// Batcher calldata carrying a reference to an EigenDA blob of `blob_length` bytes.
+pub fn frame_ref_calldata(commitment: &[u8], blob_length: u32, quorum_ids: Vec<u32>) -> Bytes {
+    calldata_frame_calldata(calldata_frame::Value::FrameRef(FrameRef {
+        quorum_ids,
+        blob_length,
+        commitment: commitment.to_vec(),
+        ..Default::default()
+    }))
+}
+
+/// RLP encodes a list of frames, as stored in an EigenDA blob.
+pub fn encode_frames(frames: &[Vec<u8>]) -> Vec<u8> {
+    rlp::encode_list::<Vec<u8>, _>(frames).to_vec()
+}
+
+/// Gzip compresses `data`, as a batcher compressing its blobs would.
+pub fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+    out.extend(miniz_oxide::deflate::compress_to_vec(data, 6));
+    out.extend(crate::derive::compression::crc32(data).to_le_bytes());
+    out.extend((data.len() as u32).to_le_bytes());
+    out
+}
+
+/// Returns a v1 cert for a blob of `data_length` field elements, dispersed to quorums 0 and 1
+/// with a confirmation threshold of 55%.
+pub fn test_cert(data_length: u32) -> BlobInfo {
+    let quorum_param = |quorum_number| BlobQuorumParam {
+        quorum_number,
+        adversary_threshold_percentage: 33,
+        confirmation_threshold_percentage: 55,
+        chunk_length: 1,
+    };
+    BlobInfo {
+        blob_header: BlobHeader {
+            commitment: G1Commitment {
+                x: [0x11; 32],
+                y: [0x22; 32],
+            },
+            data_length,
+            blob_quorum_params: vec![quorum_param(0), quorum_param(1)],
+        },
+        ..Default::default()
+    }
+}
+
+/// Encodes the cert into a commitment as posted by the batcher, i.e. prefixed with the
+/// 3 bytes of commitment metadata.
+pub fn encode_commitment(cert: &BlobInfo) -> Vec<u8> {
+    let mut commitment = vec![0x01, 0x00, 0x00];
+    cert.encode(&mut commitment);
+    commitment
+}
+
+/// A hook injecting retrieval errors into a [TestEigenDAProvider], called with the commitment
+/// of every retrieval. The retrieval fails with the returned error, if any.
+pub type ErrorHook = Arc<dyn Fn(&[u8]) -> Option<EigenDAProviderError> + Send + Sync>;
+
+/// An in-memory [EigenDAProvider] serving blobs keyed by commitment, for deterministic tests
+/// of pipelines built on the [EigenDASource](crate::EigenDASource).
+///
+/// Retrievals of the commitments registered with [TestEigenDAProvider::fail_commitment]
+/// always fail, then the [ErrorHook] may fail the others, before the blob is looked up.
+#[derive(Clone, Default)]
+pub struct TestEigenDAProvider {
+    /// The blobs, keyed by commitment.
+    pub blobs: BTreeMap<Vec<u8>, Vec<u8>>,
+    /// The commitments whose retrieval always fails.
+    failing: BTreeSet<Vec<u8>>,
+    /// Injects errors into the retrievals when set.
+    error_hook: Option<ErrorHook>,
+}
+
+/// The [TestEigenDAProvider] under the name downstream crates mock the EigenDA Proxy with.
+pub type MockEigenDAProvider = TestEigenDAProvider;
+
+impl fmt::Debug for TestEigenDAProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestEigenDAProvider")
+            .field("blobs", &self.blobs.len())
+            .field("failing", &self.failing.len())
+            .field("error_hook", &self.error_hook.is_some())
+            .finish()
+    }
+}
+
+impl TestEigenDAProvider {
+    /// Creates a new [TestEigenDAProvider] serving the given blobs, keyed by commitment.
+    pub fn with_blobs(blobs: impl IntoIterator<Item = (Vec<u8>, Vec<u8>)>) -> Self {
+        Self {
+            blobs: blobs.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Inserts a blob under the given commitment.
+    pub fn insert_blob(&mut self, commitment: Vec<u8>, blob: Vec<u8>) {
+        self.blobs.insert(commitment, blob);
+    }
+
+    /// Fails every retrieval of the commitment, whether or not its blob is served.
+    pub fn fail_commitment(&mut self, commitment: Vec<u8>) {
+        self.failing.insert(commitment);
+    }
+
+    /// Injects the errors returned by `hook` into the retrievals.
+    pub fn with_error_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&[u8]) -> Option<EigenDAProviderError> + Send + Sync + 'static,
+    {
+        self.error_hook = Some(Arc::new(hook));
+        self
+    }
+}
+
+#[async_trait]
+impl EigenDAProvider for TestEigenDAProvider {
+    type Error = EigenDAProviderError;
+
+    async fn blob_get(&self, commitment: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        if self.failing.contains(commitment) {
+            return Err(EigenDAProviderError::Status(alloc::format!(
+                "injected failure of commitment {}",
+                hex::encode_prefixed(commitment)
+            )));
+        }
+        if let Some(e) = self.error_hook.as_ref().and_then(|hook| hook(commitment)) {
+            return Err(e);
+        }
+        self.blobs.get(commitment).cloned().ok_or_else(|| {
+            EigenDAProviderError::RetrieveFramesFromDaIndexer("blob not found".to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_provider() {
+        let commitment = encode_commitment(&test_cert(1));
+        let mut provider = MockEigenDAProvider::with_blobs([(commitment.clone(), vec![1, 2, 3])])
+            .with_error_hook(|commitment| {
+                (commitment[0] == 0xff)
+                    .then(|| EigenDAProviderError::TimeOut("injected".to_string()))
+            });
+        assert_eq!(provider.blob_get(&commitment).await, Ok(vec![1, 2, 3]));
+        assert!(provider.blob_get(&[0x01]).await.is_err());
+        assert_eq!(
+            provider.blob_get(&[0xff]).await,
+            Err(EigenDAProviderError::TimeOut("injected".to_string()))
+        );
+
+        // a failing commitment fails even though its blob is served
+        provider.fail_commitment(commitment.clone());
+        let err = provider.blob_get(&commitment).await.unwrap_err();
+        assert!(err.to_string().contains("injected failure"), "{err}");
+        // clones share the error hook
+        assert!(provider.clone().blob_get(&[0xff]).await.is_err());
+    }
+}
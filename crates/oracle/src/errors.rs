@@ -0,0 +1,63 @@
+//! Errors of the oracle-backed EigenDA provider.
+
+use alloc::string::{String, ToString};
+use hydro_eigenda::errors::EigenDADataError;
+use kona_derive::errors::{PipelineError, PipelineErrorKind};
+use kona_proof::errors::OracleProviderError;
+use thiserror::Error;
+
+/// An error returned by the [OracleEigenDaProvider](crate::OracleEigenDaProvider).
+#[derive(Error, Debug)]
+pub enum EigenDAClientError {
+    /// The commitment is too short to contain a cert.
+    #[error("Commitment does not contain a cert header")]
+    MissingCertHeader,
+    /// The commitment exceeds the configured maximum length.
+    #[error("Commitment of {length} bytes exceeds the maximum of {max} bytes")]
+    CommitmentTooLarge {
+        /// The length of the commitment.
+        length: usize,
+        /// The maximum commitment length.
+        max: usize,
+    },
+    /// Failed to decode the cert from the commitment.
+    #[error("Failed to decode cert, error: {0}")]
+    CertDecode(#[from] alloy_rlp::Error),
+    /// The preimages served by the host breach an invariant of the blob reconstruction.
+    #[error("EigenDA blob invariant breached: {0}")]
+    InvariantBreach(String),
+    /// The host flagged the cert as invalid, serving the all-zero sentinel as first field
+    /// element instead of the blob.
+    #[error("EigenDA cert was flagged as invalid by the host")]
+    InvalidCert,
+    /// The reconstructed blob does not match the commitment of the cert.
+    #[error("EigenDA blob does not match the cert commitment: {0}")]
+    CommitmentMismatch(String),
+    /// Failed to decode the reconstructed blob.
+    #[error("Failed to decode EigenDA blob, error: {0}")]
+    BlobDecode(EigenDADataError),
+    /// Error communicating with the preimage oracle.
+    #[error(transparent)]
+    Oracle(#[from] OracleProviderError),
+}
+
+impl EigenDAClientError {
+    /// Whether retrying the retrieval may succeed.
+    ///
+    /// Only failures to communicate with the oracle are transient, every other error is
+    /// deterministic in the commitment and the preimages of the host.
+    pub const fn is_retryable(&self) -> bool {
+        matches!(self, Self::Oracle(_))
+    }
+}
+
+impl From<EigenDAClientError> for PipelineErrorKind {
+    fn from(e: EigenDAClientError) -> Self {
+        let err = PipelineError::Provider(e.to_string());
+        if e.is_retryable() {
+            err.temp()
+        } else {
+            err.crit()
+        }
+    }
+}
@@ -6,6 +6,17 @@ use kona_proof::{errors::HintParsingError, HintType};
 pub enum HintWrapper {
     Standard(HintType),
     EigenDABlob,
+    /// Only the preimages of the cert of a commitment, without the blob and its witness, for
+    /// clients validating a cert without reading the blob.
+    EigenDACert,
+}
+
+impl HintWrapper {
+    /// All hints added on top of the standard kona hints.
+    ///
+    /// The wire strings of these hints are shared by the host and the client, changing one
+    /// breaks their compatibility.
+    pub const EIGEN_DA_HINTS: &'static [Self] = &[Self::EigenDABlob, Self::EigenDACert];
 }
 
 impl FromStr for HintWrapper {
@@ -18,6 +29,7 @@ impl FromStr for HintWrapper {
 
         match s {
             "eigen-da-blob" => Ok(HintWrapper::EigenDABlob),
+            "eigen-da-cert" => Ok(HintWrapper::EigenDACert),
             _ => Err(HintParsingError(String::from("unknown hint"))),
         }
     }
@@ -28,6 +40,50 @@ impl fmt::Display for HintWrapper {
         match self {
             HintWrapper::Standard(hint) => write!(f, "{hint}"),
             HintWrapper::EigenDABlob => write!(f, "eigen-da-blob"),
+            HintWrapper::EigenDACert => write!(f, "eigen-da-cert"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    /// The pinned wire string of every hint, matching exhaustively so that a new variant
+    /// cannot be added without pinning its string.
+    fn wire_string(hint: HintWrapper) -> Option<&'static str> {
+        match hint {
+            HintWrapper::Standard(_) => None,
+            HintWrapper::EigenDABlob => Some("eigen-da-blob"),
+            HintWrapper::EigenDACert => Some("eigen-da-cert"),
         }
     }
+
+    #[test]
+    fn test_eigen_da_hints_round_trip() {
+        for hint in HintWrapper::EIGEN_DA_HINTS {
+            let wire = hint.to_string();
+            assert_eq!(Some(wire.as_str()), wire_string(*hint));
+            assert_eq!(HintWrapper::from_str(&wire).unwrap(), *hint);
+        }
+    }
+
+    #[test]
+    fn test_standard_hints_round_trip() {
+        for standard in [
+            HintType::L1BlockHeader,
+            HintType::L1Blob,
+            HintType::L2BlockHeader,
+        ] {
+            let hint = HintWrapper::Standard(standard);
+            assert_eq!(hint.to_string(), standard.to_string());
+            assert_eq!(HintWrapper::from_str(&hint.to_string()).unwrap(), hint);
+        }
+    }
+
+    #[test]
+    fn test_unknown_hint() {
+        assert!(HintWrapper::from_str("eigen-da-unknown").is_err());
+    }
 }
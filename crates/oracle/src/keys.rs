@@ -0,0 +1,82 @@
+//! The preimage keys the host writes for an EigenDA cert, for tooling to cross-check the
+//! key-value store of a host independently of it.
+
+use crate::errors::EigenDAClientError;
+use crate::kzg::blob_challenge;
+use alloc::vec::Vec;
+use alloy_primitives::B256;
+use hydro_eigenda::common::{
+    blob_complete_key, eigenda_field_element_key, kzg_commitment_key, kzg_opening_key, BlobInfo,
+    Keccak256Hasher, KeyHasher,
+};
+use kona_preimage::{PreimageKey, PreimageKeyType};
+
+/// Returns the keys of the preimages the host writes for the blob of the cert, derived with
+/// the [Keccak256Hasher], see [expected_keys_with_hasher].
+pub fn expected_keys(cert: &BlobInfo) -> Vec<PreimageKey> {
+    expected_keys_with_hasher::<Keccak256Hasher>(cert)
+}
+
+/// Returns the keys of the preimages the host writes for the blob of the cert, derived with
+/// the [KeyHasher] `H`, in write order: the `Keccak256` key of the key preimage and the
+/// `GlobalGeneric` key of the value of every field element, then the ones of the KZG
+/// commitment and of the completion marker.
+///
+/// The keys of the KZG opening derive from the blob, see [expected_opening_keys].
+pub fn expected_keys_with_hasher<H: KeyHasher>(cert: &BlobInfo) -> Vec<PreimageKey> {
+    let commitment = &cert.blob_header.commitment;
+    let field_elements = (0..cert.blob_header.data_length as u64)
+        .map(|i| H::hash_key(&eigenda_field_element_key(&commitment.x, &commitment.y, i)));
+    field_elements
+        .chain([
+            H::hash_key(&kzg_commitment_key(commitment)),
+            H::hash_key(&blob_complete_key(commitment)),
+        ])
+        .flat_map(key_pair)
+        .collect()
+}
+
+/// Returns the keys of the KZG opening the host writes for the blob of the cert, unless it
+/// trusts the proxy commitment, derived with the [KeyHasher] `H`.
+///
+/// The opening is keyed by the challenge of the blob, so the rollup data of the blob is needed
+/// along with the cert. Fails if the cert commitment is not a G1 point.
+pub fn expected_opening_keys<H: KeyHasher>(
+    cert: &BlobInfo,
+    blob: &[u8],
+) -> Result<[PreimageKey; 2], EigenDAClientError> {
+    let (_, _, z) = blob_challenge(cert, blob)?;
+    let opening_key = kzg_opening_key(&cert.blob_header.commitment, &z);
+    Ok(key_pair(H::hash_key(&opening_key)))
+}
+
+/// Returns the `Keccak256` key of a key preimage and the `GlobalGeneric` key of its value.
+fn key_pair(hash: B256) -> [PreimageKey; 2] {
+    [
+        PreimageKey::new(*hash, PreimageKeyType::Keccak256),
+        PreimageKey::new(*hash, PreimageKeyType::GlobalGeneric),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_cert;
+
+    #[test]
+    fn test_expected_keys() {
+        let cert = test_cert(3);
+        let keys = expected_keys(&cert);
+        assert_eq!(keys.len(), 10);
+
+        let first = eigenda_field_element_key(&[0x11; 32], &[0x22; 32], 0);
+        assert_eq!(keys[..2], key_pair(Keccak256Hasher::hash_key(&first)));
+        let commitment = kzg_commitment_key(&cert.blob_header.commitment);
+        assert_eq!(keys[6..8], key_pair(Keccak256Hasher::hash_key(&commitment)));
+        let marker = blob_complete_key(&cert.blob_header.commitment);
+        assert_eq!(keys[8..], key_pair(Keccak256Hasher::hash_key(&marker)));
+
+        // the test cert does not commit to a curve point
+        assert!(expected_opening_keys::<Keccak256Hasher>(&cert, &[1, 2, 3]).is_err());
+    }
+}
@@ -0,0 +1,79 @@
+//! Verification of the KZG opening of a reconstructed blob against the commitment of its cert.
+
+use crate::errors::EigenDAClientError;
+use alloc::string::ToString;
+use alloy_primitives::B256;
+use ark_bn254::{Fq, G1Affine};
+use ark_ff::{BigInteger, PrimeField};
+use hydro_eigenda::common::{BlobInfo, EigenDABlobData, BLOB_ENCODING_VERSION_0};
+use rust_kzg_bn254_primitives::{blob::Blob, errors::KzgError, helpers::compute_challenge};
+use rust_kzg_bn254_verifier::verify::verify_blob_kzg_proof;
+
+/// Decodes a G1 point from its 32 byte big endian coordinates, [None] if it is not a canonical
+/// point of the curve.
+pub(crate) fn g1_point(x: &[u8], y: &[u8]) -> Option<G1Affine> {
+    let coordinate = |bytes: &[u8]| {
+        let value = Fq::from_be_bytes_mod_order(bytes);
+        (value.into_bigint().to_bytes_be() == bytes).then_some(value)
+    };
+    let point = G1Affine::new_unchecked(coordinate(x)?, coordinate(y)?);
+    point.is_on_curve().then_some(point)
+}
+
+/// Returns the Fiat-Shamir challenge the blob polynomial is opened at, as a 32 byte big endian
+/// field element.
+pub(crate) fn challenge(blob: &Blob, commitment: &G1Affine) -> Result<B256, KzgError> {
+    let z = compute_challenge(blob, commitment)?;
+    Ok(B256::from_slice(&z.into_bigint().to_bytes_be()))
+}
+
+/// Returns the blob as encoded by the host, the point of the cert commitment and the
+/// challenge the blob polynomial is opened at.
+pub(crate) fn blob_challenge(
+    cert_blob_info: &BlobInfo,
+    blob: &[u8],
+) -> Result<(Blob, G1Affine, B256), EigenDAClientError> {
+    let commitment = &cert_blob_info.blob_header.commitment;
+    let commitment_point = g1_point(&commitment.x, &commitment.y).ok_or_else(|| {
+        EigenDAClientError::CommitmentMismatch("cert commitment is not a G1 point".into())
+    })?;
+    // the opening is computed over the blob as encoded by the host
+    let encoded = EigenDABlobData::encode(blob, BLOB_ENCODING_VERSION_0)
+        .map_err(|e| EigenDAClientError::CommitmentMismatch(e.to_string()))?;
+    let kzg_blob = Blob::new(&encoded.blob);
+    let z = challenge(&kzg_blob, &commitment_point)
+        .map_err(|e| EigenDAClientError::CommitmentMismatch(e.to_string()))?;
+    Ok((kzg_blob, commitment_point, z))
+}
+
+/// Verifies that the proof opens the blob polynomial committed to by `commitment` at its
+/// challenge.
+pub(crate) fn verify_opening(
+    blob: &Blob,
+    commitment: &G1Affine,
+    proof: &G1Affine,
+) -> Result<bool, KzgError> {
+    verify_blob_kzg_proof(blob, commitment, proof)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The big endian coordinate `value`.
+    fn coordinate(value: u8) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes[31] = value;
+        bytes
+    }
+
+    #[test]
+    fn test_g1_point() {
+        // the generator of G1
+        assert!(g1_point(&coordinate(1), &coordinate(2)).is_some());
+        assert!(g1_point(&coordinate(1), &coordinate(3)).is_none());
+        // coordinates beyond the field modulus are not canonical
+        assert!(g1_point(&[0xff; 32], &coordinate(2)).is_none());
+        assert!(g1_point(&coordinate(1), &[0x02; 31]).is_none());
+    }
+}
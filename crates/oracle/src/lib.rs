@@ -5,6 +5,19 @@
 
 extern crate alloc;
 
+pub mod errors;
+pub use errors::EigenDAClientError;
+
 pub mod hint;
+pub mod keys;
+pub use keys::{expected_keys, expected_keys_with_hasher, expected_opening_keys};
+
+mod kzg;
+pub mod progress;
+pub use progress::ReconstructionProgress;
+
 pub mod provider;
 pub use provider::OracleEigenDaProvider;
+
+#[cfg(test)]
+pub(crate) mod test_utils;
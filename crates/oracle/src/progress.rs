@@ -0,0 +1,50 @@
+//! Progress reporting of the blob reconstruction.
+
+use alloc::sync::Arc;
+use core::fmt;
+use tracing::debug;
+
+/// Reports the progress of the reconstruction of a blob from its field elements, to tell a
+/// slow reconstruction of a large blob from a hang.
+#[derive(Clone)]
+pub struct ReconstructionProgress {
+    /// The number of field elements between two reports.
+    interval: u64,
+    /// Called with the number of field elements read so far and the total.
+    report: Arc<dyn Fn(u64, u64) + Send + Sync>,
+}
+
+impl fmt::Debug for ReconstructionProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReconstructionProgress")
+            .field("interval", &self.interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ReconstructionProgress {
+    /// Calls `report` every `interval` field elements and once all are read.
+    pub fn new<R>(interval: u64, report: R) -> Self
+    where
+        R: Fn(u64, u64) + Send + Sync + 'static,
+    {
+        Self {
+            interval: interval.max(1),
+            report: Arc::new(report),
+        }
+    }
+
+    /// Logs the progress at debug level every `interval` field elements.
+    pub fn logging(interval: u64) -> Self {
+        Self::new(interval, |read, total| {
+            debug!(target: "eigenda-oracle", "Reconstructed {read}/{total} field elements");
+        })
+    }
+
+    /// Reports the progress if `read` field elements out of `total` are due for a report.
+    pub(crate) fn on_field_element(&self, read: u64, total: u64) {
+        if read % self.interval == 0 || read == total {
+            (self.report)(read, total);
+        }
+    }
+}
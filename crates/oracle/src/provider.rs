@@ -1,44 +1,181 @@
+use crate::errors::EigenDAClientError;
 use crate::hint::HintWrapper;
+use crate::kzg::{blob_challenge, g1_point, verify_opening};
+use crate::progress::ReconstructionProgress;
 use alloc::boxed::Box;
 use alloc::string::ToString;
 use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
-use alloy_primitives::{keccak256, Bytes};
-use alloy_rlp::Decodable;
+use alloy_primitives::Bytes;
 use async_trait::async_trait;
-use hydro_eigenda::common::{BlobInfo, EigenDABlobData, BYTES_PER_FIELD_ELEMENT};
+use core::marker::PhantomData;
+use hydro_eigenda::common::{
+    blob_complete_key, eigenda_field_element_key, field_elements_size, kzg_opening_key, BlobInfo,
+    EigenDABlobData, G1Commitment, Keccak256Hasher, KeyHasher, BYTES_PER_FIELD_ELEMENT,
+    DEFAULT_MAX_COMMITMENT_LENGTH,
+};
 use hydro_eigenda::derive::EigenDAProvider;
-use kona_preimage::errors::PreimageOracleError;
 use kona_preimage::{CommsClient, PreimageKey, PreimageKeyType};
 use kona_proof::errors::OracleProviderError;
 use kona_proof::Hint;
 use tracing::debug;
 
 /// An oracle-backed eigenDA provider.
+///
+/// The preimage keys of the blob field elements are derived with the [KeyHasher] `H`, which
+/// must match the hasher used by the host when it populated the preimages.
 #[derive(Debug, Clone)]
-pub struct OracleEigenDaProvider<T: CommsClient> {
+pub struct OracleEigenDaProvider<T: CommsClient, H = Keccak256Hasher> {
     /// The preimage oracle client.
     pub oracle: Arc<T>,
+    /// The maximum length of a commitment, longer ones are rejected before being hinted.
+    pub max_commitment_length: usize,
+    /// Reports the progress of blob reconstructions, off when [None].
+    pub progress: Option<ReconstructionProgress>,
+    /// Whether reconstructed blobs are verified against the commitment of their cert, on by
+    /// default.
+    pub check_commitment: bool,
+    /// The key derivation hash function.
+    _hasher: PhantomData<fn() -> H>,
 }
 
 impl<T: CommsClient> OracleEigenDaProvider<T> {
     /// Constructs a new `OracleEigenDaProvider`.
     pub fn new(oracle: Arc<T>) -> Self {
-        Self { oracle }
+        Self::with_hasher(oracle)
+    }
+}
+
+impl<T: CommsClient, H: KeyHasher> OracleEigenDaProvider<T, H> {
+    /// Constructs a new `OracleEigenDaProvider` deriving preimage keys with the hasher `H`.
+    pub fn with_hasher(oracle: Arc<T>) -> Self {
+        Self {
+            oracle,
+            max_commitment_length: DEFAULT_MAX_COMMITMENT_LENGTH,
+            progress: None,
+            check_commitment: true,
+            _hasher: PhantomData,
+        }
+    }
+
+    /// Sets the maximum length of a commitment.
+    pub fn with_max_commitment_length(mut self, max_commitment_length: usize) -> Self {
+        self.max_commitment_length = max_commitment_length;
+        self
+    }
+
+    /// Reports the progress of blob reconstructions.
+    pub fn with_progress(mut self, progress: ReconstructionProgress) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Verifies every reconstructed blob against the commitment of its cert, failing with
+    /// [EigenDAClientError::CommitmentMismatch] otherwise, which is the default.
+    ///
+    /// The host serves the KZG opening of the blob at its Fiat-Shamir challenge, which is
+    /// checked against the cert commitment, so that a host can't feed arbitrary field elements.
+    /// The host must not trust the proxy commitment, as the opening is not served then.
+    pub fn with_commitment_check(mut self) -> Self {
+        self.check_commitment = true;
+        self
+    }
+
+    /// Serves reconstructed blobs without verifying them against the commitment of their cert,
+    /// e.g. for a host trusting the proxy commitment. Unsafe outside of tests and debugging, as
+    /// the host can then feed arbitrary field elements.
+    pub fn without_commitment_check(mut self) -> Self {
+        self.check_commitment = false;
+        self
+    }
+
+    /// Checks that the host completed writing the preimages of the blob, reading the completion
+    /// marker it writes last, so that a partially written blob is never read.
+    async fn check_complete(
+        &self,
+        commitment: &G1Commitment,
+        data_length: u64,
+    ) -> Result<(), EigenDAClientError> {
+        let mut marker = [0u8; 8];
+        let marker_key = blob_complete_key(commitment);
+        self.oracle
+            .get_exact(
+                PreimageKey::new(*H::hash_key(&marker_key), PreimageKeyType::GlobalGeneric),
+                &mut marker,
+            )
+            .await
+            .map_err(OracleProviderError::Preimage)?;
+        let completed = u64::from_be_bytes(marker);
+        if completed != data_length {
+            return Err(EigenDAClientError::InvariantBreach(alloc::format!(
+                "host completed a blob of {completed} field elements, expected {data_length}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Verifies the KZG opening of the blob served by the host against the cert commitment.
+    async fn verify_commitment(
+        &self,
+        cert_blob_info: &BlobInfo,
+        blob: &[u8],
+    ) -> Result<(), EigenDAClientError> {
+        let (kzg_blob, commitment_point, z) = blob_challenge(cert_blob_info, blob)?;
+
+        // the evaluation at the challenge followed by the proof
+        let mut opening = [0u8; 96];
+        let opening_key = kzg_opening_key(&cert_blob_info.blob_header.commitment, &z);
+        self.oracle
+            .get_exact(
+                PreimageKey::new(*H::hash_key(&opening_key), PreimageKeyType::GlobalGeneric),
+                &mut opening,
+            )
+            .await
+            .map_err(OracleProviderError::Preimage)?;
+        let proof = g1_point(&opening[32..64], &opening[64..]).ok_or_else(|| {
+            EigenDAClientError::CommitmentMismatch("KZG proof is not a G1 point".into())
+        })?;
+
+        match verify_opening(&kzg_blob, &commitment_point, &proof) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(EigenDAClientError::CommitmentMismatch(
+                "KZG opening does not verify against the cert commitment".into(),
+            )),
+            Err(e) => Err(EigenDAClientError::CommitmentMismatch(e.to_string())),
+        }
     }
 }
 
 #[async_trait]
-impl<T: CommsClient + Sync + Send> EigenDAProvider for OracleEigenDaProvider<T> {
-    type Error = OracleProviderError;
+impl<T: CommsClient + Sync + Send, H: KeyHasher> EigenDAProvider for OracleEigenDaProvider<T, H> {
+    type Error = EigenDAClientError;
+
+    async fn blob_get(&self, commitment: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        self.blob_get_with_cert(commitment)
+            .await
+            .map(|(blob, _)| blob)
+    }
 
-    async fn blob_get(&mut self, commitment: &[u8]) -> Result<Vec<u8>, Self::Error> {
+    async fn blob_get_with_cert(
+        &self,
+        commitment: &[u8],
+    ) -> Result<(Vec<u8>, BlobInfo), Self::Error>
+    where
+        Self::Error: From<alloy_rlp::Error>,
+    {
         debug!(
             "Starting to retrieve blob from EigenDA with commitment: {:?}",
             commitment
         );
 
+        if commitment.len() > self.max_commitment_length {
+            return Err(EigenDAClientError::CommitmentTooLarge {
+                length: commitment.len(),
+                max: self.max_commitment_length,
+            });
+        }
+
         // same as HintType::EigenDa.with_data(&[commitment.as_ref()]).send(self.oracle.as_ref()).await?;
         let mut encoded = Vec::new();
         encoded.extend_from_slice(commitment);
@@ -48,59 +185,346 @@ impl<T: CommsClient + Sync + Send> EigenDAProvider for OracleEigenDaProvider<T>
         // the fourth because 0x010000 in the beginning is metadata
         // cert should at least contain 32 bytes for header + 3 bytes for commitment type metadata
         if commitment.len() <= 32 + 3 {
-            return Err(OracleProviderError::Preimage(PreimageOracleError::Other(
-                "does not contain header".into(),
-            )));
+            return Err(EigenDAClientError::MissingCertHeader);
         }
 
         // the first four bytes are metadata, like cert version, OP generic commitement
         // see https://github.com/Layr-Labs/eigenda-proxy/blob/main/commitments/mode.go#L39
         // the first byte my guess is the OP
-        let cert_blob_info = BlobInfo::decode(&mut &commitment[3..]).unwrap();
+        let cert_blob_info =
+            BlobInfo::from_commitment(commitment).map_err(EigenDAClientError::CertDecode)?;
 
-        // data_length measurs in field element, multiply to get num bytes
-        let mut blob: Vec<u8> =
-            vec![0; cert_blob_info.blob_header.data_length as usize * BYTES_PER_FIELD_ELEMENT];
+        // a blob always contains at least its header field element
+        if cert_blob_info.blob_header.data_length == 0 {
+            return Err(EigenDAClientError::InvariantBreach(
+                "cert data length is zero".into(),
+            ));
+        }
 
-        // 96 because our g1 commitment has 64 bytes in v1
-        // why 96, the original 4844 has bytes length of 80 (it has 48 bytes for commitment)
-        // even then, it is not that the entire 80 bytes are used. Some bytes are empty
-        // for solidity optimization, I remember.
-        //
-        // TODO: investigate later to decide a right size
-        let mut blob_key = [0u8; 96];
+        // data_length measurs in field element, multiply to get num bytes
+        let blob_size = field_elements_size(cert_blob_info.blob_header.data_length)
+            .map_err(|e| EigenDAClientError::InvariantBreach(e.to_string()))?;
+        let mut blob: Vec<u8> = vec![0; blob_size];
 
         // In eigenDA terminology, length describes the number of field element, size describes
         // number of bytes.
         let data_length = cert_blob_info.blob_header.data_length as u64;
 
-        // the common key
-        blob_key[..32].copy_from_slice(&cert_blob_info.blob_header.commitment.x);
-        blob_key[32..64].copy_from_slice(&cert_blob_info.blob_header.commitment.y);
-
-        // + 1 for the proof
+        let commitment = &cert_blob_info.blob_header.commitment;
+        self.check_complete(commitment, data_length).await?;
         for i in 0..data_length {
-            blob_key[88..].copy_from_slice(i.to_be_bytes().as_ref());
+            let blob_key = eigenda_field_element_key(&commitment.x, &commitment.y, i);
 
             let mut field_element = [0u8; 32];
             self.oracle
                 .get_exact(
-                    PreimageKey::new(*keccak256(blob_key), PreimageKeyType::GlobalGeneric),
+                    PreimageKey::new(*H::hash_key(&blob_key), PreimageKeyType::GlobalGeneric),
                     &mut field_element,
                 )
                 .await
                 .map_err(OracleProviderError::Preimage)?;
 
+            // an empty blob is never dispersed, so a zero header is the sentinel of a cert the
+            // host found invalid rather than an encoding of no data
+            if i == 0 && field_element == [0u8; 32] {
+                return Err(EigenDAClientError::InvalidCert);
+            }
             blob[(i as usize) << 5..(i as usize + 1) << 5].copy_from_slice(field_element.as_ref());
+            if let Some(progress) = &self.progress {
+                progress.on_field_element(i + 1, data_length);
+            }
         }
 
+        check_blob_length(&blob, blob_size)?;
         let eigenda_blob_data = EigenDABlobData::new(Bytes::copy_from_slice(&blob));
-        let blobs = eigenda_blob_data.decode();
+        let blob_data = eigenda_blob_data
+            .decode()
+            .map_err(EigenDAClientError::BlobDecode)?;
+
+        if self.check_commitment {
+            self.verify_commitment(&cert_blob_info, &blob_data).await?;
+        }
+        Ok((blob_data.to_vec(), cert_blob_info))
+    }
+}
+
+/// Checks that the reconstructed blob holds exactly the `expected` bytes of the field elements
+/// of the cert, anything else being a reconstruction bug.
+fn check_blob_length(blob: &[u8], expected: usize) -> Result<(), EigenDAClientError> {
+    if blob.len() != expected {
+        return Err(EigenDAClientError::InvariantBreach(alloc::format!(
+            "reconstructed blob has {} bytes, expected {expected}",
+            blob.len()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::kzg::challenge;
+    use crate::test_utils::{encode_commitment, test_cert, MockOracle};
+    use alloy_primitives::B256;
+    use hydro_eigenda::common::BLOB_ENCODING_VERSION_0;
+    use rust_kzg_bn254_primitives::blob::Blob;
+
+    /// A stub hasher folding the key preimage into 32 bytes.
+    #[derive(Debug, Clone, Copy)]
+    struct FoldHasher;
+
+    impl KeyHasher for FoldHasher {
+        fn hash_key(data: &[u8]) -> B256 {
+            let mut out = [0u8; 32];
+            for (i, b) in data.iter().enumerate() {
+                out[i % 32] ^= b;
+            }
+            out.into()
+        }
+    }
+
+    fn populate<H: KeyHasher>(oracle: &MockOracle, cert: &BlobInfo, encoded: &[u8]) {
+        let commitment = &cert.blob_header.commitment;
+        for i in 0..cert.blob_header.data_length as u64 {
+            let blob_key = eigenda_field_element_key(&commitment.x, &commitment.y, i);
+            let start = (i as usize) << 5;
+            oracle.insert(
+                PreimageKey::new(*H::hash_key(&blob_key), PreimageKeyType::GlobalGeneric),
+                encoded[start..start + 32].to_vec(),
+            );
+        }
+        let marker_key = blob_complete_key(commitment);
+        oracle.insert(
+            PreimageKey::new(*H::hash_key(&marker_key), PreimageKeyType::GlobalGeneric),
+            (cert.blob_header.data_length as u64).to_be_bytes().to_vec(),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_blob_get_with_alternative_hasher() {
+        let rollup_data = vec![7u8; 100];
+        let encoded = EigenDABlobData::encode(&rollup_data, BLOB_ENCODING_VERSION_0)
+            .unwrap()
+            .blob;
+        let cert = test_cert((encoded.len() / BYTES_PER_FIELD_ELEMENT) as u32);
+        let commitment = encode_commitment(&cert);
+
+        let oracle = MockOracle::default();
+        populate::<FoldHasher>(&oracle, &cert, &encoded);
+
+        let provider =
+            OracleEigenDaProvider::<_, FoldHasher>::with_hasher(Arc::new(oracle.clone()))
+                .without_commitment_check();
+        assert_eq!(provider.blob_get(&commitment).await.unwrap(), rollup_data);
+
+        // keys derived by a different hasher are not found
+        let provider = OracleEigenDaProvider::new(Arc::new(oracle));
+        assert!(matches!(
+            provider.blob_get(&commitment).await,
+            Err(EigenDAClientError::Oracle(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_blob_get_reports_progress() {
+        let rollup_data = vec![7u8; 300];
+        let encoded = EigenDABlobData::encode(&rollup_data, BLOB_ENCODING_VERSION_0)
+            .unwrap()
+            .blob;
+        let cert = test_cert((encoded.len() / BYTES_PER_FIELD_ELEMENT) as u32);
+        assert_eq!(cert.blob_header.data_length, 11);
+
+        let oracle = MockOracle::default();
+        populate::<Keccak256Hasher>(&oracle, &cert, &encoded);
+
+        let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = reports.clone();
+        let progress = ReconstructionProgress::new(4, move |read, total| {
+            recorded.lock().unwrap().push((read, total));
+        });
+        let provider = OracleEigenDaProvider::new(Arc::new(oracle))
+            .without_commitment_check()
+            .with_progress(progress);
+        assert_eq!(
+            provider.blob_get(&encode_commitment(&cert)).await.unwrap(),
+            rollup_data
+        );
+
+        assert_eq!(*reports.lock().unwrap(), vec![(4, 11), (8, 11), (11, 11)]);
+    }
+
+    #[tokio::test]
+    async fn test_blob_get_missing_cert_header() {
+        let provider = OracleEigenDaProvider::new(Arc::new(MockOracle::default()));
+        let err = provider
+            .blob_get(&[0x01, 0x00, 0x00, 0xaa])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EigenDAClientError::MissingCertHeader));
+        assert!(!err.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_blob_get_commitment_too_large() {
+        let oracle = MockOracle::default();
+        let provider =
+            OracleEigenDaProvider::new(Arc::new(oracle.clone())).with_max_commitment_length(64);
+        let err = provider.blob_get(&[0x01; 65]).await.unwrap_err();
+        assert!(matches!(
+            err,
+            EigenDAClientError::CommitmentTooLarge {
+                length: 65,
+                max: 64
+            }
+        ));
+        // rejected before hinting the host
+        assert!(oracle.hints().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_blob_get_cert_decode_error() {
+        let provider = OracleEigenDaProvider::new(Arc::new(MockOracle::default()));
+        let err = provider.blob_get(&[0xff; 64]).await.unwrap_err();
+        assert!(matches!(err, EigenDAClientError::CertDecode(_)));
+    }
+
+    #[tokio::test]
+    async fn test_blob_get_invariant_breach() {
+        let provider = OracleEigenDaProvider::new(Arc::new(MockOracle::default()));
+        let err = provider
+            .blob_get(&encode_commitment(&test_cert(0)))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EigenDAClientError::InvariantBreach(_)));
+    }
+
+    #[tokio::test]
+    async fn test_blob_get_blob_decode_error() {
+        let cert = test_cert(2);
+        let oracle = MockOracle::default();
+        // header field element with an unknown encoding version
+        let mut encoded = vec![0u8; 64];
+        encoded[1] = 0xff;
+        populate::<Keccak256Hasher>(&oracle, &cert, &encoded);
+
+        let provider = OracleEigenDaProvider::new(Arc::new(oracle));
+        let err = provider
+            .blob_get(&encode_commitment(&cert))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EigenDAClientError::BlobDecode(_)));
+    }
+
+    #[tokio::test]
+    async fn test_blob_get_oracle_error_is_retryable() {
+        let provider = OracleEigenDaProvider::new(Arc::new(MockOracle::default()));
+        let err = provider
+            .blob_get(&encode_commitment(&test_cert(1)))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EigenDAClientError::Oracle(_)));
+        assert!(err.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_blob_get_invalid_cert_sentinel() {
+        let cert = test_cert(2);
+        let oracle = MockOracle::default();
+        populate::<Keccak256Hasher>(&oracle, &cert, &[0u8; 64]);
+
+        let provider = OracleEigenDaProvider::new(Arc::new(oracle));
+        let err = provider
+            .blob_get(&encode_commitment(&cert))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EigenDAClientError::InvalidCert));
+        assert!(!err.is_retryable());
+    }
+
+    /// A cert committing to the generator of G1, a valid point for any blob.
+    fn generator_cert(data_length: u32) -> BlobInfo {
+        let mut cert = test_cert(data_length);
+        cert.blob_header.commitment.x = [0u8; 32];
+        cert.blob_header.commitment.x[31] = 1;
+        cert.blob_header.commitment.y = [0u8; 32];
+        cert.blob_header.commitment.y[31] = 2;
+        cert
+    }
+
+    #[tokio::test]
+    async fn test_blob_get_commitment_mismatch() {
+        let rollup_data = vec![7u8; 100];
+        let encoded = EigenDABlobData::encode(&rollup_data, BLOB_ENCODING_VERSION_0)
+            .unwrap()
+            .blob;
+        let data_length = (encoded.len() / BYTES_PER_FIELD_ELEMENT) as u32;
+
+        // the test cert does not commit to a curve point
+        let cert = test_cert(data_length);
+        let oracle = MockOracle::default();
+        populate::<Keccak256Hasher>(&oracle, &cert, &encoded);
+        let provider = OracleEigenDaProvider::new(Arc::new(oracle));
+        let err = provider
+            .blob_get(&encode_commitment(&cert))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EigenDAClientError::CommitmentMismatch(_)));
+        assert!(!err.is_retryable());
+
+        let cert = generator_cert(data_length);
+        let oracle = MockOracle::default();
+        populate::<Keccak256Hasher>(&oracle, &cert, &encoded);
+        let provider = OracleEigenDaProvider::new(Arc::new(oracle.clone()));
+        // the opening is looked up at the challenge of the blob
+        let err = provider
+            .blob_get(&encode_commitment(&cert))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EigenDAClientError::Oracle(_)));
+
+        // an opening which does not prove the blob
+        let commitment = g1_point(
+            &cert.blob_header.commitment.x,
+            &cert.blob_header.commitment.y,
+        );
+        let z = challenge(&Blob::new(&encoded), &commitment.unwrap()).unwrap();
+        let opening_key = kzg_opening_key(&cert.blob_header.commitment, &z);
+        let opening = [
+            [0u8; 32],
+            cert.blob_header.commitment.x,
+            cert.blob_header.commitment.y,
+        ];
+        let opening_key = *Keccak256Hasher::hash_key(&opening_key);
+        oracle.insert(
+            PreimageKey::new(opening_key, PreimageKeyType::GlobalGeneric),
+            opening.concat(),
+        );
+        let err = provider
+            .blob_get(&encode_commitment(&cert))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, EigenDAClientError::CommitmentMismatch(_)),
+            "{err}"
+        );
+
+        // without the check the blob is served as is
+        let provider = OracleEigenDaProvider::new(Arc::new(oracle)).without_commitment_check();
+        assert_eq!(
+            provider.blob_get(&encode_commitment(&cert)).await.unwrap(),
+            rollup_data
+        );
+    }
+
+    #[test]
+    fn test_check_blob_length() {
+        assert!(check_blob_length(&[0u8; 64], 64).is_ok());
 
-        blobs
-            .map_err(|err| {
-                OracleProviderError::Preimage(PreimageOracleError::Other(err.to_string()))
-            })
-            .map(|blob_data| blob_data.to_vec())
+        let err = check_blob_length(&[0u8; 65], 64).unwrap_err();
+        assert!(matches!(err, EigenDAClientError::InvariantBreach(_)));
+        let err = check_blob_length(&[0u8; 32], 64).unwrap_err();
+        assert!(matches!(err, EigenDAClientError::InvariantBreach(_)));
     }
 }
@@ -0,0 +1,91 @@
+//! Test helpers for the oracle crate.
+
+extern crate std;
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use alloy_primitives::B256;
+use alloy_rlp::Encodable;
+use async_trait::async_trait;
+use hydro_eigenda::common::{BlobHeader, BlobInfo, G1Commitment};
+use kona_preimage::{
+    errors::{PreimageOracleError, PreimageOracleResult},
+    HintWriterClient, PreimageKey, PreimageOracleClient,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// An in-memory preimage oracle recording the hints it receives.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MockOracle {
+    preimages: Arc<Mutex<HashMap<B256, Vec<u8>>>>,
+    hints: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockOracle {
+    /// Inserts a preimage under the given key.
+    pub(crate) fn insert(&self, key: PreimageKey, value: Vec<u8>) {
+        self.preimages.lock().unwrap().insert(key.into(), value);
+    }
+
+    /// The hints written so far.
+    pub(crate) fn hints(&self) -> Vec<String> {
+        self.hints.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl PreimageOracleClient for MockOracle {
+    async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+        self.preimages
+            .lock()
+            .unwrap()
+            .get(&B256::from(key))
+            .cloned()
+            .ok_or(PreimageOracleError::KeyNotFound)
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
+        let value = self.get(key).await?;
+        if value.len() != buf.len() {
+            return Err(PreimageOracleError::BufferLengthMismatch(
+                buf.len(),
+                value.len(),
+            ));
+        }
+        buf.copy_from_slice(&value);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HintWriterClient for MockOracle {
+    async fn write(&self, hint: &str) -> PreimageOracleResult<()> {
+        self.hints.lock().unwrap().push(hint.into());
+        Ok(())
+    }
+}
+
+/// Returns a v1 cert for a blob of `data_length` field elements.
+pub(crate) fn test_cert(data_length: u32) -> BlobInfo {
+    BlobInfo {
+        blob_header: BlobHeader {
+            commitment: G1Commitment {
+                x: [0x11; 32],
+                y: [0x22; 32],
+            },
+            data_length,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// Encodes the cert into a commitment as posted by the batcher, i.e. prefixed with the
+/// 3 bytes of commitment metadata.
+pub(crate) fn encode_commitment(cert: &BlobInfo) -> Vec<u8> {
+    let mut commitment = alloc::vec![0x01, 0x00, 0x00];
+    cert.encode(&mut commitment);
+    commitment
+}
@@ -1,20 +1,251 @@
-use alloc::vec::Vec;
+use alloc::{sync::Arc, vec::Vec};
 use alloy_primitives::Bytes;
+use core::fmt;
 use num::BigUint;
 use rust_kzg_bn254_primitives::blob::Blob;
 use rust_kzg_bn254_primitives::errors::KzgError;
+use rust_kzg_bn254_primitives::helpers::{
+    compute_challenge, evaluate_polynomial_in_evaluation_form, read_g1_point_from_bytes_be,
+};
 use rust_kzg_bn254_prover::kzg::KZG;
 use rust_kzg_bn254_prover::srs::SRS;
+use std::{io::Read, path::PathBuf, sync::OnceLock};
+
+/// The version of the binary layout written by [EigenDABlobWitness::to_bytes].
+pub const WITNESS_ENCODING_VERSION: u8 = 2;
+
+/// The path of the G1 points of the EigenDA KZG trusted setup, relative to the working
+/// directory.
+pub const DEFAULT_G1_PATH: &str = "resources/g1.point";
+
+/// The order of the EigenDA KZG trusted setup.
+pub const DEFAULT_SRS_ORDER: u32 = 268435456;
+
+/// The number of G1 points of the EigenDA KZG trusted setup loaded by default.
+pub const DEFAULT_SRS_POINTS: u32 = 1000000;
+
+/// The size of a compressed G1 point of a trusted setup, and of a blob field element.
+const POINT_SIZE: usize = 32;
+
+/// The trusted setup used by [EigenDABlobWitness::push_witness], see [default_srs].
+static DEFAULT_SRS: OnceLock<KzgSrs> = OnceLock::new();
+
+/// Where the G1 points of a KZG trusted setup are read from, each a 32 byte big endian
+/// compressed point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SrsSource {
+    /// A file of G1 points, e.g. [DEFAULT_G1_PATH].
+    Path(PathBuf),
+    /// G1 points in memory, e.g. embedded with `include_bytes!`.
+    Bytes(Bytes),
+}
+
+impl SrsSource {
+    /// Reads the G1 points of a reader into memory.
+    pub fn from_reader(mut reader: impl Read) -> std::io::Result<Self> {
+        let mut points = Vec::new();
+        reader.read_to_end(&mut points)?;
+        Ok(Self::Bytes(points.into()))
+    }
+}
+
+/// Where and how much of the KZG trusted setup (SRS) is loaded to compute a witness.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrsConfig {
+    /// Where the G1 points are read from.
+    pub source: SrsSource,
+    /// The order of the trusted setup.
+    pub order: u32,
+    /// The number of G1 points loaded, at least the number of field elements of a blob.
+    pub points_to_load: u32,
+}
+
+impl Default for SrsConfig {
+    fn default() -> Self {
+        Self {
+            source: SrsSource::Path(DEFAULT_G1_PATH.into()),
+            order: DEFAULT_SRS_ORDER,
+            points_to_load: DEFAULT_SRS_POINTS,
+        }
+    }
+}
+
+impl SrsConfig {
+    /// Returns the default configuration, reading the G1 points from the file at `path`.
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        Self {
+            source: SrsSource::Path(path.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Loads the configured G1 points, at most [SrsConfig::points_to_load] of them.
+    pub fn load(&self) -> Result<KzgSrs, WitnessError> {
+        let srs = match &self.source {
+            SrsSource::Path(path) => {
+                let path = path.to_str().ok_or_else(|| {
+                    KzgError::GenericError(format!("non UTF-8 SRS path {}", path.display()))
+                })?;
+                SRS::new(path, self.order, self.points_to_load)?
+            }
+            SrsSource::Bytes(bytes) => {
+                if bytes.len() % POINT_SIZE != 0 {
+                    return Err(KzgError::SerializationError(format!(
+                        "SRS of {} bytes is not a whole number of G1 points",
+                        bytes.len()
+                    ))
+                    .into());
+                }
+                let g1 = bytes
+                    .chunks_exact(POINT_SIZE)
+                    .take(self.points_to_load as usize)
+                    .map(|point| {
+                        read_g1_point_from_bytes_be(point)
+                            .map_err(|e| KzgError::SerializationError(e.to_string()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                SRS {
+                    g1,
+                    order: self.order,
+                }
+            }
+        };
+        Ok(KzgSrs(Arc::new(srs)))
+    }
+}
+
+/// A loaded KZG trusted setup, shared by all the witnesses computed with it.
+#[derive(Clone)]
+pub struct KzgSrs(Arc<SRS>);
+
+impl fmt::Debug for KzgSrs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KzgSrs")
+            .field("points", &self.num_points())
+            .finish_non_exhaustive()
+    }
+}
+
+impl KzgSrs {
+    /// Returns the number of loaded G1 points.
+    pub fn num_points(&self) -> usize {
+        self.0.g1.len()
+    }
+
+    /// Checks that the trusted setup has a G1 point for every field element of a blob of
+    /// `blob_size` bytes, e.g. of the largest blob of the EigenDA deployment.
+    pub fn check_blob_size(&self, blob_size: usize) -> Result<(), WitnessError> {
+        let required = blob_size.div_ceil(POINT_SIZE);
+        if required > self.num_points() {
+            return Err(WitnessError::SrsTooSmall {
+                points: self.num_points(),
+                required,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Sets the trusted setup of [EigenDABlobWitness::push_witness] for the rest of the process,
+/// handing it back if one is already set, or was loaded by a witness computed before.
+pub fn set_default_srs(srs: KzgSrs) -> Result<(), KzgSrs> {
+    DEFAULT_SRS.set(srs)
+}
+
+/// Returns the trusted setup of [EigenDABlobWitness::push_witness], loading the one of the
+/// default [SrsConfig] once unless set with [set_default_srs].
+pub fn default_srs() -> Result<KzgSrs, WitnessError> {
+    if let Some(srs) = DEFAULT_SRS.get() {
+        return Ok(srs.clone());
+    }
+    let srs = SrsConfig::default().load()?;
+    Ok(DEFAULT_SRS.get_or_init(|| srs).clone())
+}
+
+/// An error computing a witness with [EigenDABlobWitness::push_witness].
+#[derive(Debug)]
+pub enum WitnessError {
+    /// The trusted setup could not be loaded or the KZG computation failed.
+    Kzg(KzgError),
+    /// The trusted setup has fewer G1 points than the blob has field elements.
+    SrsTooSmall {
+        /// The number of G1 points of the trusted setup.
+        points: usize,
+        /// The number of field elements of the blob.
+        required: usize,
+    },
+    /// The KZG commitment computed from the blob is not the expected one.
+    CommitmentMismatch {
+        /// The `x ‖ y` commitment computed from the blob.
+        computed: Bytes,
+    },
+}
+
+impl fmt::Display for WitnessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Kzg(e) => write!(f, "KZG witness computation failed: {e}"),
+            Self::SrsTooSmall { points, required } => write!(
+                f,
+                "KZG trusted setup of {points} G1 points is too small for a blob of {required} \
+                 field elements"
+            ),
+            Self::CommitmentMismatch { computed } => {
+                write!(
+                    f,
+                    "computed KZG commitment {computed} differs from the expected one"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for WitnessError {}
+
+impl From<KzgError> for WitnessError {
+    fn from(e: KzgError) -> Self {
+        Self::Kzg(e)
+    }
+}
+
+/// An error decoding a witness with [EigenDABlobWitness::from_bytes].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WitnessDecodingError {
+    /// The encoding version is not supported.
+    UnsupportedVersion(u8),
+    /// The input ended before the witness was fully decoded.
+    UnexpectedEnd,
+    /// Bytes are left after the witness.
+    TrailingBytes(usize),
+}
+
+impl fmt::Display for WitnessDecodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported witness encoding version {version}")
+            }
+            Self::UnexpectedEnd => write!(f, "unexpected end of witness"),
+            Self::TrailingBytes(len) => write!(f, "{len} trailing bytes after witness"),
+        }
+    }
+}
+
+impl std::error::Error for WitnessDecodingError {}
 
 /// stores the witness for a eigenDA blob
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct EigenDABlobWitness {
     /// The eigenDA blobs
     pub eigenda_blobs: Vec<Bytes>,
     /// The commitments
     pub commitments: Vec<Bytes>,
-    /// The proofs
+    /// The proofs of the evaluations at the challenges
     pub proofs: Vec<Bytes>,
+    /// The Fiat-Shamir challenges the blob polynomials are evaluated at
+    pub challenges: Vec<Bytes>,
+    /// The evaluations of the blob polynomials at the challenges
+    pub evaluations: Vec<Bytes>,
 }
 
 /// Witness for a eigenDA blob
@@ -25,18 +256,43 @@ impl EigenDABlobWitness {
             eigenda_blobs: Vec::new(),
             commitments: Vec::new(),
             proofs: Vec::new(),
+            challenges: Vec::new(),
+            evaluations: Vec::new(),
         }
     }
 
+    /// Computes the witness of an encoded eigenDA blob with the [default_srs], see
+    /// [EigenDABlobWitness::push_witness_with_srs].
+    pub fn push_witness(
+        &mut self,
+        blob: &[u8],
+        expected_commitment: &[u8],
+    ) -> Result<(), WitnessError> {
+        self.push_witness_with_srs(blob, expected_commitment, &default_srs()?)
+    }
+
     /// This function computes a witness for a eigenDA blob
     /// nitro code https://github.com/Layr-Labs/nitro/blob/14f09745b74321f91d1f702c3e7bb5eb7d0e49ce/arbitrator/prover/src/kzgbn254.rs#L141
     /// could refactor in the future, such that both host and client can compute the proof
-    pub fn push_witness(&mut self, blob: &[u8]) -> Result<(), KzgError> {
-        // TODO remove the need for G2 access
-        // Add command line to specify where are g1 and g2 path
+    ///
+    /// The KZG commitment is computed from the blob field elements with the trusted setup
+    /// `srs`, which must have a G1 point for each of them, and must be exactly the `x ‖ y`
+    /// `expected_commitment`, e.g. the one of the cert, else nothing is pushed. The
+    /// commitments of the witness are thus always the ones of their blobs.
+    ///
+    /// The proof opens the blob polynomial at the challenge `z` derived from the blob and the
+    /// commitment, see [compute_challenge]. The challenge and the evaluation at it are stored
+    /// as 32 byte big endian field elements.
+    pub fn push_witness_with_srs(
+        &mut self,
+        blob: &[u8],
+        expected_commitment: &[u8],
+        srs: &KzgSrs,
+    ) -> Result<(), WitnessError> {
+        srs.check_blob_size(blob.len())?;
+        let srs = &*srs.0;
         // In the future, it might make sense to let the proxy to return such
         // value, instead of local computation
-        let srs = SRS::new("resources/g1.point", 268435456, 1000000).unwrap();
         let mut kzg = KZG::new();
 
         let input = Blob::new(blob);
@@ -46,7 +302,7 @@ impl EigenDABlobWitness {
 
         let mut commitment_bytes = vec![0u8; 0];
 
-        let commitment = kzg.commit_eval_form(&input_poly, &srs)?;
+        let commitment = kzg.commit_eval_form(&input_poly, srs)?;
 
         // TODO the library should have returned the bytes, or provide a helper
         // for conversion. For both proof and commitment
@@ -55,31 +311,124 @@ impl EigenDABlobWitness {
 
         append_left_padded_biguint_be(&mut commitment_bytes, &commitment_x_bigint);
         append_left_padded_biguint_be(&mut commitment_bytes, &commitment_y_bigint);
+        if commitment_bytes != expected_commitment {
+            return Err(WitnessError::CommitmentMismatch {
+                computed: commitment_bytes.into(),
+            });
+        }
 
         let mut proof_bytes = vec![0u8; 0];
 
-        let proof = kzg.compute_blob_proof(&input, &commitment, &srs)?;
+        let proof = kzg.compute_blob_proof(&input, &commitment, srs)?;
         let proof_x_bigint: BigUint = proof.x.into();
         let proof_y_bigint: BigUint = proof.y.into();
 
         append_left_padded_biguint_be(&mut proof_bytes, &proof_x_bigint);
         append_left_padded_biguint_be(&mut proof_bytes, &proof_y_bigint);
 
+        // the same challenge the blob proof is computed at
+        let challenge = compute_challenge(&input, &commitment)?;
+        let evaluation = evaluate_polynomial_in_evaluation_form(&input_poly, &challenge)?;
+
+        let mut challenge_bytes = vec![0u8; 0];
+        append_left_padded_biguint_be(&mut challenge_bytes, &challenge.into());
+        let mut evaluation_bytes = vec![0u8; 0];
+        append_left_padded_biguint_be(&mut evaluation_bytes, &evaluation.into());
+
         // push data into witness
         self.write(
             Bytes::copy_from_slice(blob),
             Bytes::copy_from_slice(&commitment_bytes),
             proof_bytes.into(),
+            challenge_bytes.into(),
+            evaluation_bytes.into(),
         );
 
         Ok(())
     }
 
-    fn write(&mut self, blob: Bytes, commitment: Bytes, proof: Bytes) {
+    /// Serializes the witness, so that it can be produced by a separate process.
+    ///
+    /// The layout is a version byte ([WITNESS_ENCODING_VERSION]) followed by the number of
+    /// entries as a big endian `u32`, then for each entry the blob, the commitment, the proof,
+    /// the challenge and the evaluation, each prefixed with its length as a big endian `u32`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = vec![WITNESS_ENCODING_VERSION];
+        out.extend_from_slice(&(self.eigenda_blobs.len() as u32).to_be_bytes());
+        let entries = self
+            .eigenda_blobs
+            .iter()
+            .zip(&self.commitments)
+            .zip(&self.proofs)
+            .zip(&self.challenges)
+            .zip(&self.evaluations);
+        for ((((blob, commitment), proof), challenge), evaluation) in entries {
+            for field in [blob, commitment, proof, challenge, evaluation] {
+                out.extend_from_slice(&(field.len() as u32).to_be_bytes());
+                out.extend_from_slice(field);
+            }
+        }
+        out
+    }
+
+    /// Deserializes a witness written by [EigenDABlobWitness::to_bytes].
+    pub fn from_bytes(mut data: &[u8]) -> Result<Self, WitnessDecodingError> {
+        let version = take(&mut data, 1)?[0];
+        if version != WITNESS_ENCODING_VERSION {
+            return Err(WitnessDecodingError::UnsupportedVersion(version));
+        }
+
+        let count = take_u32(&mut data)?;
+        let mut witness = Self::new();
+        for _ in 0..count {
+            let blob = take_field(&mut data)?;
+            let commitment = take_field(&mut data)?;
+            let proof = take_field(&mut data)?;
+            let challenge = take_field(&mut data)?;
+            let evaluation = take_field(&mut data)?;
+            witness.write(blob, commitment, proof, challenge, evaluation);
+        }
+
+        if !data.is_empty() {
+            return Err(WitnessDecodingError::TrailingBytes(data.len()));
+        }
+        Ok(witness)
+    }
+
+    fn write(
+        &mut self,
+        blob: Bytes,
+        commitment: Bytes,
+        proof: Bytes,
+        challenge: Bytes,
+        evaluation: Bytes,
+    ) {
         self.eigenda_blobs.push(blob);
         self.commitments.push(commitment);
         self.proofs.push(proof);
+        self.challenges.push(challenge);
+        self.evaluations.push(evaluation);
+    }
+}
+
+/// Splits `len` bytes off the front of `data`.
+fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], WitnessDecodingError> {
+    if data.len() < len {
+        return Err(WitnessDecodingError::UnexpectedEnd);
     }
+    let (head, tail) = data.split_at(len);
+    *data = tail;
+    Ok(head)
+}
+
+fn take_u32(data: &mut &[u8]) -> Result<u32, WitnessDecodingError> {
+    let bytes = take(data, 4)?;
+    Ok(u32::from_be_bytes(bytes.try_into().expect("4 bytes")))
+}
+
+fn take_field(data: &mut &[u8]) -> Result<Bytes, WitnessDecodingError> {
+    let len = take_u32(data)? as usize;
+    take(data, len).map(Bytes::copy_from_slice)
 }
 
 /// This function convert a BigUint into 32Bytes vector in big endian format
@@ -89,3 +438,167 @@ fn append_left_padded_biguint_be(vec: &mut Vec<u8>, biguint: &BigUint) {
     vec.extend(std::iter::repeat(0).take(padding));
     vec.extend_from_slice(&bytes);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_witness() -> EigenDABlobWitness {
+        let mut witness = EigenDABlobWitness::new();
+        witness.write(
+            Bytes::from(vec![1u8; 96]),
+            Bytes::from(vec![2u8; 64]),
+            Bytes::from(vec![3u8; 64]),
+            Bytes::from(vec![4u8; 32]),
+            Bytes::from(vec![5u8; 32]),
+        );
+        witness.write(
+            Bytes::from(vec![6u8; 32]),
+            Bytes::from(vec![7u8; 64]),
+            Bytes::new(),
+            Bytes::new(),
+            Bytes::new(),
+        );
+        witness
+    }
+
+    /// The path of the G1 points of the EigenDA trusted setup.
+    const G1_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../", "resources/g1.point");
+
+    /// A small trusted setup, loading the first points of the EigenDA one.
+    fn test_srs() -> SrsConfig {
+        SrsConfig {
+            points_to_load: 64,
+            ..SrsConfig::from_path(G1_PATH)
+        }
+    }
+
+    /// An encoded blob of 4 field elements, each with a zero leading byte.
+    fn test_blob() -> Vec<u8> {
+        (0..128u32)
+            .map(|i| if i % 32 == 0 { 0 } else { i as u8 })
+            .collect()
+    }
+
+    #[test]
+    fn test_push_witness_checks_commitment() {
+        let blob = test_blob();
+        let srs = test_srs().load().unwrap();
+
+        let mut witness = EigenDABlobWitness::new();
+        let err = witness
+            .push_witness_with_srs(&blob, &[0u8; 64], &srs)
+            .unwrap_err();
+        let WitnessError::CommitmentMismatch { computed } = err else {
+            panic!("unexpected error {err}");
+        };
+        assert_eq!(computed.len(), 64);
+        assert_eq!(witness, EigenDABlobWitness::new());
+
+        // trailing bytes are not ignored
+        let extended = [computed.as_ref(), &[0u8; 32]].concat();
+        assert!(witness
+            .push_witness_with_srs(&blob, &extended, &srs)
+            .is_err());
+
+        witness
+            .push_witness_with_srs(&blob, &computed, &srs)
+            .unwrap();
+        assert_eq!(witness.commitments, vec![computed.clone()]);
+        assert_eq!(witness.proofs[0].len(), 64);
+        assert_eq!(witness.challenges[0].len(), 32);
+    }
+
+    #[test]
+    fn test_srs_sources() {
+        let blob = test_blob();
+        let srs = test_srs().load().unwrap();
+        assert_eq!(srs.num_points(), 64);
+        let err = EigenDABlobWitness::new()
+            .push_witness_with_srs(&blob, &[0u8; 64], &srs)
+            .unwrap_err();
+        let WitnessError::CommitmentMismatch {
+            computed: commitment,
+        } = err
+        else {
+            panic!("unexpected error {err}");
+        };
+
+        // the same points read into memory give the same commitment
+        let file = std::fs::File::open(G1_PATH).unwrap();
+        let config = SrsConfig {
+            source: SrsSource::from_reader(file.take(64 * 32)).unwrap(),
+            ..test_srs()
+        };
+        let srs = config.load().unwrap();
+        assert_eq!(srs.num_points(), 64);
+        let mut from_bytes = EigenDABlobWitness::new();
+        from_bytes
+            .push_witness_with_srs(&blob, &commitment, &srs)
+            .unwrap();
+        assert_eq!(from_bytes.commitments, vec![commitment.clone()]);
+
+        // a setup smaller than the blob fails before computing anything
+        let config = SrsConfig {
+            points_to_load: 2,
+            ..config
+        };
+        let err = from_bytes
+            .push_witness_with_srs(&blob, &commitment, &config.load().unwrap())
+            .unwrap_err();
+        let WitnessError::SrsTooSmall { points, required } = err else {
+            panic!("unexpected error {err}");
+        };
+        assert_eq!((points, required), (2, 4));
+        assert_eq!(from_bytes.commitments.len(), 1);
+
+        // the trusted setup must be loadable
+        let err = SrsConfig::from_path("missing/g1.point").load().unwrap_err();
+        assert!(matches!(err, WitnessError::Kzg(_)), "{err}");
+        let truncated = SrsConfig {
+            source: SrsSource::Bytes(Bytes::from(vec![0u8; 33])),
+            ..test_srs()
+        };
+        assert!(truncated.load().is_err());
+    }
+
+    #[test]
+    fn test_witness_round_trip() {
+        let witness = test_witness();
+        let encoded = witness.to_bytes();
+        assert_eq!(encoded[0], WITNESS_ENCODING_VERSION);
+        assert_eq!(EigenDABlobWitness::from_bytes(&encoded), Ok(witness));
+
+        let empty = EigenDABlobWitness::new();
+        assert_eq!(EigenDABlobWitness::from_bytes(&empty.to_bytes()), Ok(empty));
+    }
+
+    #[test]
+    fn test_witness_decoding_errors() {
+        let encoded = test_witness().to_bytes();
+
+        let truncated = &encoded[..encoded.len() - 1];
+        assert_eq!(
+            EigenDABlobWitness::from_bytes(truncated),
+            Err(WitnessDecodingError::UnexpectedEnd)
+        );
+
+        let mut trailing = encoded.clone();
+        trailing.push(0);
+        assert_eq!(
+            EigenDABlobWitness::from_bytes(&trailing),
+            Err(WitnessDecodingError::TrailingBytes(1))
+        );
+
+        let mut versioned = encoded;
+        versioned[0] = 1;
+        assert_eq!(
+            EigenDABlobWitness::from_bytes(&versioned),
+            Err(WitnessDecodingError::UnsupportedVersion(1))
+        );
+        assert_eq!(
+            EigenDABlobWitness::from_bytes(&[]),
+            Err(WitnessDecodingError::UnexpectedEnd)
+        );
+    }
+}